@@ -0,0 +1,70 @@
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    AppData,
+    error::AppError,
+    services::{
+        archive_service::{self, ArchiveCache, TrackedArchive},
+        creative_api::ArchivePage,
+    },
+};
+
+/// 分页获取稿件列表，结果同时写入本地缓存
+#[tauri::command]
+pub async fn get_archive_list(
+    app: AppHandle,
+    uid: u64,
+    pn: u32,
+    ps: u32,
+    status: Option<String>,
+) -> Result<ArchivePage, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    let page = client
+        .creative()
+        .archives(pn.max(1), ps.clamp(1, 50), status.as_deref())
+        .await?;
+
+    ArchiveCache::merge_and_save(uid, &page.archives)
+        .await
+        .map_err(|e| AppError::Custom(format!("保存稿件缓存失败: {e}")))?;
+
+    Ok(page)
+}
+
+/// 同步账号的全部稿件到本地缓存
+#[tauri::command]
+pub async fn sync_archives(app: AppHandle, uid: u64) -> Result<ArchiveCache, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    archive_service::sync_archives(&client).await
+}
+
+/// 读取本地缓存的稿件列表
+#[tauri::command]
+pub async fn get_cached_archives(uid: u64) -> Result<ArchiveCache, AppError> {
+    Ok(ArchiveCache::load(uid))
+}
+
+/// 跟踪稿件的审核状态，状态变化时发送 `archive-state-changed` 事件
+#[tauri::command]
+pub async fn track_archive(app: AppHandle, uid: u64, aid: u64) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    app_data.get_client(uid).await?;
+    app_data.archive_tracker.track(uid, aid).await;
+    Ok(true)
+}
+
+/// 停止跟踪稿件
+#[tauri::command]
+pub async fn untrack_archive(app: AppHandle, aid: u64) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    Ok(app_data.archive_tracker.untrack(aid).await)
+}
+
+/// 获取正在跟踪的稿件
+#[tauri::command]
+pub async fn get_tracked_archives(app: AppHandle) -> Result<Vec<TrackedArchive>, AppError> {
+    let app_data = app.state::<AppData>();
+    Ok(app_data.archive_tracker.list().await)
+}
@@ -1,4 +1,4 @@
-use crate::models::{ConfigRoot, User};
+use crate::models::{ConfigRoot, User, proxy::ProxyRoute};
 use crate::services::{AuthService, QrLoginCheckResult, SmsSendState};
 use crate::{AppData, error::AppError};
 use serde::{Deserialize, Serialize};
@@ -9,7 +9,7 @@ use std::{
 };
 use tauri::Manager;
 use tokio::sync::Mutex;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginResponse {
@@ -26,7 +26,7 @@ async fn persist_login_success(
     user: User,
     proxy: Option<String>,
 ) {
-    {
+    let api_proxy = {
         let mut config_guard = config.lock().await;
         if let Some(existing_config) = config_guard.config.get_mut(&user.uid) {
             existing_config.user.name = user.username.clone();
@@ -40,15 +40,24 @@ async fn persist_login_success(
                 proxy.clone(),
             );
         }
-    }
+        config_guard.proxy_url(user.uid, ProxyRoute::Api)
+    };
 
-    clients.lock().await.insert(
-        user.uid,
-        crate::MyClient {
-            bilibili,
-            user: user.clone(),
-        },
-    );
+    // 已选择接口代理的账号，登录后改用所选代理创建客户端
+    let bilibili = if api_proxy != proxy {
+        biliup::credential::bilibili_from_info(bilibili.login_info.clone(), api_proxy.as_deref())
+            .unwrap_or_else(|e| {
+                warn!("使用所选代理创建用户 {} 的客户端失败: {}", user.uid, e);
+                bilibili
+            })
+    } else {
+        bilibili
+    };
+
+    clients
+        .lock()
+        .await
+        .insert(user.uid, crate::MyClient::new(bilibili, user.clone()));
 }
 
 /// 获取登录二维码
@@ -1,10 +1,20 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
 use tauri::AppHandle;
 use tauri::Manager;
 
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::{AppData, error::AppError, models::ConfigRoot};
-use crate::{models::TemplateConfig, utils::get_config_json_path};
+use crate::{AppData, MyClient, error::AppError, models::ConfigRoot};
+use crate::{
+    models::{
+        TemplateConfig,
+        proxy::{ProxyProfile, ProxyRoute},
+        template_share::{SharedTemplate, TemplateBundle},
+    },
+    services::backup_service::{BackupArchive, BackupImport, BackupSummary, BackupUserStatus},
+    utils::get_config_json_path,
+};
 
 /// 加载配置文件
 #[tauri::command]
@@ -28,6 +38,14 @@ pub async fn save_config(app: AppHandle) -> Result<bool, AppError> {
     Ok(true)
 }
 
+/// 获取启动时加载配置产生的警告（如从备份恢复）
+#[tauri::command]
+pub async fn get_config_warning(app: AppHandle) -> Result<Option<String>, AppError> {
+    let app_data = app.state::<AppData>();
+
+    Ok(app_data.config_warning.clone())
+}
+
 #[tauri::command]
 pub async fn save_user_config(
     app: AppHandle,
@@ -41,15 +59,99 @@ pub async fn save_user_config(
     let app_data = app.state::<AppData>();
     info!("用户({uid})配置已保存");
 
-    app_data
-        .config
-        .lock()
-        .await
-        .save_user_config(uid, line, proxy, limit, watermark, auto_edit)
-        .map_err(|e| AppError::Config(format!("保存用户配置失败: {e}")))?;
+    let previous = {
+        let mut config = app_data.config.lock().await;
+        let previous = api_proxies(&config);
+        config
+            .save_user_config(uid, line, proxy, limit, watermark, auto_edit)
+            .map_err(|e| AppError::Config(format!("保存用户配置失败: {e}")))?;
+        previous
+    };
+    rebuild_proxied_clients(&app_data, previous).await;
     Ok(true)
 }
 
+/// 更新代理配置列表，需要再调用 `save_config` 写入文件
+#[tauri::command]
+pub async fn save_proxy_profiles(
+    app: AppHandle,
+    profiles: Vec<ProxyProfile>,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let previous = {
+        let mut config = app_data.config.lock().await;
+        let previous = api_proxies(&config);
+        config
+            .save_proxy_profiles(profiles)
+            .map_err(|e| AppError::Config(format!("保存代理配置失败: {e}")))?;
+        info!("代理配置已更新: 数量={}", config.proxy_profiles.len());
+        previous
+    };
+    rebuild_proxied_clients(&app_data, previous).await;
+    Ok(true)
+}
+
+/// 选择账号的接口代理与上传代理，需要再调用 `save_config` 写入文件
+#[tauri::command]
+pub async fn save_user_proxy(
+    app: AppHandle,
+    uid: u64,
+    api_proxy: Option<String>,
+    upload_proxy: Option<String>,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let previous = {
+        let mut config = app_data.config.lock().await;
+        let previous = api_proxies(&config);
+        config
+            .save_user_proxy(uid, api_proxy, upload_proxy)
+            .map_err(|e| AppError::Config(format!("保存代理设置失败: {e}")))?;
+        previous
+    };
+    rebuild_proxied_clients(&app_data, previous).await;
+    Ok(true)
+}
+
+/// 各账号接口请求使用的代理地址
+fn api_proxies(config: &ConfigRoot) -> HashMap<u64, Option<String>> {
+    config
+        .config
+        .keys()
+        .map(|uid| (*uid, config.proxy_url(*uid, ProxyRoute::Api)))
+        .collect()
+}
+
+/// 接口代理有变化的账号使用新的代理重新创建客户端，正在上传的分片不受影响
+async fn rebuild_proxied_clients(app_data: &AppData, previous: HashMap<u64, Option<String>>) {
+    let changed: Vec<_> = {
+        let config = app_data.config.lock().await;
+        api_proxies(&config)
+            .into_iter()
+            .filter(|(uid, proxy)| previous.get(uid) != Some(proxy))
+            .filter_map(|(uid, proxy)| {
+                let cookie = config.config.get(&uid)?.user.cookie.clone();
+                Some((uid, cookie, proxy))
+            })
+            .collect()
+    };
+
+    for (uid, cookie, proxy) in changed {
+        let bilibili = match biliup::credential::bilibili_from_info(cookie, proxy.as_deref()) {
+            Ok(bilibili) => bilibili,
+            Err(e) => {
+                warn!("使用新的代理创建用户 {} 的客户端失败: {}", uid, e);
+                continue;
+            }
+        };
+        if let Some(client) = app_data.clients.lock().await.get_mut(&uid) {
+            *client = MyClient::new(bilibili, client.user.clone());
+            info!("用户 {} 的接口代理已变更，重新创建客户端", uid);
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn save_global_config(
     app: AppHandle,
@@ -90,6 +192,26 @@ pub async fn delete_user_template(
     Ok(true)
 }
 
+/// 检查模板的父模板存在且继承链不成环
+fn check_template_parent(
+    config: &ConfigRoot,
+    uid: u64,
+    template_name: &str,
+    template: &TemplateConfig,
+) -> Result<(), AppError> {
+    if template.parent_name().is_none() {
+        return Ok(());
+    }
+
+    config
+        .config
+        .get(&uid)
+        .ok_or(AppError::UserNotFound(uid))?
+        .resolve_template(template_name, template)
+        .map_err(|e| AppError::Config(format!("模板继承设置无效: {e}")))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn update_user_template(
     app: AppHandle,
@@ -99,11 +221,10 @@ pub async fn update_user_template(
 ) -> Result<TemplateConfig, AppError> {
     let app_data = app.state::<AppData>();
 
-    let updated = app_data
-        .config
-        .lock()
-        .await
-        .add_user_template(uid, &template_name, template);
+    let mut config = app_data.config.lock().await;
+    check_template_parent(&config, uid, &template_name, &template)?;
+
+    let updated = config.add_user_template(uid, &template_name, template);
     info!("更新模板: {}", template_name);
 
     Ok(updated)
@@ -118,11 +239,10 @@ pub async fn add_user_template(
 ) -> Result<TemplateConfig, AppError> {
     let app_data = app.state::<AppData>();
 
-    let added = app_data
-        .config
-        .lock()
-        .await
-        .add_user_template(uid, &template_name, template);
+    let mut config = app_data.config.lock().await;
+    check_template_parent(&config, uid, &template_name, &template)?;
+
+    let added = config.add_user_template(uid, &template_name, template);
     info!("添加模板: {}", template_name);
 
     Ok(added)
@@ -171,6 +291,130 @@ pub async fn save_template_order(
     Ok(saved_order)
 }
 
+/// 沿继承链解析模板的最终配置，`template` 为尚未保存的修改
+#[tauri::command]
+pub async fn resolve_user_template(
+    app: AppHandle,
+    uid: u64,
+    template_name: String,
+    template: Option<TemplateConfig>,
+) -> Result<TemplateConfig, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let config = app_data.config.lock().await;
+    let user_config = config.config.get(&uid).ok_or(AppError::UserNotFound(uid))?;
+    let template = match template {
+        Some(template) => template,
+        None => user_config
+            .templates
+            .get(&template_name)
+            .cloned()
+            .ok_or_else(|| AppError::Config(format!("模板不存在: {template_name}")))?,
+    };
+
+    user_config
+        .resolve_template(&template_name, &template)
+        .map_err(|e| AppError::Config(format!("解析模板失败: {e}")))
+}
+
+/// 导出模板到独立的 JSON 文件
+#[tauri::command]
+pub async fn export_templates(
+    app: AppHandle,
+    uid: u64,
+    template_names: Vec<String>,
+    include_cover: bool,
+    path: String,
+) -> Result<usize, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let templates = {
+        let config = app_data.config.lock().await;
+        let user_config = config
+            .config
+            .get(&uid)
+            .ok_or_else(|| AppError::UserNotFound(uid))?;
+
+        template_names
+            .iter()
+            .map(|name| {
+                let template = user_config
+                    .templates
+                    .get(name)
+                    .ok_or_else(|| AppError::Config(format!("模板不存在: {name}")))?;
+                let template = user_config
+                    .resolve_template(name, template)
+                    .map_err(|e| AppError::Config(format!("解析模板 {name} 失败: {e}")))?;
+                Ok(SharedTemplate {
+                    name: name.clone(),
+                    template: template.to_shareable(include_cover),
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?
+    };
+
+    let count = templates.len();
+    TemplateBundle::new(templates)
+        .write_to_file(Path::new(&path))
+        .map_err(|e| AppError::Config(format!("导出模板失败: {e}")))?;
+
+    info!("导出 {} 个模板到: {}", count, path);
+    Ok(count)
+}
+
+/// 从 JSON 文件导入模板，重名时自动重命名，返回导入后的模板名
+#[tauri::command]
+pub async fn import_templates(
+    app: AppHandle,
+    uid: u64,
+    path: String,
+) -> Result<Vec<String>, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let bundle = TemplateBundle::from_file(Path::new(&path))
+        .map_err(|e| AppError::Config(format!("读取模板文件失败: {e}")))?;
+    let templates = bundle
+        .templates
+        .into_iter()
+        .map(|shared| (shared.name, shared.template.to_shareable(true)))
+        .collect();
+
+    let imported = app_data
+        .config
+        .lock()
+        .await
+        .import_user_templates(uid, templates)
+        .map_err(|e| AppError::Config(format!("导入模板失败: {e}")))?;
+
+    info!("用户({uid})导入模板: {:?}", imported);
+    Ok(imported)
+}
+
+/// 将模板复制到另一个账号，返回新模板名
+#[tauri::command]
+pub async fn clone_user_template(
+    app: AppHandle,
+    from_uid: u64,
+    to_uid: u64,
+    template_name: String,
+    new_name: Option<String>,
+) -> Result<String, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let cloned = app_data
+        .config
+        .lock()
+        .await
+        .clone_user_template(from_uid, to_uid, &template_name, new_name.as_deref())
+        .map_err(|e| AppError::Config(format!("复制模板失败: {e}")))?;
+
+    info!(
+        "复制模板: {}({}) -> {}({})",
+        template_name, from_uid, cloned, to_uid
+    );
+    Ok(cloned)
+}
+
 #[tauri::command]
 pub async fn save_user_order(app: AppHandle, user_order: Vec<u64>) -> Result<Vec<u64>, AppError> {
     let app_data = app.state::<AppData>();
@@ -186,3 +430,114 @@ pub async fn save_user_order(app: AppHandle, user_order: Vec<u64>) -> Result<Vec
 
     Ok(user_order)
 }
+
+/// 导出应用数据备份，返回生成的 zip 文件路径
+#[tauri::command]
+pub async fn export_backup(app: AppHandle, include_credentials: bool) -> Result<String, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let upload_queue = app_data
+        .upload_service
+        .get_upload_queue()
+        .await
+        .map_err(AppError::Internal)?;
+    let tracked = app_data.archive_tracker.list().await;
+    let config = app_data.config.lock().await.clone();
+
+    let zip_path = crate::services::backup_service::export_backup(
+        &config,
+        &upload_queue,
+        &tracked,
+        include_credentials,
+    )
+    .map_err(|e| AppError::Config(format!("导出备份失败: {e}")))?;
+
+    Ok(zip_path.to_string_lossy().to_string())
+}
+
+/// 校验备份文件并返回与当前配置的差异，不做修改
+#[tauri::command]
+pub async fn inspect_backup(app: AppHandle, path: String) -> Result<BackupSummary, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let archive = BackupArchive::open(Path::new(&path))
+        .map_err(|e| AppError::Config(format!("读取备份失败: {e}")))?;
+    let mut config = app_data.config.lock().await.clone();
+
+    Ok(archive.merge_into(&mut config).summary)
+}
+
+/// 从备份文件合并用户与模板，并恢复未完成的上传任务
+#[tauri::command]
+pub async fn import_backup(app: AppHandle, path: String) -> Result<BackupSummary, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let archive = BackupArchive::open(Path::new(&path))
+        .map_err(|e| AppError::Config(format!("读取备份失败: {e}")))?;
+
+    let (mut summary, upload_queue, user_data, new_users) = {
+        let mut config = app_data.config.lock().await;
+        let BackupImport {
+            summary,
+            upload_queue,
+            user_data,
+        } = archive.merge_into(&mut config);
+        config
+            .save_to_file(&get_config_json_path().map_err(AppError::Internal)?)
+            .map_err(|e| AppError::Config(format!("保存导入的配置失败: {e}")))?;
+
+        let new_users: Vec<_> = summary
+            .users
+            .iter()
+            .filter(|diff| matches!(diff.status, BackupUserStatus::New))
+            .filter_map(|diff| {
+                let user_config = config.config.get(&diff.uid)?.clone();
+                Some((user_config, config.proxy_url(diff.uid, ProxyRoute::Api)))
+            })
+            .collect();
+        (summary, upload_queue, user_data, new_users)
+    };
+
+    user_data.restore(&app_data.archive_tracker).await;
+
+    for (user_config, proxy) in new_users {
+        match MyClient::from_user_config(&user_config, proxy.as_deref()).await {
+            Ok(client) => {
+                app_data
+                    .clients
+                    .lock()
+                    .await
+                    .insert(client.user.uid, client);
+            }
+            Err(e) => warn!("导入用户 {} 登录失败: {}", user_config.user.uid, e),
+        }
+    }
+
+    let mut restored = 0;
+    for task in upload_queue {
+        // 已登录时使用当前的账号信息
+        let user = match app_data.get_client(task.user.uid).await {
+            Ok(client) => client.user,
+            Err(_) => task.user,
+        };
+        match app_data
+            .upload_service
+            .create_task(
+                &user,
+                &task.template,
+                &task.video,
+                Arc::clone(&app_data.config),
+                Arc::clone(&app_data.clients),
+            )
+            .await
+        {
+            Ok(true) => restored += 1,
+            Ok(false) => {}
+            Err(e) => warn!("恢复上传任务 {} 失败: {}", task.video.title, e),
+        }
+    }
+    summary.upload_queue_restored = restored;
+
+    info!("已从备份导入配置: {}，恢复上传任务 {} 个", path, restored);
+    Ok(summary)
+}
@@ -1,9 +1,15 @@
+pub mod archive;
 pub mod auth;
 pub mod config;
+pub mod notification;
+pub mod season;
 pub mod upload;
 pub mod utils;
 
+pub use archive::*;
 pub use auth::*;
 pub use config::*;
+pub use notification::*;
+pub use season::*;
 pub use upload::*;
 pub use utils::*;
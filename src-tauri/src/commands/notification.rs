@@ -0,0 +1,48 @@
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::{
+    AppData,
+    error::AppError,
+    models::notification::{NotificationConfig, NotificationSink, SinkKind},
+    services::notification_service,
+};
+
+/// 更新通知配置，需要再调用 `save_config` 写入文件
+#[tauri::command]
+pub async fn save_notification_config(
+    app: AppHandle,
+    notifications: NotificationConfig,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+
+    for sink in &notifications.sinks {
+        if let SinkKind::Webhook {
+            body_template: Some(template),
+            ..
+        } = &sink.kind
+        {
+            notification_service::check_body_template(template).map_err(|e| {
+                AppError::Config(format!("{} 的请求体模板不是有效的 JSON: {e}", sink.name))
+            })?;
+        }
+    }
+
+    info!(
+        "通知配置已更新: enabled={}, 渠道数={}",
+        notifications.enabled,
+        notifications.sinks.len()
+    );
+    app_data.config.lock().await.notifications = notifications;
+    Ok(true)
+}
+
+/// 向指定渠道发送一条测试通知，不受总开关与事件过滤影响
+#[tauri::command]
+pub async fn test_notification_sink(
+    app: AppHandle,
+    sink: NotificationSink,
+) -> Result<bool, AppError> {
+    notification_service::send_test(&app, &sink).await?;
+    Ok(true)
+}
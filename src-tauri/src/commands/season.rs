@@ -0,0 +1,197 @@
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::{
+    AppData,
+    error::AppError,
+    services::season_api::{Episode, SeasonDetail, SeasonEdit, SeasonPage, SectionDetail},
+};
+
+/// 分页获取合集列表
+#[tauri::command]
+pub async fn get_season_page(
+    app: AppHandle,
+    uid: u64,
+    pn: u32,
+    ps: u32,
+) -> Result<SeasonPage, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client
+        .creative()
+        .season_page(pn.max(1), ps.clamp(1, 50))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_season_detail(
+    app: AppHandle,
+    uid: u64,
+    season_id: u64,
+) -> Result<SeasonDetail, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().season(season_id).await
+}
+
+/// 创建合集，返回合集 ID
+#[tauri::command]
+pub async fn create_season(app: AppHandle, uid: u64, season: SeasonEdit) -> Result<u64, AppError> {
+    if season.title.trim().is_empty() {
+        return Err(AppError::Custom("合集标题不能为空".to_string()));
+    }
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().create_season(&season).await
+}
+
+#[tauri::command]
+pub async fn edit_season(
+    app: AppHandle,
+    uid: u64,
+    season_id: u64,
+    season: SeasonEdit,
+) -> Result<bool, AppError> {
+    if season.title.trim().is_empty() {
+        return Err(AppError::Custom("合集标题不能为空".to_string()));
+    }
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().edit_season(season_id, &season).await?;
+    info!("合集 {} 已修改", season_id);
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn delete_season(app: AppHandle, uid: u64, season_id: u64) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().delete_season(season_id).await?;
+    info!("合集 {} 已删除", season_id);
+    Ok(true)
+}
+
+/// 上传合集封面，返回封面地址
+#[tauri::command]
+pub async fn upload_season_cover(
+    app: AppHandle,
+    uid: u64,
+    file: String,
+) -> Result<String, AppError> {
+    let cover = std::fs::read(file)?;
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    let url = client.creative().upload_cover(&cover).await?;
+    info!("合集封面上传成功: {}", url);
+    Ok(url)
+}
+
+/// 在合集中创建小节，返回小节 ID
+#[tauri::command]
+pub async fn create_section(
+    app: AppHandle,
+    uid: u64,
+    season_id: u64,
+    title: String,
+) -> Result<u64, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().create_section(season_id, &title).await
+}
+
+#[tauri::command]
+pub async fn rename_section(
+    app: AppHandle,
+    uid: u64,
+    section_id: u64,
+    title: String,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().rename_section(section_id, &title).await?;
+    Ok(true)
+}
+
+/// 调整小节顺序，`section_ids` 需包含合集中的全部小节
+#[tauri::command]
+pub async fn sort_sections(
+    app: AppHandle,
+    uid: u64,
+    season_id: u64,
+    section_ids: Vec<u64>,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client
+        .creative()
+        .sort_sections(season_id, &section_ids)
+        .await?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn delete_section(app: AppHandle, uid: u64, section_id: u64) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().delete_section(section_id).await?;
+    info!("小节 {} 已删除", section_id);
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn get_section_episodes(
+    app: AppHandle,
+    uid: u64,
+    section_id: u64,
+) -> Result<SectionDetail, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().section(section_id).await
+}
+
+#[tauri::command]
+pub async fn add_section_episodes(
+    app: AppHandle,
+    uid: u64,
+    section_id: u64,
+    episodes: Vec<Episode>,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client
+        .creative()
+        .add_episodes(section_id, &episodes)
+        .await?;
+    info!("已向小节 {} 添加 {} 个分集", section_id, episodes.len());
+    Ok(true)
+}
+
+/// 将分集移出小节，`episode_id` 为分集 ID
+#[tauri::command]
+pub async fn remove_section_episode(
+    app: AppHandle,
+    uid: u64,
+    episode_id: u64,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client.creative().remove_episode(episode_id).await?;
+    Ok(true)
+}
+
+/// 调整分集顺序，`episode_ids` 需包含小节中的全部分集
+#[tauri::command]
+pub async fn sort_section_episodes(
+    app: AppHandle,
+    uid: u64,
+    section_id: u64,
+    episode_ids: Vec<u64>,
+) -> Result<bool, AppError> {
+    let app_data = app.state::<AppData>();
+    let client = app_data.get_client(uid).await?;
+    client
+        .creative()
+        .sort_episodes(section_id, &episode_ids)
+        .await?;
+    Ok(true)
+}
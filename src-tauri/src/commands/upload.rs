@@ -1,14 +1,78 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use crate::{
     AppData,
     error::AppError,
-    models::{TemplateConfig, UploadTask, VideoInfo},
+    models::{
+        TemplateConfig, UploadTask, VideoInfo, notification::NotifyEvent, validation::FieldError,
+    },
+    services::{
+        notification_service::Notification,
+        season_service::SeasonJoin,
+        subtitle_service::{self, SubtitleUpload},
+        tag_service,
+    },
+    utils::{media, placeholder::PlaceholderContext},
 };
+use serde::Serialize;
 use serde_json::Value;
 use tauri::{AppHandle, Manager};
 
-use tracing::info;
+use tracing::{info, warn};
+
+/// 模板变量替换预览
+#[derive(Debug, Serialize)]
+pub struct TemplatePreview {
+    pub title: String,
+    pub desc: String,
+    pub dynamic: String,
+    pub tag: String,
+    pub part_titles: Vec<String>,
+    /// 无法解析、已原样保留的变量
+    pub unresolved: Vec<String>,
+    /// 替换后超出长度限制等错误
+    pub errors: Vec<FieldError>,
+}
+
+/// 带有父模板时，沿继承链解析出最终的模板配置
+pub(super) async fn resolve_inheritance(
+    app: &AppHandle,
+    uid: u64,
+    template_name: &str,
+    form: TemplateConfig,
+) -> Result<TemplateConfig, AppError> {
+    if form.parent_name().is_none() {
+        return Ok(form);
+    }
+
+    let app_data = app.state::<AppData>();
+    let config = app_data.config.lock().await;
+    config
+        .config
+        .get(&uid)
+        .ok_or(AppError::UserNotFound(uid))?
+        .resolve_template(template_name, &form)
+        .map_err(|e| AppError::Config(format!("解析模板继承失败: {e}")))
+}
+
+/// 展开模板变量并检查长度限制
+pub(super) fn expand_template(
+    form: &mut TemplateConfig,
+    template_name: &str,
+) -> Result<Vec<String>, AppError> {
+    let ctx = PlaceholderContext::new(
+        template_name,
+        form.placeholder_parts(),
+        form.filename_pattern.as_deref(),
+    )
+    .map_err(|e| AppError::Custom(e.to_string()))?;
+
+    let unresolved = form.expand_placeholders(&ctx);
+    if !unresolved.is_empty() {
+        warn!("模板中存在无法解析的变量: {:?}", unresolved);
+    }
+    Ok(unresolved)
+}
 
 /// 创建上传任务
 #[tauri::command]
@@ -91,11 +155,116 @@ pub async fn retry_upload(app: AppHandle, task_id: String) -> Result<bool, AppEr
         .map_err(AppError::Internal)?)
 }
 
+/// 使用给定文件预览模板变量替换结果
+#[tauri::command]
+pub async fn preview_template(
+    app: AppHandle,
+    uid: u64,
+    template_name: String,
+    template: TemplateConfig,
+    files: Vec<String>,
+) -> Result<TemplatePreview, AppError> {
+    let mut form = resolve_inheritance(&app, uid, &template_name, template).await?;
+
+    if !files.is_empty() {
+        let mut videos = Vec::with_capacity(files.len());
+        for file in &files {
+            let path = Path::new(file);
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            videos.push(VideoInfo {
+                id: file.clone(),
+                cid: 0,
+                title: stem.clone(),
+                filename: String::new(),
+                desc: String::new(),
+                path: file.clone(),
+                finished_at: 0,
+                encoding_status: 0,
+                status_desc: String::new(),
+                local_name: stem,
+                file_mtime: media::file_mtime(path).unwrap_or(0),
+                duration: media::probe_duration(path).await.unwrap_or(0),
+                subtitle_path: String::new(),
+            });
+        }
+        form.videos = videos;
+    }
+
+    let unresolved = expand_template(&mut form, &template_name)?;
+    let errors = form.check_text_limits();
+
+    Ok(TemplatePreview {
+        title: form.title,
+        desc: form.desc,
+        dynamic: form.dynamic,
+        tag: form.tag,
+        part_titles: form.videos.into_iter().map(|video| video.title).collect(),
+        unresolved,
+        errors,
+    })
+}
+
+/// 提交前离线校验模板，返回字段级错误，`check_videos` 为 false 时跳过分P检查
 #[tauri::command]
-pub async fn submit(app: AppHandle, uid: u64, form: TemplateConfig) -> Result<Value, AppError> {
+pub async fn validate_template(
+    app: AppHandle,
+    uid: u64,
+    template_name: String,
+    template: TemplateConfig,
+    check_videos: bool,
+) -> Result<Vec<FieldError>, AppError> {
+    let mut form = resolve_inheritance(&app, uid, &template_name, template).await?;
+    expand_template(&mut form, &template_name)?;
+    let mut errors = form.validate(check_videos);
+    if check_videos && let Err(subtitle_errors) = subtitle_service::prepare(&form) {
+        errors.extend(subtitle_errors);
+    }
+    Ok(errors)
+}
+
+#[tauri::command]
+pub async fn submit(
+    app: AppHandle,
+    uid: u64,
+    form: TemplateConfig,
+    template_name: Option<String>,
+) -> Result<Value, AppError> {
     let app_data = app.state::<AppData>();
+    let template_name = template_name.unwrap_or_default();
+
+    let mut form = resolve_inheritance(&app, uid, &template_name, form).await?;
+    expand_template(&mut form, &template_name)?;
+    let mut errors = form.validate(true);
+    // 各分P的字幕在稿件提交成功后再上传
+    let subtitles = subtitle_service::prepare(&form).unwrap_or_else(|subtitle_errors| {
+        errors.extend(subtitle_errors);
+        Vec::new()
+    });
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
 
+    let title = form.title.clone();
+    let subtitle_lan = subtitle_service::subtitle_lan(&form);
+    let tags: Vec<String> = form.tag_list().into_iter().map(str::to_string).collect();
     if form.aid.is_none() {
+        // 投稿成功后再加入模板配置的合集
+        let season = form.season_id.filter(|id| *id != 0).map(|season_id| {
+            let episode_title = form
+                .season_episode_title
+                .clone()
+                .filter(|episode_title| !episode_title.trim().is_empty())
+                .unwrap_or_else(|| title.clone());
+            (
+                season_id,
+                form.section_id.filter(|id| *id != 0),
+                episode_title,
+            )
+        });
+
         // 将前端表单转换为B站API需要的格式
         let bilibili_form = form.into_bilibili_form();
         let studio = bilibili_form
@@ -110,36 +279,101 @@ pub async fn submit(app: AppHandle, uid: u64, form: TemplateConfig) -> Result<Va
             debug!("转换后的B站提交表单: {uid}\n{}", json_content);
         }
 
-        let proxy = app_data
-            .config
-            .lock()
-            .await
-            .config
-            .get(&uid)
-            .and_then(|c| c.proxy.clone());
-
-        let bilibili = app_data.get_bilibili(uid).await?;
-        match bilibili.submit_by_web(&studio, proxy.as_deref()).await {
-            Ok(resp) => {
-                info!("添加稿件成功：{resp}");
-                Ok(resp
-                    .data
-                    .ok_or_else(|| AppError::Biliup("返回值错误".to_string()))?)
+        let my_client = app_data.get_client(uid).await?;
+        match my_client.creative().submit(&studio).await {
+            Ok(data) => {
+                info!("添加稿件成功：{data}");
+                record_tags(uid, &tags);
+                if let Some(aid) = data["aid"].as_u64() {
+                    app_data.archive_tracker.track(uid, aid).await;
+                    if let Some((season_id, section_id, episode_title)) = season {
+                        SeasonJoin {
+                            uid,
+                            aid,
+                            season_id,
+                            section_id,
+                            title: episode_title,
+                        }
+                        .spawn(app.clone());
+                    }
+                    if !subtitles.is_empty() {
+                        SubtitleUpload {
+                            uid,
+                            aid,
+                            bvid: data["bvid"].as_str().map(str::to_string),
+                            lan: subtitle_lan,
+                            parts: subtitles,
+                        }
+                        .spawn(app.clone());
+                    }
+                }
+                let mut notification = Notification::new(
+                    NotifyEvent::SubmitSucceeded,
+                    format!("{} 投稿成功: {}", my_client.user.username, title),
+                );
+                if let Some(bvid) = data["bvid"].as_str() {
+                    notification = notification.with_url(video_url(bvid));
+                }
+                app_data.notifier.notify(notification);
+                Ok(data)
+            }
+            Err(e) => {
+                app_data.notifier.notify(Notification::new(
+                    NotifyEvent::SubmitFailed,
+                    format!("{} 投稿失败: {}: {}", my_client.user.username, title, e),
+                ));
+                Err(e)
             }
-            Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
         }
     } else {
+        let aid = form.aid;
         let bilibili_form = form.into_bilibili_form();
         let studio = bilibili_form
             .try_into_studio()
             .map_err(AppError::Internal)?;
-        let bilibili = app_data.get_bilibili(uid).await?;
-        match bilibili.edit_by_web(&studio).await {
+        let my_client = app_data.get_client(uid).await?;
+        match my_client.creative().edit(&studio).await {
             Ok(resp) => {
                 info!("编辑稿件成功：{resp}");
+                record_tags(uid, &tags);
+                // 编辑后稿件会重新进入审核
+                if let Some(aid) = aid {
+                    app_data.archive_tracker.track(uid, aid).await;
+                    if !subtitles.is_empty() {
+                        SubtitleUpload {
+                            uid,
+                            aid,
+                            bvid: resp["data"]["bvid"].as_str().map(str::to_string),
+                            lan: subtitle_lan,
+                            parts: subtitles,
+                        }
+                        .spawn(app.clone());
+                    }
+                }
+                app_data.notifier.notify(Notification::new(
+                    NotifyEvent::SubmitSucceeded,
+                    format!("{} 编辑稿件成功: {}", my_client.user.username, title),
+                ));
                 Ok(resp["data"].clone())
             }
-            Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
+            Err(e) => {
+                app_data.notifier.notify(Notification::new(
+                    NotifyEvent::SubmitFailed,
+                    format!("{} 编辑稿件失败: {}: {}", my_client.user.username, title, e),
+                ));
+                Err(e)
+            }
         }
     }
 }
+
+/// 记录投稿使用的标签，用于推荐常用标签
+fn record_tags(uid: u64, tags: &[String]) {
+    if let Err(e) = tag_service::record(uid, tags) {
+        warn!("保存标签记录失败: {}", e);
+    }
+}
+
+fn video_url(bvid: &str) -> String {
+    format!("https://www.bilibili.com/video/{bvid}")
+}
@@ -1,18 +1,35 @@
-use serde_json::{Value, json};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::{LazyLock, Mutex as StdMutex};
 use std::time::{Duration, SystemTime};
 use std::{fs::File, io::Read, path::Path};
 use tauri::Manager;
 
 use tracing::{debug, error, info, warn};
 
-use crate::{AppData, error::AppError, models::TemplateConfig};
-use crate::{models::user_config::Credit, utils::crypto::encode_base64};
+use crate::commands::upload::{expand_template, resolve_inheritance};
+use crate::services::{
+    bili_api::EndpointMetrics,
+    creative_api::{Activity, Topic},
+    mention_service::{self, MENTION_SEARCH_URL, ParsedDesc},
+    metadata_service::{self, CategoryMatch, MetadataStatus},
+    season_api::{Episode, SeasonList, SeasonSwitch},
+    tag_service::{self, TagSuggestion},
+};
+use crate::{
+    AppData,
+    error::AppError,
+    models::{TemplateConfig, VideoInfo, proxy::ProxyRoute},
+};
 use crate::{
-    models::user_config::Staff,
+    utils::crypto::encode_base64,
     utils::{
+        cover::{self, CoverOptions, ProcessedCover},
+        cover_compose,
         file_utils::{self, FileEntry},
-        get_avatar_cache_path,
+        get_avatar_cache_path, media,
+        part_title::{self, PartTitle, PartTitleRule},
     },
 };
 
@@ -59,6 +76,33 @@ fn extract_avatar_filename(face_url: &str, uid: &str) -> String {
     }
 }
 
+/// 单次搜索最多下载的头像数量
+const MAX_AVATAR_JOBS: usize = 30;
+
+/// 默认截取的候选封面数量及上限
+const COVER_CANDIDATE_COUNT: u32 = 6;
+const COVER_CANDIDATE_MAX: u32 = 20;
+/// 候选封面缩略图的宽度
+const COVER_CANDIDATE_WIDTH: u32 = 480;
+
+/// 正在下载的头像文件名，避免多次搜索重复下载同一头像
+static AVATAR_DOWNLOADS: LazyLock<StdMutex<HashSet<String>>> =
+    LazyLock::new(|| StdMutex::new(HashSet::new()));
+
+/// 标记头像开始下载，已有其他任务在下载时返回 false
+fn lock_avatar_download(file_name: &str) -> bool {
+    AVATAR_DOWNLOADS
+        .lock()
+        .map(|mut pending| pending.insert(file_name.to_string()))
+        .unwrap_or(false)
+}
+
+fn unlock_avatar_download(file_name: &str) {
+    if let Ok(mut pending) = AVATAR_DOWNLOADS.lock() {
+        pending.remove(file_name);
+    }
+}
+
 fn normalize_face_url(face_url: &str) -> String {
     if face_url.starts_with("//") {
         format!("https:{face_url}")
@@ -69,24 +113,6 @@ fn normalize_face_url(face_url: &str) -> String {
     }
 }
 
-fn normalize_desc_v2_tokens(tokens: Vec<Credit>) -> Vec<Credit> {
-    let mut normalized: Vec<Credit> = Vec::with_capacity(tokens.len());
-
-    for token in tokens {
-        if let Some(last) = normalized.last_mut()
-            && last.r#type == 1
-            && token.r#type == 1
-        {
-            last.raw_text.push_str(&token.raw_text);
-            continue;
-        }
-
-        normalized.push(token);
-    }
-
-    normalized
-}
-
 #[tauri::command]
 pub async fn get_avatar_cache_dir() -> Result<String, AppError> {
     Ok(get_avatar_cache_path()
@@ -121,19 +147,39 @@ pub async fn read_dir_recursive(
     )
 }
 
+/// 按自然顺序排序待添加的视频并生成分P标题
+#[tauri::command]
+pub async fn generate_part_titles(
+    rule: Option<PartTitleRule>,
+    files: Vec<String>,
+    start_index: usize,
+) -> Result<Vec<PartTitle>, AppError> {
+    part_title::generate_part_titles(rule.as_ref(), files, start_index)
+        .map_err(|e| AppError::Custom(e.to_string()))
+}
+
 /// 上传封面并进行返回url
 #[tauri::command]
 pub async fn upload_cover(
     app: tauri::AppHandle,
     uid: u64,
     file: String,
+    options: Option<CoverOptions>,
 ) -> Result<String, AppError> {
     let app_data = app.state::<AppData>();
 
-    let mut cover_file = File::open(file)?;
-    let mut cover_buf = vec![];
+    let cover = load_cover(file, options.unwrap_or_default()).await?;
+    upload_processed_cover(&app_data, uid, &cover).await
+}
 
-    cover_file.read_to_end(&mut cover_buf)?;
+async fn upload_processed_cover(
+    app_data: &AppData,
+    uid: u64,
+    cover: &ProcessedCover,
+) -> Result<String, AppError> {
+    for warning in &cover.warnings {
+        warn!("封面: {}", warning);
+    }
 
     match app_data
         .clients
@@ -142,15 +188,212 @@ pub async fn upload_cover(
         .get(&uid)
         .ok_or_else(|| AppError::UserNotFound(uid))?
         .bilibili
-        .cover_up(&cover_buf)
+        .cover_up(&cover.data)
         .await
     {
         Ok(url) => {
             info!("封面上传成功: {}", url);
             Ok(url)
         }
-        Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
+        Err(e) => Err(AppError::from_biliup(e)),
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct CoverPreview {
+    /// 处理后的 JPEG，格式为 data URL
+    data_url: String,
+    size: usize,
+    width: u32,
+    height: u32,
+    original_width: u32,
+    original_height: u32,
+    original_format: String,
+    warnings: Vec<String>,
+}
+
+/// 预览封面处理结果，与上传时的处理一致
+#[tauri::command]
+pub async fn preview_cover(
+    file: String,
+    options: Option<CoverOptions>,
+) -> Result<CoverPreview, AppError> {
+    let cover = load_cover(file, options.unwrap_or_default()).await?;
+    Ok(cover.into())
+}
+
+impl From<ProcessedCover> for CoverPreview {
+    fn from(cover: ProcessedCover) -> Self {
+        Self {
+            data_url: format!("data:image/jpeg;base64,{}", encode_base64(&cover.data)),
+            size: cover.data.len(),
+            width: cover.width,
+            height: cover.height,
+            original_width: cover.original_width,
+            original_height: cover.original_height,
+            original_format: cover.original_format,
+            warnings: cover.warnings,
+        }
+    }
+}
+
+/// 读取封面文件并在阻塞线程中处理
+async fn load_cover(file: String, options: CoverOptions) -> Result<ProcessedCover, AppError> {
+    let mut cover_file = File::open(&file)?;
+    let mut cover_buf = vec![];
+    cover_file.read_to_end(&mut cover_buf)?;
+
+    let cover = process_cover_blocking(cover_buf, options).await?;
+    debug!(
+        "封面处理完成: {} {}x{} -> {}x{}, {} 字节",
+        file,
+        cover.original_width,
+        cover.original_height,
+        cover.width,
+        cover.height,
+        cover.data.len()
+    );
+    Ok(cover)
+}
+
+/// 在阻塞线程中处理封面
+async fn process_cover_blocking(
+    data: Vec<u8>,
+    options: CoverOptions,
+) -> Result<ProcessedCover, AppError> {
+    tokio::task::spawn_blocking(move || cover::process_cover(&data, &options))
+        .await
+        .map_err(|e| AppError::Internal(e.into()))?
+        .map_err(|e| AppError::Custom(e.to_string()))
+}
+
+#[derive(serde::Serialize)]
+pub struct CoverCandidate {
+    /// 截取画面的时间点（秒）
+    timestamp: f64,
+    /// JPEG 缩略图，格式为 data URL
+    thumbnail: String,
+}
+
+/// 从视频中均匀截取若干帧作为候选封面
+#[tauri::command]
+pub async fn extract_cover_candidates(
+    video: VideoInfo,
+    count: Option<u32>,
+) -> Result<Vec<CoverCandidate>, AppError> {
+    let path = Path::new(&video.path);
+    if !path.is_file() {
+        return Err(AppError::Custom(format!("视频文件不存在: {}", video.path)));
     }
+
+    let duration = match video.duration {
+        0 => media::probe_duration(path).await.ok_or_else(|| {
+            AppError::Custom("无法获取视频时长，请确认已安装 ffprobe".to_string())
+        })?,
+        duration => duration,
+    };
+    let count = count
+        .unwrap_or(COVER_CANDIDATE_COUNT)
+        .clamp(1, COVER_CANDIDATE_MAX);
+
+    let mut candidates = Vec::new();
+    for timestamp in media::frame_timestamps(duration as f64, count) {
+        let frame = media::extract_frame(path, timestamp, Some(COVER_CANDIDATE_WIDTH))
+            .await
+            .map_err(|e| AppError::Custom(e.to_string()))?;
+        candidates.push(CoverCandidate {
+            timestamp,
+            thumbnail: format!("data:image/jpeg;base64,{}", encode_base64(&frame)),
+        });
+    }
+    info!("已从 {} 截取 {} 张候选封面", video.path, candidates.len());
+    Ok(candidates)
+}
+
+/// 截取视频指定时间点的原始分辨率画面，处理后作为封面上传
+#[tauri::command]
+pub async fn upload_cover_frame(
+    app: tauri::AppHandle,
+    uid: u64,
+    video: VideoInfo,
+    timestamp: f64,
+    options: Option<CoverOptions>,
+) -> Result<String, AppError> {
+    let app_data = app.state::<AppData>();
+
+    let frame = media::extract_frame(Path::new(&video.path), timestamp, None)
+        .await
+        .map_err(|e| AppError::Custom(e.to_string()))?;
+    let cover = process_cover_blocking(frame, options.unwrap_or_default()).await?;
+    debug!("已截取 {} 第 {:.1} 秒的画面作为封面", video.path, timestamp);
+    upload_processed_cover(&app_data, uid, &cover).await
+}
+
+/// 按模板的封面配置合成封面，文字图层中的模板变量与标题一样展开
+async fn compose_template_cover(
+    app: &tauri::AppHandle,
+    uid: u64,
+    template_name: &str,
+    template: TemplateConfig,
+    options: CoverOptions,
+) -> Result<ProcessedCover, AppError> {
+    let mut form = resolve_inheritance(app, uid, template_name, template).await?;
+    expand_template(&mut form, template_name)?;
+    let spec = form
+        .cover_spec
+        .ok_or_else(|| AppError::Config("模板未设置封面生成配置".to_string()))?;
+
+    tokio::task::spawn_blocking(move || {
+        let (image, format, mut warnings) = cover_compose::compose_cover(&spec)?;
+        let mut cover = cover::process_image(image, format, &options)?;
+        warnings.append(&mut cover.warnings);
+        cover.warnings = warnings;
+        anyhow::Ok(cover)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.into()))?
+    .map_err(|e| AppError::Custom(format!("{e:#}")))
+}
+
+/// 预览按模板封面配置合成的封面
+#[tauri::command]
+pub async fn preview_template_cover(
+    app: tauri::AppHandle,
+    uid: u64,
+    template_name: String,
+    template: TemplateConfig,
+    options: Option<CoverOptions>,
+) -> Result<CoverPreview, AppError> {
+    let cover = compose_template_cover(
+        &app,
+        uid,
+        &template_name,
+        template,
+        options.unwrap_or_default(),
+    )
+    .await?;
+    Ok(cover.into())
+}
+
+/// 按模板封面配置合成封面并上传
+#[tauri::command]
+pub async fn upload_template_cover(
+    app: tauri::AppHandle,
+    uid: u64,
+    template_name: String,
+    template: TemplateConfig,
+    options: Option<CoverOptions>,
+) -> Result<String, AppError> {
+    let cover = compose_template_cover(
+        &app,
+        uid,
+        &template_name,
+        template,
+        options.unwrap_or_default(),
+    )
+    .await?;
+    info!("已按模板 {} 的封面配置生成封面", template_name);
+    upload_processed_cover(&app.state::<AppData>(), uid, &cover).await
 }
 
 /// 下载封面并进行base64编码
@@ -162,80 +405,100 @@ pub async fn download_cover(
 ) -> Result<String, AppError> {
     let app_data = app.state::<AppData>();
 
-    let res = app_data
-        .clients
-        .lock()
-        .await
-        .get(&uid)
-        .ok_or_else(|| AppError::UserNotFound(uid))?
-        .bilibili
-        .client
-        .get(&url)
-        .send()
-        .await?;
-    let bytes = res.bytes().await?;
+    let bytes = app_data.get_api(uid).await?.download(&url).await?;
     Ok(encode_base64(&bytes))
 }
 
 #[tauri::command]
 pub async fn get_archive_pre(app: tauri::AppHandle, uid: u64) -> Result<Value, AppError> {
     let app_data = app.state::<AppData>();
-    let bilibili = app_data.get_bilibili(uid).await?;
-
-    let archive_pre_res = bilibili
-        .archive_pre()
-        .await
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("{}", e)))?;
-    let mut archive_pre_data = archive_pre_res["data"].clone();
+    let my_client = app_data.get_client(uid).await?;
+    metadata_service::archive_pre(&my_client, uid).await
+}
 
-    let type2_url = format!(
-        "https://member.bilibili.com/x/vupre/web/archive/human/type2/list?t={}",
-        chrono::Utc::now().timestamp(),
-    );
+#[tauri::command]
+pub async fn get_topic_list(app: tauri::AppHandle, uid: u64) -> Result<Vec<Topic>, AppError> {
+    let app_data = app.state::<AppData>();
+    let my_client = app_data.get_client(uid).await?;
+    metadata_service::topics(&my_client, uid).await
+}
 
-    let type2_res = bilibili.client.get(type2_url).send().await;
-    match type2_res {
-        Ok(response) => match response.json::<Value>().await {
-            Ok(payload) => {
-                let code = payload["code"].as_i64().unwrap_or(-1);
-                if code == 0 {
-                    archive_pre_data["type_list_v2"] = payload["data"]["type_list"].clone();
-                } else {
-                    warn!("获取新版分区列表返回异常 code={} payload={}", code, payload);
-                }
-            }
-            Err(e) => {
-                warn!("解析新版分区列表响应失败: {}", e);
-            }
-        },
-        Err(e) => {
-            warn!("获取新版分区列表失败: {}", e);
-        }
-    }
+#[tauri::command]
+pub async fn get_activity_list(app: tauri::AppHandle, uid: u64) -> Result<Vec<Activity>, AppError> {
+    let app_data = app.state::<AppData>();
+    let my_client = app_data.get_client(uid).await?;
+    metadata_service::activities(&my_client, uid).await
+}
 
-    Ok(archive_pre_data)
+/// 分区、话题等元数据的缓存状态
+#[tauri::command]
+pub async fn get_metadata_status(uid: u64) -> Vec<MetadataStatus> {
+    metadata_service::status(uid)
 }
 
+/// 忽略有效期重新获取全部元数据
 #[tauri::command]
-pub async fn get_topic_list(app: tauri::AppHandle, uid: u64) -> Result<Value, AppError> {
+pub async fn refresh_metadata(
+    app: tauri::AppHandle,
+    uid: u64,
+) -> Result<Vec<MetadataStatus>, AppError> {
     let app_data = app.state::<AppData>();
+    let my_client = app_data.get_client(uid).await?;
+    Ok(metadata_service::refresh(&my_client, uid).await)
+}
 
-    let bilibili = app_data.get_bilibili(uid).await?;
+/// 在本地缓存的分区中按名称搜索
+#[tauri::command]
+pub async fn search_categories(uid: u64, keyword: String) -> Vec<CategoryMatch> {
+    metadata_service::search_categories(uid, &keyword)
+}
 
-    match bilibili
-        .client
-        .get("https://member.bilibili.com/x/vupre/web/topic/type?pn=0&ps=999")
-        .send()
-        .await?
-        .json::<Value>()
+/// 按标题与分区推荐标签，并合并模板与投稿记录中常用的标签
+#[tauri::command]
+pub async fn suggest_tags(
+    app: tauri::AppHandle,
+    uid: u64,
+    title: String,
+    tid: Option<u32>,
+    desc: Option<String>,
+    current: Option<Vec<String>>,
+) -> Result<Vec<TagSuggestion>, AppError> {
+    let app_data = app.state::<AppData>();
+    let my_client = app_data.get_client(uid).await?;
+
+    let recommended = if title.trim().is_empty() {
+        Vec::new()
+    } else {
+        my_client
+            .creative()
+            .recommend_tags(
+                title.trim(),
+                tid.unwrap_or_default(),
+                desc.as_deref().unwrap_or_default(),
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("获取推荐标签失败: {}", e);
+                Vec::new()
+            })
+    };
+    let template_counts = app_data
+        .config
+        .lock()
         .await
-    {
-        Ok(res) => {
-            // debug!("获取话题列表成功: {}", res);
-            Ok(res["data"]["topics"].clone())
-        }
-        Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
-    }
+        .config
+        .get(&uid)
+        .map(|config| tag_service::template_tag_counts(config.templates.values()))
+        .unwrap_or_default();
+
+    let suggestions = tag_service::suggest(
+        uid,
+        recommended,
+        template_counts,
+        &current.unwrap_or_default(),
+    );
+    debug!("推荐标签: {} 个", suggestions.len());
+    Ok(suggestions)
 }
 
 #[tauri::command]
@@ -243,31 +506,12 @@ pub async fn search_topics(
     app: tauri::AppHandle,
     uid: u64,
     query: String,
-) -> Result<Value, AppError> {
+) -> Result<Vec<Topic>, AppError> {
     let app_data = app.state::<AppData>();
-
-    let bilibili = app_data.get_bilibili(uid).await?;
-
-    match bilibili
-        .client
-        .get("https://member.bilibili.com/x/vupre/web/topic/search")
-        .query(&[
-            ("keywords", &query),
-            ("page_size", &"50".to_string()),
-            ("offset", &"0".to_string()),
-            ("t", &chrono::Utc::now().timestamp().to_string()),
-        ])
-        .send()
-        .await?
-        .json::<Value>()
-        .await
-    {
-        Ok(res) => {
-            debug!("搜索话题成功: {}", res);
-            Ok(res["data"]["result"]["topics"].clone())
-        }
-        Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
-    }
+    let my_client = app_data.get_client(uid).await?;
+    let topics = my_client.creative().search_topics(&query).await?;
+    debug!("搜索话题成功: {} 个结果", topics.len());
+    Ok(topics)
 }
 
 #[tauri::command]
@@ -277,29 +521,17 @@ pub async fn search_mention(
     keyword: Option<String>,
 ) -> Result<Vec<MentionUserGroup>, AppError> {
     let app_data = app.state::<AppData>();
-    let client = app_data.get_bilibili(uid).await?.client.clone();
-
-    let mut request = client
-        .get("https://api.bilibili.com/x/polymer/web-dynamic/v1/mention/search")
-        .query(&[("uid", uid.to_string())]);
+    let api = app_data.get_api(uid).await?;
 
+    let mut query = vec![("uid", uid.to_string())];
     if let Some(keyword) = keyword
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
     {
-        request = request.query(&[("keyword", keyword)]);
+        query.push(("keyword", keyword));
     }
 
-    let res = request.send().await?.json::<Value>().await?;
-
-    if res["code"].as_i64().unwrap_or(-1) != 0 {
-        return Err(AppError::Biliup(
-            res["message"]
-                .as_str()
-                .unwrap_or("搜索用户失败")
-                .to_string(),
-        ));
-    }
+    let res = api.get_query(MENTION_SEARCH_URL, &query).await?;
 
     let groups = res["data"]["groups"]
         .as_array()
@@ -307,6 +539,7 @@ pub async fn search_mention(
         .unwrap_or_default();
 
     let mut avatar_jobs: Vec<(String, String)> = Vec::new();
+    let mut known_users: Vec<(String, String)> = Vec::new();
 
     let parsed_groups = groups
         .iter()
@@ -318,17 +551,19 @@ pub async fn search_mention(
                 .iter()
                 .map(|item| {
                     let uid = item["uid"].as_str().unwrap_or("0").to_string();
+                    let name = item["name"].as_str().unwrap_or("").to_string();
+                    known_users.push((name.clone(), uid.clone()));
                     let face_url = normalize_face_url(item["face"].as_str().unwrap_or(""));
                     let face = extract_avatar_filename(&face_url, &uid);
 
-                    if !face_url.is_empty() {
+                    if !face_url.is_empty() && avatar_jobs.len() < MAX_AVATAR_JOBS {
                         avatar_jobs.push((face_url, face.clone()));
                     }
 
                     MentionUserItem {
                         face,
                         fans: item["fans"].as_u64().unwrap_or(0),
-                        name: item["name"].as_str().unwrap_or("").to_string(),
+                        name,
                         official_verify_type: item["official_verify_type"].as_i64().unwrap_or(-1),
                         uid,
                     }
@@ -342,8 +577,8 @@ pub async fn search_mention(
             }
         })
         .collect::<Vec<_>>();
+    mention_service::remember(uid, &known_users);
 
-    let download_client = client.clone();
     tokio::spawn(async move {
         debug!("开始后台头像下载任务");
         let cache_dir = match get_avatar_cache_path() {
@@ -369,26 +604,25 @@ pub async fn search_mention(
                 Err(_) => false,
             };
 
-            if is_fresh_cache {
+            // 其他搜索已在下载同一头像时跳过，避免连续输入时重复下载
+            if is_fresh_cache || !lock_avatar_download(&file_name) {
                 continue;
             }
 
-            match download_client.get(&face_url).send().await {
-                Ok(response) => match response.bytes().await {
-                    Ok(bytes) => {
-                        if let Err(e) = tokio::fs::write(&save_path, bytes).await {
-                            warn!(
-                                "写入头像缓存失败: {} -> {} ({})",
-                                face_url,
-                                save_path.display(),
-                                e
-                            );
-                        }
+            match api.download(&face_url).await {
+                Ok(bytes) => {
+                    if let Err(e) = tokio::fs::write(&save_path, bytes).await {
+                        warn!(
+                            "写入头像缓存失败: {} -> {} ({})",
+                            face_url,
+                            save_path.display(),
+                            e
+                        );
                     }
-                    Err(e) => warn!("读取头像响应失败: {} ({})", face_url, e),
-                },
+                }
                 Err(e) => warn!("下载头像失败: {} ({})", face_url, e),
             }
+            unlock_avatar_download(&file_name);
         }
         debug!("后台头像下载任务完成");
     });
@@ -396,65 +630,23 @@ pub async fn search_mention(
     Ok(parsed_groups)
 }
 
+/// 将含有 `@用户名` 的纯文本简介解析为 desc_v2
 #[tauri::command]
-pub async fn get_season_list(app: tauri::AppHandle, uid: u64) -> Result<Value, AppError> {
+pub async fn parse_desc_mentions(
+    app: tauri::AppHandle,
+    uid: u64,
+    desc: String,
+) -> Result<ParsedDesc, AppError> {
     let app_data = app.state::<AppData>();
+    let api = app_data.get_api(uid).await?;
+    mention_service::parse_desc(&api, uid, &desc).await
+}
 
-    let bilibili = app_data.get_bilibili(uid).await?;
-
-    match bilibili
-            .client
-            .get(format!("https://member.bilibili.com/x2/creative/web/seasons?pn=1&ps=50&order=desc&sort=mtime&filter=1&t={}", chrono::Utc::now().timestamp()))
-            .send()
-            .await?
-            .json::<Value>()
-            .await
-        {
-            Ok(res) => {
-                // debug!("获取合集列表成功: {}", res);
-                let mut season_vec = Vec::new();
-
-                let seasons = res["data"]["seasons"].as_array()
-                    .unwrap_or(&season_vec).to_owned();
-                for season in &seasons {
-                    let season_id = season["season"]["id"].as_u64().unwrap_or(0);
-                    let season_title = season["season"]["title"].as_str().unwrap_or("").to_string();
-                    let mut sections_vec = Vec::new();
-
-                    if let Some(sections) = season["sections"]["sections"].as_array() {
-                        for section in sections {
-                            let section_id = section["id"].as_u64().unwrap_or(0);
-                            let section_title = section["title"]
-                                .as_str()
-                                .unwrap_or(&season_title)
-                                .to_string();
-
-                            sections_vec.push(serde_json::json!({
-                                "section_id": if section_id != 0 { Some(section_id) } else { None },
-                                "title": section_title,
-                            }));
-                        }
-                    }
-
-                    let default_section_id = sections_vec
-                        .first()
-                        .and_then(|item| item["section_id"].as_u64())
-                        .unwrap_or(0);
-
-                    season_vec.push(serde_json::json!({
-                        "season_id": if season_id != 0 { Some(season_id) } else { None },
-                        "section_id": if default_section_id != 0 { Some(default_section_id) } else { None },
-                        "title": season_title,
-                        "sections": sections_vec,
-                    }));
-                }
-
-                Ok(serde_json::json!({
-                    "seasons": season_vec,
-                }))
-            },
-            Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
-        }
+#[tauri::command]
+pub async fn get_season_list(app: tauri::AppHandle, uid: u64) -> Result<SeasonList, AppError> {
+    let app_data = app.state::<AppData>();
+    let my_client = app_data.get_client(uid).await?;
+    my_client.creative().seasons().await
 }
 
 #[tauri::command]
@@ -468,134 +660,24 @@ pub async fn get_video_detail(
 
     let app_data = app.state::<AppData>();
 
-    let (bilibili, proxy) = {
-        let proxy = app_data
-            .config
-            .lock()
-            .await
-            .config
-            .get(&uid)
-            .and_then(|c| c.proxy.clone());
-        let bilibili = app_data.get_bilibili(uid).await?;
-        (bilibili, proxy)
+    let (my_client, proxy) = {
+        let proxy = app_data.config.lock().await.proxy_url(uid, ProxyRoute::Api);
+        let my_client = app_data.get_client(uid).await?;
+        (my_client, proxy)
     };
 
     // 第1步：通过创作者 API 获取基础 TemplateConfig
-    let res = bilibili
+    let res = my_client
+        .bilibili
         .video_data(&vid, proxy.as_deref())
         .await
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("{}", e)))?;
+        .map_err(AppError::from_biliup)?;
     let mut template_config = TemplateConfig::from_bilibili_res(res)
         .map_err(|e| AppError::Internal(anyhow::anyhow!("{}", e)))?;
 
-    match bilibili
-        .client
-        .get(format!(
-            "https://member.bilibili.com/x/vupre/web/archive/view?topic_grey=1&{vid}&t={}",
-            chrono::Utc::now().timestamp() * 1000
-        ))
-        .send()
-        .await
-    {
-        Ok(response) => match response.json::<Value>().await {
-            Ok(res) => {
-                // debug!("获取稿件 web 接口数据成功: {}", res);
-                if let Some(data) = res.get("data") {
-                    let archive_data = data.get("archive").unwrap_or(data);
-
-                    if let Some(desc) = archive_data["desc"].as_str().filter(|s| !s.is_empty()) {
-                        template_config.desc = desc.to_string();
-                    }
-                    if let Some(human_type2) = archive_data.get("human_type2") {
-                        if let Some(id) = human_type2.get("id").and_then(|v| v.as_u64()) {
-                            template_config.tid_v2 = id as u32;
-                        }
-                    }
-                    template_config.state = archive_data["state"].as_i64();
-                    template_config.state_desc = archive_data["state_desc"]
-                        .as_str()
-                        .map(|value| value.to_string())
-                        .or_else(|| data["state_desc"].as_str().map(|value| value.to_string()));
-                    if !archive_data["desc_v2"].is_null() {
-                        match serde_json::from_value::<Vec<Credit>>(archive_data["desc_v2"].clone())
-                        {
-                            Ok(credits) => {
-                                let cleaned_credits = normalize_desc_v2_tokens(credits);
-                                if !cleaned_credits.is_empty() {
-                                    template_config.desc_v2 = Some(cleaned_credits);
-                                }
-                            }
-                            Err(e) => {
-                                warn!("解析 desc_v2 失败: {}", e);
-                            }
-                        }
-                    }
-                    if let Some(dynamic) =
-                        archive_data["dynamic"].as_str().filter(|s| !s.is_empty())
-                    {
-                        template_config.dynamic = dynamic.to_string();
-                    }
-                    // mission_id：活动 ID
-                    if let Some(mission_id) = archive_data["mission_id"].as_u64() {
-                        template_config.mission_id = Some(mission_id as u32);
-                    }
-                    if let Some(topic_id) = archive_data["topic_id"].as_u64() {
-                        template_config.topic_id = Some(topic_id as u32);
-                    }
-                    if let Some(topic_name) = archive_data["topic_name"]
-                        .as_str()
-                        .or_else(|| data["topic_name"].as_str())
-                        .filter(|s| !s.is_empty())
-                    {
-                        template_config.topic_name = Some(topic_name.to_string());
-                    }
-                    if let Some(rights) = archive_data.get("rights").or_else(|| data.get("rights"))
-                    {
-                        template_config.is_360 =
-                            rights.get("is_360").and_then(|v| v.as_i64()).unwrap_or(-1);
-                    }
-                    if let Some(staff) = data
-                        .get("staffs")
-                        .or_else(|| archive_data.get("staffs"))
-                        .or_else(|| data.get("staff"))
-                    {
-                        if let Some(arr) = staff.as_array() {
-                            let staff_vec: Vec<Staff> = arr
-                                .iter()
-                                .filter_map(|item| {
-                                    let title = item
-                                        .get("apply_title")
-                                        .and_then(|v| v.as_str())
-                                        .or_else(|| item.get("title").and_then(|v| v.as_str()))
-                                        .unwrap_or("")
-                                        .to_string();
-                                    let mid = item
-                                        .get("apply_staff_mid")
-                                        .and_then(|v| v.as_u64())
-                                        .or_else(|| item.get("mid").and_then(|v| v.as_u64()))
-                                        .unwrap_or(0);
-                                    if title.is_empty() || mid == 0 {
-                                        None
-                                    } else {
-                                        Some(Staff {
-                                            title,
-                                            mid,
-                                            is_del: 0,
-                                        })
-                                    }
-                                })
-                                .collect();
-                            if !staff_vec.is_empty() {
-                                template_config.staff = Some(staff_vec);
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                error!("解析 web 接口响应失败: {:?}", e);
-            }
-        },
+    // 第2步：通过 web 接口补充简介 @、话题、联合投稿等信息
+    match my_client.creative().archive_view(&vid).await {
+        Ok(view) => view.apply_to(&mut template_config),
         Err(e) => {
             error!("请求 web 接口失败: {:?}", e);
         }
@@ -607,27 +689,8 @@ pub async fn get_video_detail(
 #[tauri::command]
 pub async fn get_video_season(app: tauri::AppHandle, uid: u64, aid: u64) -> Result<u64, AppError> {
     let app_data = app.state::<AppData>();
-
-    let bilibili = app_data.get_bilibili(uid).await?;
-
-    match bilibili
-        .client
-        .get(format!(
-            "https://member.bilibili.com/x2/creative/web/season/aid?id={}&t={}",
-            aid,
-            chrono::Utc::now().timestamp()
-        ))
-        .send()
-        .await?
-        .json::<Value>()
-        .await
-    {
-        Ok(res) => {
-            // debug!("获取稿件合集信息成功: {}", res);
-            Ok(res["data"]["id"].as_u64().unwrap_or(0))
-        }
-        Err(e) => Err(AppError::Internal(anyhow::anyhow!("{}", e))),
-    }
+    let my_client = app_data.get_client(uid).await?;
+    my_client.creative().video_season(aid).await
 }
 
 #[tauri::command]
@@ -643,70 +706,36 @@ pub async fn switch_season(
 ) -> Result<bool, AppError> {
     let app_data = app.state::<AppData>();
     let my_client = app_data.get_client(uid).await?;
-    let csrf = my_client.get_csrf()?;
+    let creative = my_client.creative();
 
     if add {
-        let res = my_client
-            .bilibili
-            .client
-            .post(format!(
-                "https://member.bilibili.com/x2/creative/web/season/section/episodes/add?t={}&csrf={}",
-                chrono::Utc::now().timestamp(),
-                csrf
-            ))
-            .json(&json!({
-                "episodes": [
-                    {
-                        "title": title,
-                        "aid": aid,
-                        "cid": cid
-                    }
-                ],
-                "sectionId": section_id,
-                "csrf": csrf
-            }))
-            .send()
-            .await?
-            .json::<Value>()
+        let episode = Episode { title, aid, cid };
+        let res = creative
+            .add_episodes(section_id, std::slice::from_ref(&episode))
             .await?;
-
         debug!("设置合集成功：{res}");
-        if res["code"].as_i64() != Some(0) {
-            return Err(AppError::Biliup(
-                serde_json::to_string(&res).unwrap_or_else(|_| "未知错误".to_string()),
-            ));
-        }
-        Ok(true)
     } else {
-        let res = my_client
-            .bilibili
-            .client
-            .post(format!(
-                "https://member.bilibili.com/x2/creative/web/season/switch?t={}&csrf={}",
-                chrono::Utc::now().timestamp(),
-                csrf
-            ))
-            .json(&json!({
-                "season_id": if season_id != 0 { Some(season_id) } else { None },
-                "section_id": if section_id != 0 { Some(section_id) } else { None },
-                "title": title,
-                "aid": aid,
-                "cid": cid,
-                "csrf": csrf
-            }))
-            .send()
-            .await?
-            .json::<Value>()
-            .await?;
-
+        let switch = SeasonSwitch {
+            season_id: Some(season_id).filter(|id| *id != 0),
+            section_id: Some(section_id).filter(|id| *id != 0),
+            title,
+            aid,
+            cid,
+        };
+        let res = creative.switch_season(&switch).await?;
         debug!("修改合集成功：{res}");
-        if res["code"].as_i64() != Some(0) {
-            return Err(AppError::Biliup(
-                serde_json::to_string(&res).unwrap_or_else(|_| "未知错误".to_string()),
-            ));
-        }
-        Ok(true)
     }
+    Ok(true)
+}
+
+/// 获取账号各接口的请求统计
+#[tauri::command]
+pub async fn get_api_metrics(
+    app: tauri::AppHandle,
+    uid: u64,
+) -> Result<HashMap<String, EndpointMetrics>, AppError> {
+    let app_data = app.state::<AppData>();
+    Ok(app_data.get_api(uid).await?.metrics().await)
 }
 
 /// 导出日志
@@ -1,4 +1,144 @@
-use serde::{Serialize, Serializer};
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use serde_json::Value;
+
+use crate::models::validation::{FieldError, join_field_errors};
+
+/// 错误分类，前端据此决定提示方式（重新登录、稍后重试、打开验证等）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// 登录失效，需要重新登录
+    AuthExpired,
+    /// 请求过于频繁或被风控拦截，稍后重试
+    RateLimited,
+    /// 需要完成验证码或安全验证
+    CaptchaRequired,
+    /// 提交内容不符合要求
+    Validation,
+    /// 账号没有相应权限
+    Permission,
+    /// 请求的资源不存在
+    NotFound,
+    /// 网络连接失败或超时
+    Network,
+    /// B站服务端错误
+    Server,
+    /// 未收录的B站错误码，无法判断是否可以重试
+    Unknown,
+    /// 本地配置错误
+    Config,
+    /// 程序内部错误
+    Internal,
+}
+
+/// B站接口返回的错误
+#[derive(Debug, Clone)]
+pub struct BiliApiError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// 已知的B站错误码，对应的分类与提示
+const BILI_ERROR_CODES: &[(i64, ErrorCategory, &str)] = &[
+    (
+        -101,
+        ErrorCategory::AuthExpired,
+        "登录已失效，请重新登录该账号",
+    ),
+    (
+        -111,
+        ErrorCategory::AuthExpired,
+        "登录凭证校验失败，请重新登录该账号",
+    ),
+    (
+        -352,
+        ErrorCategory::CaptchaRequired,
+        "触发风控验证，请在浏览器中登录B站完成验证后重试",
+    ),
+    (
+        -400,
+        ErrorCategory::Validation,
+        "请求参数错误，请检查投稿信息",
+    ),
+    (-403, ErrorCategory::Permission, "账号没有权限执行该操作"),
+    (-404, ErrorCategory::NotFound, "请求的稿件或资源不存在"),
+    (
+        -412,
+        ErrorCategory::RateLimited,
+        "请求被B站拦截，请稍后再试",
+    ),
+    (-509, ErrorCategory::RateLimited, "请求过于频繁，请稍后再试"),
+    (-799, ErrorCategory::RateLimited, "请求过于频繁，请稍后再试"),
+    (-500, ErrorCategory::Server, "B站服务器错误，请稍后再试"),
+    (-503, ErrorCategory::Server, "B站服务暂不可用，请稍后再试"),
+    (-504, ErrorCategory::Network, "B站服务响应超时，请稍后再试"),
+    (
+        21070,
+        ErrorCategory::RateLimited,
+        "投稿频率过快，请稍等一段时间后再提交",
+    ),
+    (
+        21566,
+        ErrorCategory::RateLimited,
+        "投稿过于频繁，请稍后再提交",
+    ),
+];
+
+impl BiliApiError {
+    /// 从接口响应中提取错误，`code` 为 0 时返回 None
+    pub fn from_response(res: &Value) -> Option<Self> {
+        let code = res["code"].as_i64().unwrap_or(-1);
+        if code == 0 {
+            return None;
+        }
+        Some(Self {
+            code,
+            message: res["message"]
+                .as_str()
+                .or_else(|| res["msg"].as_str())
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    /// 从 biliup 返回的错误文本中提取B站响应，无法识别时返回 None
+    pub fn from_error_text(text: &str) -> Option<Self> {
+        let start = text.find('{')?;
+        let end = text.rfind('}')?;
+        let res: Value = serde_json::from_str(text.get(start..=end)?).ok()?;
+        res.get("code")?;
+        Self::from_response(&res)
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        BILI_ERROR_CODES
+            .iter()
+            .find(|(code, _, _)| *code == self.code)
+            .map(|(_, category, _)| *category)
+            .unwrap_or(ErrorCategory::Unknown)
+    }
+
+    /// 面向用户的提示，已知错误码使用对照表中的提示并附带B站原始信息
+    pub fn hint(&self) -> String {
+        let known = BILI_ERROR_CODES
+            .iter()
+            .find(|(code, _, _)| *code == self.code)
+            .map(|(_, _, hint)| *hint);
+
+        match (known, self.message.is_empty()) {
+            (Some(hint), true) => hint.to_string(),
+            (Some(hint), false) => format!("{hint}（{}）", self.message),
+            (None, true) => format!("B站返回错误 {}", self.code),
+            (None, false) => format!("{}（{}）", self.message, self.code),
+        }
+    }
+}
+
+impl std::fmt::Display for BiliApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.hint())
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -20,18 +160,97 @@ pub enum AppError {
     #[error("Bilibili API 错误: {0}")]
     Biliup(String),
 
+    #[error("{0}")]
+    Api(BiliApiError),
+
     #[error("内部错误: {0}")]
     Internal(anyhow::Error),
 
+    #[error("投稿信息校验失败: {}", join_field_errors(.0))]
+    Validation(Vec<FieldError>),
+
+    /// 稿件刚投稿时分P、cid 等尚未生成，稍后重试即可
+    #[error("{0}")]
+    NotReady(String),
+
     #[error("{0}")]
     Custom(String),
 }
 
+impl AppError {
+    /// 检查B站接口响应，`code` 不为 0 时返回对应的错误
+    pub fn check_response(res: &Value) -> Result<(), Self> {
+        match BiliApiError::from_response(res) {
+            Some(error) => Err(Self::Api(error)),
+            None => Ok(()),
+        }
+    }
+
+    /// 转换 biliup 返回的错误，能识别出B站响应时保留错误码
+    pub fn from_biliup(error: impl std::fmt::Display) -> Self {
+        let text = error.to_string();
+        match BiliApiError::from_error_text(&text) {
+            Some(error) => Self::Api(error),
+            None => Self::Biliup(text),
+        }
+    }
+
+    /// 机器可读的错误代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UserNotFound(_) => "user_not_found",
+            Self::Config(_) => "config",
+            Self::Network(_) => "network",
+            Self::Io(_) => "io",
+            Self::Zip(_) => "zip",
+            Self::Biliup(_) => "biliup",
+            Self::Api(_) => "bili_api",
+            Self::Internal(_) => "internal",
+            Self::Validation(_) => "validation",
+            Self::NotReady(_) => "not_ready",
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::UserNotFound(_) => ErrorCategory::AuthExpired,
+            Self::Config(_) => ErrorCategory::Config,
+            Self::Network(_) => ErrorCategory::Network,
+            Self::Api(error) => error.category(),
+            Self::Validation(_) => ErrorCategory::Validation,
+            Self::NotReady(_) => ErrorCategory::NotFound,
+            Self::Io(_) | Self::Zip(_) | Self::Biliup(_) | Self::Internal(_) | Self::Custom(_) => {
+                ErrorCategory::Internal
+            }
+        }
+    }
+}
+
+/// 序列化为对象：`{ code, category, message, bili_code?, bili_message?, fields? }`
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut state = serializer.serialize_struct("AppError", 6)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("category", &self.category())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            Self::Api(error) => {
+                state.serialize_field("bili_code", &error.code)?;
+                state.serialize_field("bili_message", &error.message)?;
+            }
+            _ => {
+                state.skip_field("bili_code")?;
+                state.skip_field("bili_message")?;
+            }
+        }
+        match self {
+            Self::Validation(errors) => state.serialize_field("fields", errors)?,
+            _ => state.skip_field("fields")?,
+        }
+        state.end()
     }
 }
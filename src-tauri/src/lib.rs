@@ -4,6 +4,9 @@ mod models;
 mod services;
 mod utils;
 
+#[cfg(feature = "mock-server")]
+pub use services::mock_server;
+
 use anyhow::Result;
 use std::{collections::HashMap, sync::Arc};
 
@@ -17,18 +20,38 @@ use utils::CompatibilityConverter;
 
 use crate::{
     error::AppError,
-    models::{ConfigRoot, User},
-    services::{AuthService, upload_service::UploadService},
-    utils::{crypto::encode_base64, get_config_json_path, get_log_path},
+    models::{ConfigRoot, User, UserConfig, proxy::ProxyRoute},
+    services::{
+        AuthService,
+        archive_service::{ArchivePoller, ArchiveTracker},
+        bili_api::BiliApi,
+        config_watcher::ConfigWatcher,
+        notification_service::{NotificationService, Notifier},
+        upload_service::UploadService,
+    },
+    utils::{
+        config_migration::SchemaTooNew, crypto::encode_base64, get_config_json_path, get_log_path,
+    },
 };
 
 #[derive(Clone)]
 pub struct MyClient {
     pub bilibili: BiliBili,
     pub user: User,
+    /// 带限速与重试的接口客户端，与 `bilibili` 共用同一个连接
+    pub api: BiliApi,
 }
 
 impl MyClient {
+    pub fn new(bilibili: BiliBili, user: User) -> Self {
+        let api = BiliApi::new(bilibili.client.clone());
+        Self {
+            bilibili,
+            user,
+            api,
+        }
+    }
+
     pub fn get_csrf(&self) -> Result<String, AppError> {
         let csrf = self
             .bilibili
@@ -46,6 +69,48 @@ impl MyClient {
             .ok_or_else(|| AppError::Custom("CSRF错误".to_string()))?;
         Ok(csrf.to_string())
     }
+
+    /// 使用已保存的登录信息创建客户端，登录状态失效时标记为过期用户
+    ///
+    /// `proxy` 为接口请求使用的代理地址，由 `ConfigRoot::proxy_url` 解析。
+    pub async fn from_user_config(user_config: &UserConfig, proxy: Option<&str>) -> Result<Self> {
+        let bilibili =
+            biliup::credential::bilibili_from_info(user_config.user.cookie.clone(), proxy)?;
+
+        let fallback_uid = user_config.user.uid;
+        let fallback_name = user_config.user.name.clone();
+
+        let myinfo = bilibili.my_info().await?;
+        let code = myinfo["code"].as_i64().unwrap_or(-1);
+        let uid = myinfo["data"]["mid"].as_u64().unwrap_or(0);
+
+        if code == 0 && uid > 0 {
+            let username = myinfo["data"]["name"].as_str().unwrap_or("").to_owned();
+            let avatar_url = myinfo["data"]["face"].as_str().unwrap_or("").to_string();
+            let api = BiliApi::new(bilibili.client.clone());
+            let avatar = api
+                .download(&avatar_url)
+                .await
+                .map_err(|e| anyhow::anyhow!("获取用户头像失败: {}", e))?;
+            let avatar = encode_base64(&avatar);
+
+            Ok(Self {
+                bilibili,
+                user: User::new(uid, username, avatar, false),
+                api,
+            })
+        } else {
+            let msg = myinfo["message"].as_str().unwrap_or("未知原因");
+            info!(
+                "用户 {} 的登录状态无效 ({}: {}), 跳过自动登录",
+                fallback_name, code, msg
+            );
+            Ok(Self::new(
+                bilibili,
+                User::new(fallback_uid, fallback_name, "".to_string(), true),
+            ))
+        }
+    }
 }
 
 pub struct AppData {
@@ -53,6 +118,9 @@ pub struct AppData {
     pub auth_service: Arc<Mutex<AuthService>>,
     pub upload_service: UploadService,
     pub clients: Arc<Mutex<HashMap<u64, MyClient>>>,
+    pub archive_tracker: ArchiveTracker,
+    pub notifier: Notifier,
+    pub config_warning: Option<String>,
 }
 
 impl AppData {
@@ -73,68 +141,74 @@ impl AppData {
             .map(|c| c.bilibili.clone())
             .ok_or_else(|| AppError::UserNotFound(uid))
     }
+
+    pub async fn get_api(&self, uid: u64) -> Result<BiliApi, AppError> {
+        self.clients
+            .lock()
+            .await
+            .get(&uid)
+            .map(|c| c.api.clone())
+            .ok_or_else(|| AppError::UserNotFound(uid))
+    }
 }
 
-async fn startup() -> Result<AppData> {
-    let config = ConfigRoot::from_file(&get_config_json_path()?)?;
-    let mut clients = HashMap::new();
+/// 加载配置文件，失败时使用默认配置，并返回需要提示给用户的警告
+fn load_config_or_default() -> (ConfigRoot, Option<String>) {
+    let loaded = get_config_json_path().and_then(|path| {
+        if path.exists() {
+            ConfigRoot::load_with_recovery(&path)
+        } else {
+            Ok((ConfigRoot::default(), None))
+        }
+    });
 
-    for user_config in config.config.values() {
-        let bilibili = biliup::credential::bilibili_from_info(
-            user_config.user.cookie.clone(),
-            user_config.proxy.as_deref(),
-        )?;
+    loaded.unwrap_or_else(|e| {
+        error!("加载配置失败: {}", e);
+        // 高版本写入的配置保持原样，保存时会被拒绝，当前会话相当于只读
+        let warning = if e.downcast_ref::<SchemaTooNew>().is_some() {
+            format!("{e}。当前使用默认配置运行，修改不会写入配置文件")
+        } else {
+            format!("加载配置失败，已使用默认配置: {e}")
+        };
+        (ConfigRoot::default(), Some(warning))
+    })
+}
 
-        let fallback_uid = user_config.user.uid;
-        let fallback_name = user_config.user.name.clone();
+/// 设置了 `BILIUP_APP_MOCK` 时启动本地模拟服务器并将接口请求指向它，
+/// 值为目录时额外加载其中的夹具
+#[cfg(feature = "mock-server")]
+async fn start_mock_server() {
+    let Ok(fixture_dir) = std::env::var("BILIUP_APP_MOCK") else {
+        return;
+    };
 
-        let myinfo = bilibili.my_info().await?;
-        let code = myinfo["code"].as_i64().unwrap_or(-1);
-        let uid = myinfo["data"]["mid"].as_u64().unwrap_or(0);
+    let fixture_dir = std::path::PathBuf::from(fixture_dir);
+    let server = if fixture_dir.is_dir() {
+        mock_server::MockServer::start_with_dir(&fixture_dir).await
+    } else {
+        mock_server::MockServer::start().await
+    };
+    match server {
+        Ok(server) => {
+            info!("已启动模拟服务器: {}", server.url());
+            if let Err(e) = mock_server::set_base_url_override(&server.url()) {
+                error!("设置模拟服务器地址失败: {}", e);
+            }
+        }
+        Err(e) => error!("启动模拟服务器失败: {}", e),
+    }
+}
 
-        if code == 0 && uid > 0 {
-            let username = myinfo["data"]["name"].as_str().unwrap_or("").to_owned();
-            let avatar_url = myinfo["data"]["face"].as_str().unwrap_or("").to_string();
-            let avatar = bilibili
-                .client
-                .get(avatar_url)
-                .send()
-                .await
-                .map_err(|e| anyhow::anyhow!("获取用户头像失败: {}", e))?
-                .bytes()
-                .await?;
-            let avatar = encode_base64(&avatar);
+async fn startup(config: &ConfigRoot) -> Result<HashMap<u64, MyClient>> {
+    let mut clients = HashMap::new();
 
-            clients.insert(
-                uid,
-                MyClient {
-                    bilibili,
-                    user: User::new(uid, username, avatar, false),
-                },
-            );
-        } else {
-            let msg = myinfo["message"].as_str().unwrap_or("未知原因");
-            info!(
-                "用户 {} 的登录状态无效 ({}: {}), 跳过自动登录",
-                fallback_name, code, msg
-            );
-            clients.insert(
-                fallback_uid,
-                MyClient {
-                    bilibili,
-                    user: User::new(fallback_uid, fallback_name, "".to_string(), true),
-                },
-            );
-        }
+    for user_config in config.config.values() {
+        let proxy = config.proxy_url(user_config.user.uid, ProxyRoute::Api);
+        let client = MyClient::from_user_config(user_config, proxy.as_deref()).await?;
+        clients.insert(client.user.uid, client);
     }
 
-    let max_curr = config.max_curr;
-    Ok(AppData {
-        config: Arc::new(Mutex::new(config)),
-        auth_service: Arc::new(Mutex::new(AuthService::new())),
-        upload_service: UploadService::new(max_curr),
-        clients: Arc::new(Mutex::new(clients)),
-    })
+    Ok(clients)
 }
 
 fn setup_logs(log_level: &str) -> Result<()> {
@@ -201,18 +275,28 @@ pub async fn run() {
         info!("无旧biliup配置: {}", e);
     }
 
-    let appdata = startup().await.unwrap_or_else(|e| {
-        error!("加载配置失败: {}", e);
-        let config = ConfigRoot::default();
-        let max_curr = config.max_curr;
-        AppData {
-            config: Arc::new(Mutex::new(config)),
-            auth_service: Arc::new(Mutex::new(AuthService::new())),
-            upload_service: UploadService::new(max_curr),
-            clients: Arc::new(Mutex::new(HashMap::new())),
-        }
+    #[cfg(feature = "mock-server")]
+    start_mock_server().await;
+
+    // 登录状态初始化失败时保留已加载的配置，避免之后的保存覆盖原有配置
+    let (config, config_warning) = load_config_or_default();
+    let clients = startup(&config).await.unwrap_or_else(|e| {
+        error!("初始化用户登录状态失败: {}", e);
+        HashMap::new()
     });
 
+    let max_curr = config.max_curr;
+    let (notifier, notification_rx) = Notifier::channel();
+    let appdata = AppData {
+        config: Arc::new(Mutex::new(config)),
+        auth_service: Arc::new(Mutex::new(AuthService::new())),
+        upload_service: UploadService::new(max_curr, notifier.clone()),
+        clients: Arc::new(Mutex::new(clients)),
+        archive_tracker: ArchiveTracker::load(),
+        notifier,
+        config_warning,
+    };
+
     setup_logs(&appdata.config.lock().await.log_level.clone()).expect("日志初始化失败");
 
     tauri::Builder::default()
@@ -222,6 +306,7 @@ pub async fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_window_state::Builder::default().build())
+        .plugin(tauri_plugin_notification::init())
         .setup(move |app: &mut tauri::App| {
             #[cfg(debug_assertions)] // only include this code on debug builds
             {
@@ -233,6 +318,13 @@ pub async fn run() {
 
             // 管理应用数据状态
             app.manage(appdata);
+
+            // 监听外部对配置文件的修改
+            ConfigWatcher::spawn(app.handle().clone());
+            // 跟踪新投稿的审核状态
+            ArchivePoller::spawn(app.handle().clone());
+            // 按配置发送上传与投稿通知
+            NotificationService::spawn(app.handle().clone(), notification_rx);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -251,33 +343,83 @@ pub async fn run() {
             cancel_upload,
             get_upload_queue,
             retry_upload,
+            preview_template,
+            validate_template,
             submit,
             // 配置相关命令
             load_config,
             save_config,
+            get_config_warning,
             save_user_config,
+            save_proxy_profiles,
+            save_user_proxy,
             save_global_config,
             add_user_template,
             update_user_template,
             delete_user_template,
             rename_user_template,
             save_template_order,
+            export_templates,
+            import_templates,
+            clone_user_template,
+            resolve_user_template,
             save_user_order,
+            export_backup,
+            inspect_backup,
+            import_backup,
             // 其他命令
             get_current_version,
             get_file_size,
             get_avatar_cache_dir,
             read_dir_recursive,
+            generate_part_titles,
             upload_cover,
+            preview_cover,
+            extract_cover_candidates,
+            upload_cover_frame,
+            preview_template_cover,
+            upload_template_cover,
             download_cover,
             get_archive_pre,
             get_topic_list,
+            get_activity_list,
+            get_metadata_status,
+            refresh_metadata,
+            search_categories,
             search_topics,
+            suggest_tags,
             search_mention,
+            parse_desc_mentions,
             get_season_list,
             get_video_detail,
             get_video_season,
             switch_season,
+            get_api_metrics,
+            // 合集管理相关命令
+            get_season_page,
+            get_season_detail,
+            create_season,
+            edit_season,
+            delete_season,
+            upload_season_cover,
+            create_section,
+            rename_section,
+            sort_sections,
+            delete_section,
+            get_section_episodes,
+            add_section_episodes,
+            remove_section_episode,
+            sort_section_episodes,
+            // 稿件管理相关命令
+            get_archive_list,
+            sync_archives,
+            get_cached_archives,
+            track_archive,
+            untrack_archive,
+            get_tracked_archives,
+            // 通知相关命令
+            save_notification_config,
+            test_notification_sink,
             export_logs,
             check_update,
             console_log
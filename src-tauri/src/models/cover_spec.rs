@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// 模板的封面生成配置：在底图上叠加若干文字图层
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverSpec {
+    /// 底图的本地路径
+    #[serde(default)]
+    pub base_image: String,
+    #[serde(default)]
+    pub layers: Vec<TextLayer>,
+}
+
+/// 单个文字图层，坐标与字号均以底图像素为单位
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextLayer {
+    /// 文字内容，支持与标题相同的模板变量，`\n` 换行
+    #[serde(default)]
+    pub text: String,
+    /// TTF/OTF 字体文件路径
+    #[serde(default)]
+    pub font: String,
+    #[serde(default = "default_font_size")]
+    pub size: f32,
+    /// `#RRGGBB` 或 `#RRGGBBAA`
+    #[serde(default = "default_color")]
+    pub color: String,
+    /// 描边颜色，为空时不描边
+    #[serde(default)]
+    pub stroke_color: Option<String>,
+    #[serde(default)]
+    pub stroke_width: u32,
+    /// 对齐点的横坐标，含义由 `align` 决定
+    #[serde(default)]
+    pub x: i32,
+    /// 第一行文字顶部的纵坐标
+    #[serde(default)]
+    pub y: i32,
+    #[serde(default)]
+    pub align: TextAlign,
+}
+
+fn default_font_size() -> f32 {
+    64.0
+}
+
+fn default_color() -> String {
+    "#FFFFFF".to_string()
+}
+
+/// 文字相对 `x` 的对齐方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
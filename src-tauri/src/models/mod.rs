@@ -1,7 +1,13 @@
+pub mod cover_spec;
+pub mod notification;
+pub mod proxy;
+pub mod template_inherit;
+pub mod template_share;
 pub mod upload_form;
 pub mod upload_task;
 pub mod user;
 pub mod user_config;
+pub mod validation;
 
 pub use upload_task::*;
 pub use user::*;
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 可以触发通知的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// 视频上传完成
+    UploadFinished,
+    /// 视频上传失败
+    UploadFailed,
+    /// 投稿或编辑稿件成功
+    SubmitSucceeded,
+    /// 投稿或编辑稿件失败
+    SubmitFailed,
+    /// 稿件审核通过
+    ArchivePublished,
+    /// 稿件被退回
+    ArchiveRejected,
+}
+
+impl NotifyEvent {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::UploadFinished => "上传完成",
+            Self::UploadFailed => "上传失败",
+            Self::SubmitSucceeded => "投稿成功",
+            Self::SubmitFailed => "投稿失败",
+            Self::ArchivePublished => "稿件已通过审核",
+            Self::ArchiveRejected => "稿件被退回",
+        }
+    }
+}
+
+/// 通知配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// 总开关，关闭时不发送任何通知
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub sinks: Vec<NotificationSink>,
+}
+
+/// 单个通知渠道
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSink {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 订阅的事件，为空时接收全部事件
+    #[serde(default)]
+    pub events: Vec<NotifyEvent>,
+    #[serde(flatten)]
+    pub kind: SinkKind,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl NotificationSink {
+    /// 渠道是否需要发送该事件
+    pub fn accepts(&self, event: NotifyEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+/// 通知渠道类型及其参数
+///
+/// 密钥类字段均可缺省，导出不含登录信息的备份时会被清空。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkKind {
+    /// 系统桌面通知
+    Desktop,
+    /// 以 JSON POST 到任意地址，`body_template` 中的 `{title}`、`{message}`、`{event}` 会被替换，
+    /// 未设置时发送包含全部字段的默认内容
+    Webhook {
+        #[serde(default)]
+        url: String,
+        #[serde(default)]
+        body_template: Option<String>,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Server酱
+    ServerChan {
+        #[serde(default)]
+        send_key: String,
+    },
+    /// Bark，`server` 为空时使用官方服务器
+    Bark {
+        #[serde(default)]
+        server: String,
+        #[serde(default)]
+        device_key: String,
+    },
+    /// Telegram 机器人
+    Telegram {
+        #[serde(default)]
+        bot_token: String,
+        #[serde(default)]
+        chat_id: String,
+    },
+    /// 钉钉自定义机器人，设置 `secret` 时使用加签校验
+    DingTalk {
+        #[serde(default)]
+        webhook: String,
+        #[serde(default)]
+        secret: Option<String>,
+    },
+    /// 飞书自定义机器人，设置 `secret` 时使用签名校验
+    Feishu {
+        #[serde(default)]
+        webhook: String,
+        #[serde(default)]
+        secret: Option<String>,
+    },
+}
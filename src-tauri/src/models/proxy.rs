@@ -0,0 +1,102 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// 表示不使用代理的保留名称
+pub const DIRECT_PROXY: &str = "direct";
+
+/// 代理协议
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyScheme {
+    #[default]
+    Http,
+    Https,
+    Socks5,
+}
+
+impl ProxyScheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Http => "http",
+            Self::Https => "https",
+            Self::Socks5 => "socks5",
+        }
+    }
+}
+
+/// 命名的代理配置，可供多个账号共用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyProfile {
+    pub name: String,
+    #[serde(default)]
+    pub scheme: ProxyScheme,
+    pub host: String,
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl ProxyProfile {
+    /// 生成代理地址，账号密码会按 URL 规则转义
+    pub fn to_url(&self) -> Result<String> {
+        let mut url = url::Url::parse(&format!(
+            "{}://{}:{}",
+            self.scheme.as_str(),
+            self.host.trim(),
+            self.port
+        ))
+        .map_err(|e| anyhow::anyhow!("代理 {} 的地址无效: {}", self.name, e))?;
+
+        if let Some(username) = self.username.as_deref().filter(|u| !u.is_empty()) {
+            url.set_username(username)
+                .map_err(|_| anyhow::anyhow!("代理 {} 的用户名无效", self.name))?;
+            url.set_password(self.password.as_deref().filter(|p| !p.is_empty()))
+                .map_err(|_| anyhow::anyhow!("代理 {} 的密码无效", self.name))?;
+        }
+
+        Ok(url.as_str().trim_end_matches('/').to_string())
+    }
+}
+
+/// 代理适用的流量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyRoute {
+    /// 登录、投稿等接口请求
+    Api,
+    /// 视频分片上传
+    Upload,
+}
+
+impl ProxyRoute {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Api => "接口",
+            Self::Upload => "上传",
+        }
+    }
+}
+
+/// 检查代理配置列表：名称不能为空、重复或使用保留名称，地址必须有效
+pub fn validate_profiles(profiles: &[ProxyProfile]) -> Result<()> {
+    let mut names = std::collections::HashSet::new();
+    for profile in profiles {
+        let name = profile.name.trim();
+        if name.is_empty() {
+            anyhow::bail!("代理名称不能为空");
+        }
+        if name == DIRECT_PROXY {
+            anyhow::bail!("代理名称不能为 {}", DIRECT_PROXY);
+        }
+        if !names.insert(name) {
+            anyhow::bail!("代理名称 {} 重复", name);
+        }
+        if profile.host.trim().is_empty() || profile.port == 0 {
+            anyhow::bail!("代理 {} 缺少主机或端口", name);
+        }
+        profile.to_url()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use tracing::warn;
+
+use crate::models::{TemplateConfig, UserConfig};
+
+/// 可以从父模板继承的字段，稿件、视频、定时发布等与单次投稿相关的字段不参与继承
+pub const INHERITABLE_FIELDS: &[&str] = &[
+    "copyright",
+    "source",
+    "tid",
+    "tid_v2",
+    "cover",
+    "title",
+    "desc",
+    "desc_v2",
+    "dynamic",
+    "subtitle",
+    "tag",
+    "open_subtitle",
+    "interactive",
+    "mission_id",
+    "topic_id",
+    "topic_name",
+    "season_id",
+    "section_id",
+    "season_episode_title",
+    "dolby",
+    "lossless_music",
+    "no_reprint",
+    "open_elec",
+    "no_disturbance",
+    "up_selection_reply",
+    "up_close_reply",
+    "up_close_danmu",
+    "is_only_self",
+    "watermark",
+    "staff",
+    "filename_pattern",
+    "part_title_rule",
+    "cover_spec",
+];
+
+/// 继承链的最大深度
+const MAX_INHERIT_DEPTH: usize = 16;
+
+impl TemplateConfig {
+    /// 父模板名称，未设置或为空时返回 None
+    pub fn parent_name(&self) -> Option<&str> {
+        self.parent
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// 用已解析的父模板覆盖 `inherit_fields` 中列出的字段
+    fn inherit_from(&self, parent: &TemplateConfig) -> Result<TemplateConfig> {
+        let mut child = serde_json::to_value(self)?;
+        let parent = serde_json::to_value(parent)?;
+
+        if let (Some(child_map), Some(parent_map)) = (child.as_object_mut(), parent.as_object()) {
+            for field in &self.inherit_fields {
+                if !INHERITABLE_FIELDS.contains(&field.as_str()) {
+                    warn!("字段 {} 不支持继承，已忽略", field);
+                    continue;
+                }
+                if let Some(value) = parent_map.get(field) {
+                    child_map.insert(field.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(serde_json::from_value(child)?)
+    }
+}
+
+impl UserConfig {
+    /// 沿继承链解析模板的最终配置
+    ///
+    /// `template` 为名为 `name` 的模板内容，可以是尚未保存的修改。
+    /// 父模板不存在、继承链成环或过深时返回错误。
+    pub fn resolve_template(
+        &self,
+        name: &str,
+        template: &TemplateConfig,
+    ) -> Result<TemplateConfig> {
+        let mut visited = vec![name];
+        let mut chain = vec![template];
+        let mut current = template;
+
+        while let Some(parent_name) = current.parent_name() {
+            let is_cycle = visited.contains(&parent_name);
+            visited.push(parent_name);
+            if is_cycle {
+                return Err(anyhow::anyhow!(
+                    "模板继承出现循环: {}",
+                    visited.join(" -> ")
+                ));
+            }
+            if chain.len() > MAX_INHERIT_DEPTH {
+                return Err(anyhow::anyhow!("模板继承层级超过 {MAX_INHERIT_DEPTH} 层"));
+            }
+            let parent = self
+                .templates
+                .get(parent_name)
+                .ok_or_else(|| anyhow::anyhow!("父模板不存在: {parent_name}"))?;
+            chain.push(parent);
+            current = parent;
+        }
+
+        let mut resolved = chain
+            .pop()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("模板为空"))?;
+        while let Some(child) = chain.pop() {
+            resolved = child.inherit_from(&resolved)?;
+        }
+        Ok(resolved)
+    }
+
+    /// 删除模板前，将直接继承它的子模板展开为独立模板，返回受影响的模板名
+    pub fn detach_template_children(&mut self, parent_name: &str) -> Result<Vec<String>> {
+        let children: Vec<String> = self
+            .templates
+            .iter()
+            .filter(|(_, template)| template.parent_name() == Some(parent_name))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in &children {
+            let Some(template) = self.templates.get(name) else {
+                continue;
+            };
+            let mut resolved = self.resolve_template(name, template)?;
+            resolved.parent = None;
+            resolved.inherit_fields.clear();
+            self.templates.insert(name.clone(), resolved);
+        }
+
+        Ok(children)
+    }
+
+    /// 模板重命名后同步更新子模板中的父模板名称
+    pub fn rename_template_parent(&mut self, old_name: &str, new_name: &str) {
+        for template in self.templates.values_mut() {
+            if template.parent_name() == Some(old_name) {
+                template.parent = Some(new_name.to_string());
+            }
+        }
+    }
+}
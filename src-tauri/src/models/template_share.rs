@@ -0,0 +1,83 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{models::TemplateConfig, utils::write_file_atomic};
+
+/// 模板导出文件格式版本
+const TEMPLATE_BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedTemplate {
+    pub name: String,
+    pub template: TemplateConfig,
+}
+
+/// 可在账号间共享的模板导出文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateBundle {
+    pub format_version: u32,
+    #[serde(default)]
+    pub app_version: String,
+    #[serde(default)]
+    pub exported_at: String,
+    pub templates: Vec<SharedTemplate>,
+}
+
+impl TemplateConfig {
+    /// 去除与账号绑定的字段（稿件、合集、联合投稿、已上传的视频、父模板），可选保留封面
+    ///
+    /// 带有父模板的模板应先通过 `UserConfig::resolve_template` 展开继承的字段。
+    pub fn to_shareable(&self, keep_cover: bool) -> Self {
+        let mut template = self.clone();
+        template.parent = None;
+        template.inherit_fields.clear();
+        template.aid = None;
+        template.season_id = None;
+        template.section_id = None;
+        template.staff = None;
+        template.state = None;
+        template.state_desc = None;
+        template.videos.clear();
+        if !keep_cover {
+            template.cover.clear();
+        }
+        template
+    }
+}
+
+impl TemplateBundle {
+    pub fn new(templates: Vec<SharedTemplate>) -> Self {
+        Self {
+            format_version: TEMPLATE_BUNDLE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            templates,
+        }
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        let json_content = serde_json::to_string_pretty(self)?;
+        write_file_atomic(path, json_content.as_bytes())
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let json_content = fs::read_to_string(path)?;
+        let bundle: Self = serde_json::from_str(&json_content)
+            .map_err(|e| anyhow::anyhow!("模板文件格式错误: {e}"))?;
+
+        if bundle.format_version > TEMPLATE_BUNDLE_VERSION {
+            return Err(anyhow::anyhow!(
+                "模板文件版本 v{} 高于当前程序支持的 v{}",
+                bundle.format_version,
+                TEMPLATE_BUNDLE_VERSION
+            ));
+        }
+        if bundle.templates.is_empty() {
+            return Err(anyhow::anyhow!("模板文件中没有模板"));
+        }
+
+        Ok(bundle)
+    }
+}
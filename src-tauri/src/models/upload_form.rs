@@ -1,12 +1,25 @@
 use std::collections::HashMap;
 
-use crate::models::TemplateConfig;
+use crate::{
+    models::TemplateConfig,
+    utils::{
+        desc_v2,
+        placeholder::{PartVars, PlaceholderContext},
+    },
+};
 use anyhow::Result;
 use biliup::bilibili;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use tracing::{debug, trace};
 
+/// 标题最大字数
+pub const TITLE_MAX_CHARS: usize = 80;
+/// 标签最大数量
+pub const TAG_MAX_COUNT: usize = 12;
+/// 单个标签最大字数
+pub const TAG_MAX_CHARS: usize = 20;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TopicDetail {
     pub from_topic_id: Option<u32>,
@@ -109,11 +122,85 @@ impl TemplateConfig {
         Ok(template_config)
     }
 
+    /// 逗号分隔的标签列表，忽略空白项
+    pub fn tag_list(&self) -> Vec<&str> {
+        self.tag
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    /// 各分P用于变量替换的文件信息
+    pub fn placeholder_parts(&self) -> Vec<PartVars> {
+        self.videos
+            .iter()
+            .map(|video| PartVars {
+                filename: if video.local_name.is_empty() {
+                    video.title.clone()
+                } else {
+                    video.local_name.clone()
+                },
+                file_mtime: (video.file_mtime > 0).then_some(video.file_mtime),
+                duration: (video.duration > 0).then_some(video.duration),
+            })
+            .collect()
+    }
+
+    /// 展开标题、简介、动态、标签、封面文字与分P标题中的模板变量，返回无法解析的变量
+    pub fn expand_placeholders(&mut self, ctx: &PlaceholderContext) -> Vec<String> {
+        let mut unresolved = Vec::new();
+
+        for text in [
+            &mut self.title,
+            &mut self.desc,
+            &mut self.dynamic,
+            &mut self.tag,
+        ] {
+            let rendered = ctx.render(text);
+            *text = rendered.text;
+            unresolved.extend(rendered.unresolved);
+        }
+
+        if let Some(episode_title) = self.season_episode_title.as_mut() {
+            let title = self.title.replace('{', "{{").replace('}', "}}");
+            let rendered = ctx.render(&episode_title.replace("{title}", &title));
+            *episode_title = rendered.text;
+            unresolved.extend(rendered.unresolved);
+        }
+
+        if let Some(credits) = self.desc_v2.as_mut() {
+            for credit in credits.iter_mut().filter(|credit| credit.r#type == 1) {
+                let rendered = ctx.render(&credit.raw_text);
+                credit.raw_text = rendered.text;
+                unresolved.extend(rendered.unresolved);
+            }
+        }
+
+        if let Some(spec) = self.cover_spec.as_mut() {
+            for layer in spec.layers.iter_mut() {
+                let rendered = ctx.render(&layer.text);
+                layer.text = rendered.text;
+                unresolved.extend(rendered.unresolved);
+            }
+        }
+
+        for (index, video) in self.videos.iter_mut().enumerate() {
+            let rendered = ctx.render_part(&video.title, index);
+            video.title = rendered.text;
+            unresolved.extend(rendered.unresolved);
+        }
+
+        unresolved.sort_unstable();
+        unresolved.dedup();
+        unresolved
+    }
+
     pub fn into_bilibili_form(self) -> BilibiliForm {
         let desc_v2 = self.desc_v2.and_then(|credits| {
             let new_credits = credits
                 .into_iter()
-                .filter(|item| (item.r#type == 1 || item.r#type == 2) && !item.raw_text.is_empty())
+                .filter(desc_v2::is_submittable)
                 .map(|c| CompatibleCredit {
                     type_id: c.r#type,
                     biz_id: if c.r#type == 2 && !c.biz_id.is_empty() {
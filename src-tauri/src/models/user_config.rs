@@ -4,14 +4,91 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+use crate::models::{
+    cover_spec::CoverSpec,
+    notification::NotificationConfig,
+    proxy::{self, DIRECT_PROXY, ProxyProfile, ProxyRoute},
+};
+use crate::utils::{
+    config_migration::{CURRENT_SCHEMA_VERSION, SchemaTooNew, ensure_overwritable, migrate_config},
+    get_config_backup_path,
+    part_title::PartTitleRule,
+    write_file_atomic,
+};
+
+/// 保留的配置备份数量
+const CONFIG_BACKUP_KEEP: usize = 10;
+const CONFIG_BACKUP_PREFIX: &str = "config-";
 
 fn current_timestamp() -> u64 {
     chrono::Utc::now().timestamp().max(0) as u64
 }
 
+/// 按时间从新到旧列出配置备份
+fn list_config_backups(backup_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(CONFIG_BACKUP_PREFIX) && name.ends_with(".json")
+                })
+        })
+        .collect();
+    // 备份文件名中的时间戳可以直接按字典序比较
+    backups.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(backups)
+}
+
+/// 备份当前配置文件，仅在其内容有效且与最新备份不同时进行，并清理过旧的备份
+fn backup_config_file(path: &Path, new_content: &str, backup_dir: &Path) -> Result<()> {
+    let Ok(current_content) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+
+    if current_content == new_content {
+        return Ok(());
+    }
+
+    // 不备份已经损坏的配置，避免有效备份被挤出
+    if ConfigRoot::from_json_str(&current_content).is_err() {
+        warn!("当前配置文件无法解析，跳过备份: {}", path.display());
+        return Ok(());
+    }
+
+    let backups = list_config_backups(backup_dir)?;
+    let latest_is_same = backups
+        .first()
+        .and_then(|latest| fs::read_to_string(latest).ok())
+        .is_some_and(|latest_content| latest_content == current_content);
+
+    if !latest_is_same {
+        let backup_path = backup_dir.join(format!(
+            "{CONFIG_BACKUP_PREFIX}{}.json",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+        ));
+        write_file_atomic(&backup_path, current_content.as_bytes())?;
+        debug!("已备份配置文件: {}", backup_path.display());
+    }
+
+    for stale in list_config_backups(backup_dir)?
+        .into_iter()
+        .skip(CONFIG_BACKUP_KEEP)
+    {
+        if let Err(e) = fs::remove_file(&stale) {
+            warn!("清理旧配置备份失败: {} ({})", stale.display(), e);
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserInfo {
     pub uid: u64,
@@ -71,6 +148,18 @@ pub struct VideoInfo {
     pub encoding_status: i64,
     #[serde(default)]
     pub status_desc: String,
+    /// 本地文件名（不含扩展名），上传时记录
+    #[serde(default)]
+    pub local_name: String,
+    /// 本地文件修改时间，上传时记录
+    #[serde(default)]
+    pub file_mtime: u64,
+    /// 视频时长（秒），上传时通过 ffprobe 获取
+    #[serde(default)]
+    pub duration: u64,
+    /// 本地字幕文件（SRT/ASS/VTT/BCC），投稿后上传到该分P
+    #[serde(default)]
+    pub subtitle_path: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,6 +204,9 @@ pub struct TemplateConfig {
     pub season_id: Option<u64>,
     #[serde(default)]
     pub section_id: Option<u64>,
+    /// 投稿后自动加入合集时的分集标题，支持模板变量，`{title}` 为稿件标题，为空时使用稿件标题
+    #[serde(default)]
+    pub season_episode_title: Option<String>,
     #[serde(default)]
     pub dolby: u8,
     #[serde(default)]
@@ -147,6 +239,20 @@ pub struct TemplateConfig {
     pub state: Option<i64>,
     #[serde(default)]
     pub state_desc: Option<String>,
+    /// 对文件名进行匹配的正则，分组可在标题等字段中以变量引用
+    #[serde(default)]
+    pub filename_pattern: Option<String>,
+    /// 添加视频时生成分P标题的规则，未设置时使用文件名
+    #[serde(default)]
+    pub part_title_rule: Option<PartTitleRule>,
+    /// 叠加文字生成封面的配置
+    #[serde(default)]
+    pub cover_spec: Option<CoverSpec>,
+    /// 父模板名称，提交时从父模板继承 `inherit_fields` 中列出的字段
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub inherit_fields: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,8 +260,15 @@ pub struct UserConfig {
     pub user: UserInfo,
     #[serde(default)]
     pub line: Option<String>,
+    /// 旧版的代理地址，未选择接口代理时使用
     #[serde(default)]
     pub proxy: Option<String>,
+    /// 接口请求使用的代理名称
+    #[serde(default)]
+    pub api_proxy: Option<String>,
+    /// 分片上传使用的代理名称，未设置时与接口请求相同
+    #[serde(default)]
+    pub upload_proxy: Option<String>,
     #[serde(default)]
     pub limit: u32,
     #[serde(default)]
@@ -172,6 +285,8 @@ pub struct UserConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigRoot {
+    #[serde(default)]
+    pub schema_version: u32,
     #[serde(default)]
     pub max_curr: u32,
     #[serde(default)]
@@ -184,6 +299,10 @@ pub struct ConfigRoot {
     pub user_order: Vec<u64>,
     #[serde(default)]
     pub config: HashMap<u64, UserConfig>,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub proxy_profiles: Vec<ProxyProfile>,
 }
 
 fn default_log_level() -> String {
@@ -193,14 +312,85 @@ fn default_log_level() -> String {
 impl ConfigRoot {
     pub fn from_file(path: &PathBuf) -> Result<Self> {
         let json_content = fs::read_to_string(path)?;
-        let mut config: Self = serde_json::from_str(&json_content)?;
+        Self::from_json_str(&json_content)
+    }
+
+    /// 解析配置内容，必要时先迁移到当前结构版本
+    pub fn from_json_str(json_content: &str) -> Result<Self> {
+        let mut raw: serde_json::Value = serde_json::from_str(json_content)?;
+        if migrate_config(&mut raw)? {
+            info!("配置已迁移到 v{}", CURRENT_SCHEMA_VERSION);
+        }
+
+        let mut config: Self = serde_json::from_value(raw)?;
         config.normalize_template_metadata();
         Ok(config)
     }
 
-    pub fn save_to_file(&mut self, path: &PathBuf) -> Result<()> {
+    /// 加载配置文件，解析失败时回退到最新的可用备份
+    ///
+    /// 返回的第二项为需要提示给用户的警告信息。损坏的配置文件会被重命名保留，
+    /// 不会在之后的保存中被覆盖。由更高版本写入的配置不属于损坏，直接返回
+    /// [`SchemaTooNew`] 且不改动原文件。
+    pub fn load_with_recovery(path: &PathBuf) -> Result<(Self, Option<String>)> {
+        Self::load_with_backups(path, &get_config_backup_path()?)
+    }
+
+    fn load_with_backups(path: &PathBuf, backup_dir: &Path) -> Result<(Self, Option<String>)> {
+        let load_err = match Self::from_file(path) {
+            Ok(config) => return Ok((config, None)),
+            Err(e) => e,
+        };
+
+        if !path.exists() || load_err.downcast_ref::<SchemaTooNew>().is_some() {
+            return Err(load_err);
+        }
+
+        warn!("配置文件解析失败: {} ({})", path.display(), load_err);
+
+        let corrupt_path = path.with_extension(format!(
+            "json.corrupt-{}",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        ));
+        fs::rename(path, &corrupt_path)?;
+        warn!("损坏的配置文件已保留为: {}", corrupt_path.display());
+
+        for backup_path in list_config_backups(backup_dir)? {
+            match Self::from_file(&backup_path) {
+                Ok(mut config) => {
+                    config.save_with_backups(path, backup_dir)?;
+                    let message = format!(
+                        "配置文件无法加载 ({load_err})，已从备份 {} 恢复，原文件保留为 {}",
+                        backup_path.display(),
+                        corrupt_path.display()
+                    );
+                    warn!("{}", message);
+                    return Ok((config, Some(message)));
+                }
+                Err(e) => {
+                    warn!("配置备份不可用: {} ({})", backup_path.display(), e);
+                }
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "配置文件无法加载且没有可用的备份 ({load_err})，原文件保留为 {}",
+            corrupt_path.display()
+        ))
+    }
+
+    /// 规范化后序列化为配置文件内容
+    pub fn serialize_normalized(&mut self) -> Result<String> {
         self.normalize_template_metadata();
+        self.schema_version = CURRENT_SCHEMA_VERSION;
+        Ok(serde_json::to_string_pretty(self)?)
+    }
 
+    pub fn save_to_file(&mut self, path: &Path) -> Result<()> {
+        self.save_with_backups(path, &get_config_backup_path()?)
+    }
+
+    fn save_with_backups(&mut self, path: &Path, backup_dir: &Path) -> Result<()> {
         // 确保父目录存在
         if let Some(parent) = path.parent()
             && !parent.exists()
@@ -208,8 +398,22 @@ impl ConfigRoot {
             fs::create_dir_all(parent)?;
         }
 
-        let json_content = serde_json::to_string_pretty(self)?;
-        fs::write(path, json_content)?;
+        // 不覆盖由更高版本写入的配置，避免丢失新版本的数据
+        if let Ok(existing) = fs::read_to_string(path) {
+            ensure_overwritable(&existing)?;
+        }
+
+        let json_content = self.serialize_normalized()?;
+
+        // 替换前确认写出的内容可以被重新加载
+        Self::from_json_str(&json_content)
+            .map_err(|e| anyhow::anyhow!("配置序列化校验失败: {e}"))?;
+
+        if let Err(e) = backup_config_file(path, &json_content, backup_dir) {
+            warn!("备份配置文件失败: {}", e);
+        }
+
+        write_file_atomic(path, json_content.as_bytes())?;
         Ok(())
     }
 
@@ -251,6 +455,8 @@ impl ConfigRoot {
             user: user_info,
             line: None,
             proxy,
+            api_proxy: None,
+            upload_proxy: None,
             limit: 0,
             watermark: 0,
             auto_edit: 0,
@@ -318,6 +524,99 @@ impl ConfigRoot {
         }
     }
 
+    pub fn proxy_profile(&self, name: &str) -> Option<&ProxyProfile> {
+        self.proxy_profiles
+            .iter()
+            .find(|profile| profile.name == name)
+    }
+
+    /// 替换代理配置列表，已删除的代理从各账号的选择中移除
+    pub fn save_proxy_profiles(&mut self, profiles: Vec<ProxyProfile>) -> Result<&Self> {
+        proxy::validate_profiles(&profiles)?;
+        self.proxy_profiles = profiles;
+
+        let names: HashSet<String> = self
+            .proxy_profiles
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect();
+        for user_config in self.config.values_mut() {
+            for selected in [&mut user_config.api_proxy, &mut user_config.upload_proxy] {
+                if selected
+                    .as_ref()
+                    .is_some_and(|name| name != DIRECT_PROXY && !names.contains(name))
+                {
+                    info!(
+                        "代理 {:?} 已删除，用户 {} 改为默认设置",
+                        selected, user_config.user.uid
+                    );
+                    *selected = None;
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// 选择账号的接口代理与上传代理，传入 None 使用默认设置
+    pub fn save_user_proxy(
+        &mut self,
+        uid: u64,
+        api_proxy: Option<String>,
+        upload_proxy: Option<String>,
+    ) -> Result<&Self> {
+        for name in [&api_proxy, &upload_proxy].into_iter().flatten() {
+            if name != DIRECT_PROXY && self.proxy_profile(name).is_none() {
+                anyhow::bail!("代理 {} 不存在", name);
+            }
+        }
+        let user_config = self
+            .config
+            .get_mut(&uid)
+            .ok_or_else(|| anyhow::anyhow!("用户配置不存在"))?;
+        info!(
+            "UID {} 代理设置更新: api_proxy={:?}, upload_proxy={:?}",
+            uid, api_proxy, upload_proxy
+        );
+        user_config.api_proxy = api_proxy;
+        user_config.upload_proxy = upload_proxy;
+        Ok(self)
+    }
+
+    /// 解析账号在指定流量上使用的代理地址，None 表示直连
+    ///
+    /// 接口请求未选择代理时使用旧版的代理地址，上传未选择代理时与接口请求相同。
+    pub fn proxy_url(&self, uid: u64, route: ProxyRoute) -> Option<String> {
+        let user_config = self.config.get(&uid)?;
+        let selected = match route {
+            ProxyRoute::Api => user_config.api_proxy.as_deref(),
+            ProxyRoute::Upload => user_config
+                .upload_proxy
+                .as_deref()
+                .or(user_config.api_proxy.as_deref()),
+        };
+
+        match selected {
+            Some(DIRECT_PROXY) => None,
+            Some(name) => match self.proxy_profile(name).map(ProxyProfile::to_url) {
+                Some(Ok(url)) => Some(url),
+                Some(Err(e)) => {
+                    warn!("用户 {} 的{}代理无效，改为直连: {}", uid, route.label(), e);
+                    None
+                }
+                None => {
+                    warn!(
+                        "用户 {} 的{}代理 {} 不存在，改为直连",
+                        uid,
+                        route.label(),
+                        name
+                    );
+                    None
+                }
+            },
+            None => user_config.proxy.clone().filter(|proxy| !proxy.is_empty()),
+        }
+    }
+
     pub fn save_global_config(
         &mut self,
         max_curr: u32,
@@ -367,12 +666,81 @@ impl ConfigRoot {
         template
     }
 
+    /// 导入模板到指定账号，与已有模板重名时自动重命名，返回实际使用的名称
+    pub fn import_user_templates(
+        &mut self,
+        uid: u64,
+        templates: Vec<(String, TemplateConfig)>,
+    ) -> Result<Vec<String>> {
+        if !self.config.contains_key(&uid) {
+            return Err(anyhow::anyhow!("用户配置不存在"));
+        }
+
+        let mut imported = Vec::with_capacity(templates.len());
+        for (name, template) in templates {
+            let base_name = match name.trim() {
+                "" => "导入的模板",
+                trimmed => trimmed,
+            };
+            let name = self
+                .config
+                .get(&uid)
+                .map(|user_config| user_config.unique_template_name(base_name))
+                .ok_or_else(|| anyhow::anyhow!("用户配置不存在"))?;
+            self.add_user_template(uid, &name, template);
+            imported.push(name);
+        }
+
+        Ok(imported)
+    }
+
+    /// 将模板复制到另一个账号，去除与原账号绑定的字段
+    pub fn clone_user_template(
+        &mut self,
+        from_uid: u64,
+        to_uid: u64,
+        template_name: &str,
+        new_name: Option<&str>,
+    ) -> Result<String> {
+        let source_config = self
+            .config
+            .get(&from_uid)
+            .ok_or_else(|| anyhow::anyhow!("源用户配置不存在"))?;
+        let template = source_config
+            .templates
+            .get(template_name)
+            .ok_or_else(|| anyhow::anyhow!("源模板不存在"))?;
+        let template = source_config
+            .resolve_template(template_name, template)?
+            .to_shareable(true);
+
+        let name = new_name
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(template_name)
+            .to_string();
+
+        self.import_user_templates(to_uid, vec![(name, template)])?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("复制模板失败"))
+    }
+
     pub fn delete_user_template(
         &mut self,
         uid: u64,
         template_name: &str,
     ) -> Option<TemplateConfig> {
         if let Some(user_config) = self.config.get_mut(&uid) {
+            match user_config.detach_template_children(template_name) {
+                Ok(children) if !children.is_empty() => {
+                    info!(
+                        "模板 {} 被删除，子模板已展开为独立模板: {:?}",
+                        template_name, children
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => warn!("展开模板 {} 的子模板失败: {}", template_name, e),
+            }
             let removed = user_config.templates.remove(template_name);
             if removed.is_some() {
                 user_config
@@ -425,6 +793,7 @@ impl ConfigRoot {
                 .ok_or_else(|| anyhow::anyhow!("原模板不存在"))?;
 
             user_config.templates.insert(new_name.to_string(), template);
+            user_config.rename_template_parent(old_name, new_name);
 
             if let Some(updated_at) = user_config.template_updated_at.remove(old_name) {
                 user_config
@@ -449,12 +818,15 @@ impl ConfigRoot {
 impl Default for ConfigRoot {
     fn default() -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             max_curr: 1,
             auto_start: true,
             auto_upload: true,
             log_level: default_log_level(),
             user_order: Vec::new(),
             config: HashMap::new(),
+            notifications: NotificationConfig::default(),
+            proxy_profiles: Vec::new(),
         }
     }
 }
@@ -529,6 +901,22 @@ impl ConfigRoot {
 }
 
 impl UserConfig {
+    /// 生成不与已有模板重名的名称，重名时追加序号
+    pub fn unique_template_name(&self, base: &str) -> String {
+        if !self.templates.contains_key(base) {
+            return base.to_string();
+        }
+
+        let mut index = 2;
+        loop {
+            let candidate = format!("{base} ({index})");
+            if !self.templates.contains_key(&candidate) {
+                return candidate;
+            }
+            index += 1;
+        }
+    }
+
     pub fn normalize_template_metadata(&mut self) {
         let mut seen = std::collections::HashSet::new();
         self.template_order
@@ -574,6 +962,7 @@ impl Default for TemplateConfig {
             topic_name: None,
             season_id: None,
             section_id: None,
+            season_episode_title: None,
             dolby: 0,
             lossless_music: 0,
             no_reprint: 0,
@@ -590,6 +979,130 @@ impl Default for TemplateConfig {
             staff: None,
             state: None,
             state_desc: None,
+            filename_pattern: None,
+            part_title_rule: None,
+            cover_spec: None,
+            parent: None,
+            inherit_fields: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn newer_config_file(name: &str) -> (PathBuf, String) {
+        let dir = std::env::temp_dir().join(format!("biliup-app-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+        let content = serde_json::json!({
+            "schema_version": CURRENT_SCHEMA_VERSION + 1,
+            "max_curr": 3,
+        })
+        .to_string();
+        fs::write(&path, &content).unwrap();
+        (path, content)
+    }
+
+    #[test]
+    fn newer_schema_is_not_treated_as_corrupt() {
+        let (path, content) = newer_config_file("load-newer");
+
+        let error = ConfigRoot::load_with_recovery(&path).unwrap_err();
+        assert!(error.downcast_ref::<SchemaTooNew>().is_some());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("biliup-app-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("backups")).unwrap();
+        dir
+    }
+
+    fn config_content(max_curr: u32) -> String {
+        let config = ConfigRoot {
+            max_curr,
+            ..Default::default()
+        };
+        serde_json::to_string_pretty(&config).unwrap()
+    }
+
+    #[test]
+    fn corrupt_config_is_restored_from_newest_valid_backup() {
+        let dir = temp_dir("recover");
+        let path = dir.join("config.json");
+        let backup_dir = dir.join("backups");
+        fs::write(
+            backup_dir.join("config-20240101_000000_000.json"),
+            config_content(1),
+        )
+        .unwrap();
+        fs::write(
+            backup_dir.join("config-20240102_000000_000.json"),
+            config_content(2),
+        )
+        .unwrap();
+        fs::write(backup_dir.join("config-20240103_000000_000.json"), "{").unwrap();
+        fs::write(&path, "{ \"max_curr\": ").unwrap();
+
+        let (config, warning) = ConfigRoot::load_with_backups(&path, &backup_dir).unwrap();
+        assert_eq!(config.max_curr, 2);
+        assert!(warning.unwrap().contains("config-20240102_000000_000.json"));
+        assert_eq!(ConfigRoot::from_file(&path).unwrap().max_curr, 2);
+        let corrupt = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(corrupt, 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn backups_are_rotated_to_keep_limit() {
+        let dir = temp_dir("rotate");
+        let path = dir.join("config.json");
+        let backup_dir = dir.join("backups");
+        for day in 1..=12 {
+            fs::write(
+                backup_dir.join(format!("config-202401{day:02}_000000_000.json")),
+                config_content(day),
+            )
+            .unwrap();
         }
+        fs::write(&path, config_content(13)).unwrap();
+
+        let mut config = ConfigRoot {
+            max_curr: 14,
+            ..Default::default()
+        };
+        config.save_with_backups(&path, &backup_dir).unwrap();
+
+        let backups = list_config_backups(&backup_dir).unwrap();
+        assert_eq!(backups.len(), CONFIG_BACKUP_KEEP);
+        // 最新的备份为保存前的配置，最旧的备份被清理
+        assert_eq!(ConfigRoot::from_file(&backups[0]).unwrap().max_curr, 13);
+        assert!(!backup_dir.join("config-20240103_000000_000.json").exists());
+        assert!(backup_dir.join("config-20240104_000000_000.json").exists());
+        assert_eq!(ConfigRoot::from_file(&path).unwrap().max_curr, 14);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_refuses_to_overwrite_newer_schema() {
+        let (path, content) = newer_config_file("save-newer");
+
+        let error = ConfigRoot::default().save_to_file(&path).unwrap_err();
+        assert!(error.downcast_ref::<SchemaTooNew>().is_some());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
     }
 }
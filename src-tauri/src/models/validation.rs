@@ -0,0 +1,459 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::{
+    models::{
+        TemplateConfig,
+        upload_form::{TAG_MAX_CHARS, TAG_MAX_COUNT, TITLE_MAX_CHARS},
+    },
+    utils::desc_v2,
+};
+
+/// 简介最大字数
+pub const DESC_MAX_CHARS: usize = 2000;
+/// 简介中@用户的最大数量
+pub const MENTION_MAX_COUNT: usize = 10;
+/// 粉丝动态最大字数
+pub const DYNAMIC_MAX_CHARS: usize = 233;
+/// 转载来源最大字数
+pub const SOURCE_MAX_CHARS: usize = 200;
+/// 定时发布时间距当前的最短间隔（秒）
+pub const DTIME_MIN_OFFSET: i64 = 2 * 60 * 60;
+/// 定时发布时间距当前的最长间隔（秒）
+pub const DTIME_MAX_OFFSET: i64 = 15 * 24 * 60 * 60;
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// 出错的字段，分P与联合投稿带下标，如 `videos[0].filename`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub(crate) fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// 将校验错误拼接为一行描述
+pub fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| error.message.as_str())
+        .collect::<Vec<_>>()
+        .join("；")
+}
+
+/// 去除空白与 `@` 后比较，用于判断简介与 desc_v2 是否一致
+fn normalize_mention_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && *c != '@')
+        .collect()
+}
+
+impl TemplateConfig {
+    /// 检查标题与标签的长度限制
+    pub fn check_text_limits(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        let title_chars = self.title.chars().count();
+        if title_chars > TITLE_MAX_CHARS {
+            errors.push(FieldError::new(
+                "title",
+                format!("标题长度 {title_chars} 超过 {TITLE_MAX_CHARS} 字"),
+            ));
+        }
+
+        let tags = self.tag_list();
+        if tags.len() > TAG_MAX_COUNT {
+            errors.push(FieldError::new(
+                "tag",
+                format!("标签数量 {} 超过 {TAG_MAX_COUNT} 个", tags.len()),
+            ));
+        }
+        for tag in tags {
+            if tag.chars().count() > TAG_MAX_CHARS {
+                errors.push(FieldError::new(
+                    "tag",
+                    format!("标签「{tag}」超过 {TAG_MAX_CHARS} 字"),
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// 提交前离线校验投稿信息，`check_videos` 为 false 时跳过分P相关检查
+    pub fn validate(&self, check_videos: bool) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.title.trim().is_empty() {
+            errors.push(FieldError::new("title", "标题不能为空"));
+        }
+        if self.tag_list().is_empty() {
+            errors.push(FieldError::new("tag", "至少需要一个标签"));
+        }
+        errors.extend(self.check_text_limits());
+
+        let desc_chars = self.desc.chars().count();
+        if desc_chars > DESC_MAX_CHARS {
+            errors.push(FieldError::new(
+                "desc",
+                format!("简介长度 {desc_chars} 超过 {DESC_MAX_CHARS} 字"),
+            ));
+        }
+        let dynamic_chars = self.dynamic.chars().count();
+        if dynamic_chars > DYNAMIC_MAX_CHARS {
+            errors.push(FieldError::new(
+                "dynamic",
+                format!("粉丝动态长度 {dynamic_chars} 超过 {DYNAMIC_MAX_CHARS} 字"),
+            ));
+        }
+
+        match self.copyright {
+            1 => {}
+            2 => {
+                let source = self.source.trim();
+                if source.is_empty() {
+                    errors.push(FieldError::new("source", "转载稿件需要填写转载来源"));
+                } else if source.chars().count() > SOURCE_MAX_CHARS {
+                    errors.push(FieldError::new(
+                        "source",
+                        format!("转载来源超过 {SOURCE_MAX_CHARS} 字"),
+                    ));
+                }
+            }
+            other => errors.push(FieldError::new(
+                "copyright",
+                format!("未知的版权类型: {other}"),
+            )),
+        }
+
+        if self.cover.trim().is_empty() {
+            errors.push(FieldError::new("cover", "请上传封面"));
+        }
+        if self.tid_v2 == 0 {
+            errors.push(FieldError::new("tid_v2", "请选择分区"));
+        }
+
+        if let Some(dtime) = self.dtime.filter(|_| self.aid.is_none()) {
+            let offset = i64::from(dtime) - chrono::Utc::now().timestamp();
+            if !(DTIME_MIN_OFFSET..=DTIME_MAX_OFFSET).contains(&offset) {
+                errors.push(FieldError::new(
+                    "dtime",
+                    "定时发布时间需在当前时间 2 小时后、15 天内",
+                ));
+            }
+        }
+
+        if check_videos {
+            errors.extend(self.validate_videos());
+        }
+        errors.extend(self.validate_staff());
+        errors.extend(self.validate_desc_v2());
+
+        errors
+    }
+
+    fn validate_videos(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.videos.is_empty() {
+            errors.push(FieldError::new("videos", "至少需要一个视频"));
+            return errors;
+        }
+
+        for (index, video) in self.videos.iter().enumerate() {
+            if video.filename.trim().is_empty() {
+                errors.push(FieldError::new(
+                    format!("videos[{index}].filename"),
+                    format!("第 {} 个视频尚未上传完成", index + 1),
+                ));
+            }
+            let title_chars = video.title.chars().count();
+            if title_chars > TITLE_MAX_CHARS {
+                errors.push(FieldError::new(
+                    format!("videos[{index}].title"),
+                    format!(
+                        "第 {} 个分P标题长度 {title_chars} 超过 {TITLE_MAX_CHARS} 字",
+                        index + 1
+                    ),
+                ));
+            }
+        }
+
+        errors
+    }
+
+    fn validate_staff(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let Some(staffs) = self.staff.as_ref() else {
+            return errors;
+        };
+
+        let active: Vec<_> = staffs
+            .iter()
+            .enumerate()
+            .filter(|(_, staff)| staff.is_del == 0)
+            .collect();
+        if !active.is_empty() && self.copyright != 1 {
+            errors.push(FieldError::new("staff", "仅自制稿件可以设置联合投稿"));
+        }
+
+        let mut seen = HashSet::new();
+        for (index, staff) in active {
+            if staff.mid == 0 {
+                errors.push(FieldError::new(
+                    format!("staff[{index}].mid"),
+                    format!("第 {} 位联合投稿成员缺少用户", index + 1),
+                ));
+            } else if !seen.insert(staff.mid) {
+                errors.push(FieldError::new(
+                    format!("staff[{index}].mid"),
+                    format!("联合投稿成员 {} 重复", staff.mid),
+                ));
+            }
+            if staff.title.trim().is_empty() {
+                errors.push(FieldError::new(
+                    format!("staff[{index}].title"),
+                    format!("第 {} 位联合投稿成员缺少职能", index + 1),
+                ));
+            }
+        }
+
+        errors
+    }
+
+    fn validate_desc_v2(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let Some(credits) = self.desc_v2.as_ref().filter(|credits| !credits.is_empty()) else {
+            return errors;
+        };
+
+        for (index, credit) in credits.iter().enumerate() {
+            if credit.r#type != 2 {
+                continue;
+            }
+            if credit.raw_text.trim().is_empty() {
+                errors.push(FieldError::new(
+                    format!("desc_v2[{index}].raw_text"),
+                    "简介中的@用户缺少名称",
+                ));
+            }
+            if credit.biz_id.parse::<u64>().is_err() {
+                errors.push(FieldError::new(
+                    format!("desc_v2[{index}].biz_id"),
+                    format!("简介中的@{} 缺少有效的用户ID", credit.raw_text.trim()),
+                ));
+            }
+        }
+
+        let mentions = desc_v2::mention_count(credits);
+        if mentions > MENTION_MAX_COUNT {
+            errors.push(FieldError::new(
+                "desc_v2",
+                format!("简介中@用户数量 {mentions} 超过 {MENTION_MAX_COUNT} 个"),
+            ));
+        }
+
+        let expected = desc_v2::render_desc(credits);
+        if normalize_mention_text(&expected) != normalize_mention_text(&self.desc) {
+            errors.push(FieldError::new(
+                "desc_v2",
+                "简介内容与@信息不一致，请重新编辑简介",
+            ));
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::user_config::{Credit, Staff};
+
+    fn valid_form() -> TemplateConfig {
+        TemplateConfig {
+            title: "标题".to_string(),
+            tag: "录播,游戏".to_string(),
+            cover: "https://i0.hdslb.com/bfs/archive/cover.jpg".to_string(),
+            tid_v2: 1008,
+            ..Default::default()
+        }
+    }
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|error| error.field.as_str()).collect()
+    }
+
+    fn staff(mid: u64, title: &str) -> Staff {
+        Staff {
+            title: title.to_string(),
+            mid,
+            is_del: 0,
+        }
+    }
+
+    fn credits(value: serde_json::Value) -> Option<Vec<Credit>> {
+        Some(serde_json::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn valid_form_has_no_errors() {
+        assert!(valid_form().validate(false).is_empty());
+    }
+
+    #[test]
+    fn dtime_must_be_within_window() {
+        let now = chrono::Utc::now().timestamp();
+        let with_dtime = |offset: i64| TemplateConfig {
+            dtime: Some(u32::try_from(now + offset).unwrap()),
+            ..valid_form()
+        };
+
+        for offset in [DTIME_MIN_OFFSET + 60, DTIME_MAX_OFFSET - 60] {
+            assert!(with_dtime(offset).validate(false).is_empty(), "{offset}");
+        }
+        for offset in [DTIME_MIN_OFFSET - 60, DTIME_MAX_OFFSET + 60, -60] {
+            assert_eq!(
+                fields(&with_dtime(offset).validate(false)),
+                ["dtime"],
+                "{offset}"
+            );
+        }
+
+        // 编辑已发布的稿件时不检查定时发布时间
+        let editing = TemplateConfig {
+            aid: Some(1),
+            ..with_dtime(-60)
+        };
+        assert!(editing.validate(false).is_empty());
+    }
+
+    #[test]
+    fn repost_requires_source() {
+        let repost = TemplateConfig {
+            copyright: 2,
+            ..valid_form()
+        };
+        assert_eq!(fields(&repost.validate(false)), ["source"]);
+
+        let with_source = TemplateConfig {
+            source: "https://www.bilibili.com/video/BV1xx411c7mD".to_string(),
+            ..repost.clone()
+        };
+        assert!(with_source.validate(false).is_empty());
+
+        let long_source = TemplateConfig {
+            source: "a".repeat(SOURCE_MAX_CHARS + 1),
+            ..repost
+        };
+        assert_eq!(fields(&long_source.validate(false)), ["source"]);
+    }
+
+    #[test]
+    fn duplicate_staff_is_rejected() {
+        let form = TemplateConfig {
+            staff: Some(vec![
+                staff(10001, "剪辑"),
+                Staff {
+                    is_del: 1,
+                    ..staff(10002, "配音")
+                },
+                staff(10001, "后期"),
+                staff(0, ""),
+            ]),
+            ..valid_form()
+        };
+        assert_eq!(
+            fields(&form.validate(false)),
+            ["staff[2].mid", "staff[3].mid", "staff[3].title"]
+        );
+
+        let repost = TemplateConfig {
+            copyright: 2,
+            source: "来源".to_string(),
+            staff: Some(vec![staff(10001, "剪辑")]),
+            ..valid_form()
+        };
+        assert_eq!(fields(&repost.validate(false)), ["staff"]);
+    }
+
+    #[test]
+    fn desc_v2_must_match_desc() {
+        let form = TemplateConfig {
+            desc: "感谢 @张三 的帮助".to_string(),
+            desc_v2: credits(json!([
+                { "type": 1, "raw_text": "感谢 " },
+                { "type": 2, "raw_text": "张三", "biz_id": "10001" },
+                { "type": 1, "raw_text": " 的帮助" }
+            ])),
+            ..valid_form()
+        };
+        assert!(form.validate(false).is_empty());
+
+        let changed = TemplateConfig {
+            desc: "感谢 @李四 的帮助".to_string(),
+            ..form.clone()
+        };
+        assert_eq!(fields(&changed.validate(false)), ["desc_v2"]);
+
+        let missing_id = TemplateConfig {
+            desc_v2: credits(json!([
+                { "type": 1, "raw_text": "感谢 " },
+                { "type": 2, "raw_text": "张三", "biz_id": "" },
+                { "type": 1, "raw_text": " 的帮助" }
+            ])),
+            ..form
+        };
+        assert_eq!(fields(&missing_id.validate(false)), ["desc_v2[1].biz_id"]);
+    }
+
+    #[test]
+    fn tag_count_and_length_are_limited() {
+        let tags: Vec<String> = (0..=TAG_MAX_COUNT).map(|i| format!("标签{i}")).collect();
+        let too_many = TemplateConfig {
+            tag: tags.join(","),
+            ..valid_form()
+        };
+        assert_eq!(fields(&too_many.validate(false)), ["tag"]);
+
+        let at_limit = TemplateConfig {
+            tag: tags[..TAG_MAX_COUNT].join(","),
+            ..valid_form()
+        };
+        assert!(at_limit.validate(false).is_empty());
+
+        let too_long = TemplateConfig {
+            tag: format!("正常,{}", "长".repeat(TAG_MAX_CHARS + 1)),
+            ..valid_form()
+        };
+        assert_eq!(fields(&too_long.validate(false)), ["tag"]);
+
+        let empty = TemplateConfig {
+            tag: " , ".to_string(),
+            ..valid_form()
+        };
+        assert_eq!(fields(&empty.validate(false)), ["tag"]);
+    }
+
+    #[test]
+    fn videos_are_checked_only_when_requested() {
+        assert!(valid_form().validate(false).is_empty());
+        assert_eq!(fields(&valid_form().validate(true)), ["videos"]);
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, LazyLock, Mutex as StdMutex},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::{
+    sync::Mutex,
+    time::{Duration, interval},
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    AppData, MyClient,
+    error::AppError,
+    models::notification::NotifyEvent,
+    services::{
+        creative_api::{ArchiveStage, ArchiveSummary},
+        notification_service::Notification,
+    },
+    utils::{get_archive_cache_path, write_file_atomic},
+};
+
+pub const ARCHIVE_STATE_CHANGED_EVENT: &str = "archive-state-changed";
+
+/// 检查跟踪稿件状态的间隔
+const ARCHIVE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// 同步稿件列表时每页的数量
+const ARCHIVE_SYNC_PAGE_SIZE: u32 = 50;
+/// 同步稿件列表的最大页数
+const ARCHIVE_SYNC_MAX_PAGES: u32 = 40;
+/// 超过该时长仍未出现在列表中或未到达终态的稿件停止跟踪（秒）
+///
+/// 定时发布最多可以设置在 15 天后，另外留出审核与转码的时间。
+const TRACK_MAX_AGE: i64 = 20 * 24 * 60 * 60;
+/// 跟踪中的稿件列表，与稿件缓存放在同一目录
+const TRACKED_FILE: &str = "tracked.json";
+
+/// 各账号稿件缓存的读写锁，避免命令与后台轮询同时写入时互相覆盖
+static CACHE_LOCKS: LazyLock<StdMutex<HashMap<u64, Arc<Mutex<()>>>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+fn cache_lock(uid: u64) -> Arc<Mutex<()>> {
+    CACHE_LOCKS
+        .lock()
+        .map(|mut locks| locks.entry(uid).or_default().clone())
+        .unwrap_or_default()
+}
+
+/// 本地缓存的稿件列表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveCache {
+    pub uid: u64,
+    /// 最近一次同步的时间（秒级时间戳）
+    pub updated_at: i64,
+    pub archives: Vec<ArchiveSummary>,
+}
+
+impl ArchiveCache {
+    fn path(uid: u64) -> Result<PathBuf> {
+        Ok(get_archive_cache_path()?.join(format!("{uid}.json")))
+    }
+
+    /// 读取缓存，不存在或无法解析时返回空列表
+    pub fn load(uid: u64) -> Self {
+        let empty = Self {
+            uid,
+            ..Default::default()
+        };
+        let Ok(path) = Self::path(uid) else {
+            return empty;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("稿件缓存 {} 无法解析，已忽略: {}", path.display(), e);
+                empty
+            }),
+            Err(_) => empty,
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let content = serde_json::to_vec(self)?;
+        write_file_atomic(&Self::path(self.uid)?, &content)
+    }
+
+    /// 将稿件合并进本地缓存并写回
+    pub async fn merge_and_save(uid: u64, archives: &[ArchiveSummary]) -> Result<Self> {
+        Self::modify(uid, |cache| cache.merge(archives)).await
+    }
+
+    /// 用完整的稿件列表替换本地缓存
+    pub async fn replace_and_save(uid: u64, archives: &[ArchiveSummary]) -> Result<Self> {
+        Self::modify(uid, |cache| {
+            cache.archives.clear();
+            cache.merge(archives);
+        })
+        .await
+    }
+
+    /// 导入备份中的稿件，只添加本地缓存中没有的稿件
+    pub async fn import_and_save(uid: u64, archives: &[ArchiveSummary]) -> Result<Self> {
+        Self::modify(uid, |cache| {
+            let missing: Vec<ArchiveSummary> = archives
+                .iter()
+                .filter(|archive| !cache.archives.iter().any(|a| a.aid == archive.aid))
+                .cloned()
+                .collect();
+            cache.merge(&missing);
+        })
+        .await
+    }
+
+    /// 持有该账号的缓存锁读取、修改并写回缓存
+    async fn modify(uid: u64, update: impl FnOnce(&mut Self)) -> Result<Self> {
+        let lock = cache_lock(uid);
+        let _guard = lock.lock().await;
+        let mut cache = Self::load(uid);
+        update(&mut cache);
+        cache.save()?;
+        Ok(cache)
+    }
+
+    /// 按 aid 更新或插入稿件，保持按投稿时间倒序
+    fn merge(&mut self, archives: &[ArchiveSummary]) {
+        for archive in archives {
+            match self.archives.iter_mut().find(|a| a.aid == archive.aid) {
+                Some(existing) => *existing = archive.clone(),
+                None => self.archives.push(archive.clone()),
+            }
+        }
+        self.archives.sort_by_key(|a| std::cmp::Reverse(a.ctime));
+        self.updated_at = chrono::Utc::now().timestamp();
+    }
+}
+
+/// 翻页获取账号的全部稿件并写入缓存
+pub async fn sync_archives(client: &MyClient) -> Result<ArchiveCache, AppError> {
+    let creative = client.creative();
+    let mut archives = Vec::new();
+
+    for pn in 1..=ARCHIVE_SYNC_MAX_PAGES {
+        let page = creative.archives(pn, ARCHIVE_SYNC_PAGE_SIZE, None).await?;
+        let fetched = page.archives.len();
+        archives.extend(page.archives);
+        if fetched < ARCHIVE_SYNC_PAGE_SIZE as usize || archives.len() >= page.count as usize {
+            break;
+        }
+    }
+
+    let cache = ArchiveCache::replace_and_save(client.user.uid, &archives)
+        .await
+        .map_err(|e| AppError::Custom(format!("保存稿件缓存失败: {e}")))?;
+    info!(
+        "用户 {} 的稿件列表已同步，共 {} 个",
+        client.user.uid,
+        cache.archives.len()
+    );
+    Ok(cache)
+}
+
+/// 正在跟踪审核状态的稿件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedArchive {
+    pub uid: u64,
+    pub aid: u64,
+    /// 最近一次看到的状态，尚未出现在稿件列表中时为 None
+    pub state: Option<i64>,
+    pub stage: ArchiveStage,
+    /// 开始跟踪的时间（秒级时间戳）
+    pub since: i64,
+}
+
+/// 稿件状态变化时通知前端的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveStateEvent {
+    pub uid: u64,
+    pub previous_state: Option<i64>,
+    pub previous_stage: ArchiveStage,
+    pub archive: ArchiveSummary,
+}
+
+impl ArchiveStateEvent {
+    /// 稿件通过审核或被退回时需要发送的通知
+    fn notification(&self) -> Option<Notification> {
+        if self.previous_stage == self.archive.stage {
+            return None;
+        }
+        let archive = &self.archive;
+        let notification = match archive.stage {
+            ArchiveStage::Published => Notification::new(
+                NotifyEvent::ArchivePublished,
+                format!("{} ({})", archive.title, archive.state_desc),
+            ),
+            ArchiveStage::Rejected => Notification::new(
+                NotifyEvent::ArchiveRejected,
+                if archive.reject_reasons.is_empty() {
+                    format!("{} ({})", archive.title, archive.state_desc)
+                } else {
+                    format!("{}: {}", archive.title, archive.reject_reasons.join("；"))
+                },
+            ),
+            _ => return None,
+        };
+        Some(notification.with_url(format!("https://www.bilibili.com/video/{}", archive.bvid)))
+    }
+}
+
+/// 需要跟踪审核状态的稿件，以 aid 为键，变化后写入磁盘以便重启后继续跟踪
+#[derive(Clone, Default)]
+pub struct ArchiveTracker {
+    tracked: Arc<Mutex<HashMap<u64, TrackedArchive>>>,
+}
+
+impl ArchiveTracker {
+    fn path() -> Result<PathBuf> {
+        Ok(get_archive_cache_path()?.join(TRACKED_FILE))
+    }
+
+    /// 读取上次退出前跟踪的稿件，不存在或无法解析时从空列表开始
+    pub fn load() -> Self {
+        let tracked: Vec<TrackedArchive> = Self::path()
+            .ok()
+            .and_then(|path| {
+                let content = std::fs::read_to_string(&path).ok()?;
+                serde_json::from_str(&content)
+                    .inspect_err(|e| {
+                        warn!("稿件跟踪列表 {} 无法解析，已忽略: {}", path.display(), e)
+                    })
+                    .ok()
+            })
+            .unwrap_or_default();
+        if !tracked.is_empty() {
+            info!("继续跟踪 {} 个稿件的审核状态", tracked.len());
+        }
+
+        Self {
+            tracked: Arc::new(Mutex::new(
+                tracked
+                    .into_iter()
+                    .map(|entry| (entry.aid, entry))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// 写入跟踪列表，调用方需持有 `tracked` 的锁
+    fn persist(tracked: &HashMap<u64, TrackedArchive>) {
+        let result = Self::path().and_then(|path| {
+            let content = serde_json::to_vec(&tracked.values().collect::<Vec<_>>())?;
+            write_file_atomic(&path, &content)
+        });
+        if let Err(e) = result {
+            warn!("保存稿件跟踪列表失败: {}", e);
+        }
+    }
+
+    /// 开始跟踪稿件，已在跟踪时重置状态（如编辑后重新进入审核）
+    pub async fn track(&self, uid: u64, aid: u64) {
+        debug!("开始跟踪稿件 av{} 的审核状态", aid);
+        let mut tracked = self.tracked.lock().await;
+        tracked.insert(
+            aid,
+            TrackedArchive {
+                uid,
+                aid,
+                state: None,
+                stage: ArchiveStage::Transcoding,
+                since: chrono::Utc::now().timestamp(),
+            },
+        );
+        Self::persist(&tracked);
+    }
+
+    pub async fn untrack(&self, aid: u64) -> bool {
+        let mut tracked = self.tracked.lock().await;
+        let removed = tracked.remove(&aid).is_some();
+        if removed {
+            Self::persist(&tracked);
+        }
+        removed
+    }
+
+    pub async fn list(&self) -> Vec<TrackedArchive> {
+        self.tracked.lock().await.values().cloned().collect()
+    }
+
+    /// 导入备份中跟踪的稿件，已在跟踪或已超过跟踪时长的稿件跳过，返回导入的数量
+    pub async fn import(&self, entries: Vec<TrackedArchive>) -> usize {
+        let now = chrono::Utc::now().timestamp();
+        let mut tracked = self.tracked.lock().await;
+        let mut imported = 0;
+        for entry in entries {
+            if now - entry.since >= TRACK_MAX_AGE || tracked.contains_key(&entry.aid) {
+                continue;
+            }
+            tracked.insert(entry.aid, entry);
+            imported += 1;
+        }
+        if imported > 0 {
+            Self::persist(&tracked);
+        }
+        imported
+    }
+
+    /// 用最新的稿件列表更新跟踪状态，返回状态发生变化的稿件
+    async fn update(&self, uid: u64, archives: &[ArchiveSummary]) -> Vec<ArchiveStateEvent> {
+        let mut tracked = self.tracked.lock().await;
+        let count = tracked.len();
+        let events = Self::apply(&mut tracked, uid, archives, chrono::Utc::now().timestamp());
+        if !events.is_empty() || tracked.len() != count {
+            Self::persist(&tracked);
+        }
+        events
+    }
+
+    /// 按稿件列表更新 `uid` 的跟踪状态，移除到达终态或超过跟踪时长的稿件
+    fn apply(
+        tracked: &mut HashMap<u64, TrackedArchive>,
+        uid: u64,
+        archives: &[ArchiveSummary],
+        now: i64,
+    ) -> Vec<ArchiveStateEvent> {
+        let mut events = Vec::new();
+        tracked.retain(|aid, entry| {
+            if entry.uid != uid {
+                return true;
+            }
+            let Some(archive) = archives.iter().find(|archive| archive.aid == *aid) else {
+                return now - entry.since < TRACK_MAX_AGE;
+            };
+
+            if entry.state != Some(archive.state) {
+                events.push(ArchiveStateEvent {
+                    uid,
+                    previous_state: entry.state,
+                    previous_stage: entry.stage,
+                    archive: archive.clone(),
+                });
+                entry.state = Some(archive.state);
+                entry.stage = archive.stage;
+            }
+            !archive.stage.is_final() && now - entry.since < TRACK_MAX_AGE
+        });
+        events
+    }
+
+    /// 各账号正在跟踪的稿件
+    async fn tracked_by_uid(&self) -> BTreeMap<u64, HashSet<u64>> {
+        let mut by_uid: BTreeMap<u64, HashSet<u64>> = BTreeMap::new();
+        for entry in self.tracked.lock().await.values() {
+            by_uid.entry(entry.uid).or_default().insert(entry.aid);
+        }
+        by_uid
+    }
+}
+
+/// 定时查询跟踪中稿件的审核状态，状态变化时发送事件
+pub struct ArchivePoller;
+
+impl ArchivePoller {
+    /// 在后台启动稿件状态轮询
+    pub fn spawn(app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = interval(ARCHIVE_POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = Self::poll(&app).await {
+                    warn!("查询稿件审核状态失败: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn poll(app: &AppHandle) -> Result<()> {
+        let app_data = app.state::<AppData>();
+        let tracker = app_data.archive_tracker.clone();
+
+        for (uid, aids) in tracker.tracked_by_uid().await {
+            let Ok(client) = app_data.get_client(uid).await else {
+                continue;
+            };
+            let archives = match Self::fetch_tracked(&client, aids).await {
+                Ok(archives) => archives,
+                Err(e) => {
+                    warn!("获取用户 {} 的稿件列表失败: {}", uid, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = ArchiveCache::merge_and_save(uid, &archives).await {
+                warn!("保存稿件缓存失败: {}", e);
+            }
+
+            for event in tracker.update(uid, &archives).await {
+                info!(
+                    "稿件 av{} 状态变化: {:?} -> {} ({})",
+                    event.archive.aid,
+                    event.previous_state,
+                    event.archive.state,
+                    event.archive.state_desc
+                );
+                if let Some(notification) = event.notification() {
+                    app_data.notifier.notify(notification);
+                }
+                app.emit(ARCHIVE_STATE_CHANGED_EVENT, event)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 翻页获取稿件列表，直到找到全部跟踪的稿件或到达列表末尾
+    ///
+    /// 编辑过的旧稿件可能不在第一页；后续页获取失败时返回已获取的部分。
+    async fn fetch_tracked(
+        client: &MyClient,
+        mut aids: HashSet<u64>,
+    ) -> Result<Vec<ArchiveSummary>, AppError> {
+        let creative = client.creative();
+        let mut archives = Vec::new();
+
+        for pn in 1..=ARCHIVE_SYNC_MAX_PAGES {
+            let page = match creative.archives(pn, ARCHIVE_SYNC_PAGE_SIZE, None).await {
+                Ok(page) => page,
+                Err(e) if pn > 1 => {
+                    warn!("获取第 {} 页稿件列表失败: {}", pn, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            let fetched = page.archives.len();
+            for archive in &page.archives {
+                aids.remove(&archive.aid);
+            }
+            archives.extend(page.archives);
+            if aids.is_empty()
+                || fetched < ARCHIVE_SYNC_PAGE_SIZE as usize
+                || archives.len() >= page.count as usize
+            {
+                break;
+            }
+        }
+        Ok(archives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UID: u64 = 10001;
+    const NOW: i64 = 1_760_000_000;
+
+    fn archive(aid: u64, state: i64, ctime: i64) -> ArchiveSummary {
+        ArchiveSummary {
+            aid,
+            state,
+            stage: ArchiveStage::from_state(state),
+            ctime,
+            ..Default::default()
+        }
+    }
+
+    fn tracked(entries: &[(u64, u64, i64)]) -> HashMap<u64, TrackedArchive> {
+        entries
+            .iter()
+            .map(|&(uid, aid, since)| {
+                (
+                    aid,
+                    TrackedArchive {
+                        uid,
+                        aid,
+                        state: None,
+                        stage: ArchiveStage::Transcoding,
+                        since,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn state_change_emits_event_and_keeps_tracking() {
+        let mut entries = tracked(&[(UID, 1, NOW)]);
+        let reviewing = archive(1, -30, NOW);
+
+        let events =
+            ArchiveTracker::apply(&mut entries, UID, std::slice::from_ref(&reviewing), NOW);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].previous_state, None);
+        assert_eq!(events[0].archive.state, -30);
+        assert_eq!(entries[&1].state, Some(-30));
+
+        // 状态未变化时不重复发送
+        let events = ArchiveTracker::apply(&mut entries, UID, &[reviewing], NOW + 60);
+        assert!(events.is_empty());
+        assert!(entries.contains_key(&1));
+    }
+
+    #[test]
+    fn final_stage_stops_tracking() {
+        let mut entries = tracked(&[(UID, 1, NOW), (UID, 2, NOW)]);
+        let archives = [archive(1, 0, NOW), archive(2, -2, NOW)];
+
+        let events = ArchiveTracker::apply(&mut entries, UID, &archives, NOW);
+        assert_eq!(events.len(), 2);
+        let published = events.iter().find(|e| e.archive.aid == 1).unwrap();
+        assert!(published.notification().is_some());
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn expired_and_other_users_entries() {
+        let mut entries = tracked(&[
+            (UID, 1, NOW - TRACK_MAX_AGE),
+            (UID, 2, NOW - 60),
+            (UID + 1, 3, NOW - TRACK_MAX_AGE),
+        ]);
+
+        // 未出现在列表中的稿件在超过跟踪时长后移除，其他账号的稿件不受影响
+        let events = ArchiveTracker::apply(&mut entries, UID, &[], NOW);
+        assert!(events.is_empty());
+        let mut remaining: Vec<u64> = entries.keys().copied().collect();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![2, 3]);
+
+        // 超过跟踪时长时即使状态变化也不再跟踪
+        let mut entries = tracked(&[(UID, 1, NOW - TRACK_MAX_AGE)]);
+        let events = ArchiveTracker::apply(&mut entries, UID, &[archive(1, -30, NOW)], NOW);
+        assert_eq!(events.len(), 1);
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn merge_updates_by_aid_and_sorts_by_ctime() {
+        let mut cache = ArchiveCache {
+            uid: UID,
+            updated_at: 0,
+            archives: vec![archive(2, -30, NOW), archive(1, 0, NOW - 100)],
+        };
+
+        cache.merge(&[archive(2, 0, NOW), archive(3, -30, NOW + 100)]);
+        let aids: Vec<u64> = cache.archives.iter().map(|a| a.aid).collect();
+        assert_eq!(aids, vec![3, 2, 1]);
+        assert_eq!(cache.archives[1].state, 0);
+        assert_eq!(cache.archives[1].stage, ArchiveStage::Published);
+        assert!(cache.updated_at > 0);
+    }
+}
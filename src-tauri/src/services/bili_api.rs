@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::error::{AppError, BiliApiError, ErrorCategory};
+
+/// 未单独配置的接口的最小请求间隔
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(300);
+
+/// 按接口路径前缀配置的最小请求间隔，先匹配的生效
+const ENDPOINT_INTERVALS: &[(&str, Duration)] = &[
+    ("/x/vu/web/add", Duration::from_secs(3)),
+    ("/x/vu/web/edit", Duration::from_secs(3)),
+    ("/x2/creative/web/season", Duration::from_millis(800)),
+    (
+        "/x/polymer/web-dynamic/v1/mention/search",
+        Duration::from_millis(500),
+    ),
+    ("/x/vupre/web/topic", Duration::from_millis(500)),
+    ("/bfs/", Duration::from_millis(100)),
+];
+
+/// B站投稿接口
+pub const SUBMIT_URL: &str = "https://member.bilibili.com/x/vu/web/add/v3";
+/// B站编辑稿件接口
+pub const EDIT_URL: &str = "https://member.bilibili.com/x/vu/web/edit";
+
+/// 最大重试次数（不含首次请求）
+const MAX_RETRIES: u32 = 3;
+/// 重试的基础等待时间，每次重试翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(800);
+/// 重试等待的最大随机抖动（毫秒）
+const RETRY_JITTER_MS: u64 = 500;
+
+/// 覆盖B站接口地址的环境变量，设置后所有经过 `BiliApi` 的请求都发往该地址
+///
+/// 仅在测试与 `mock-server` 构建中生效，正式版本始终请求B站。
+#[cfg(any(test, feature = "mock-server"))]
+pub const BASE_URL_ENV: &str = "BILIUP_APP_BASE_URL";
+
+#[cfg(any(test, feature = "mock-server"))]
+static BASE_URL_OVERRIDE: std::sync::OnceLock<reqwest::Url> = std::sync::OnceLock::new();
+
+/// 将B站接口重定向到指定地址（如本地模拟服务器），只能设置一次
+#[cfg(feature = "mock-server")]
+pub fn set_base_url_override(url: &str) -> Result<(), AppError> {
+    let url = reqwest::Url::parse(url)
+        .map_err(|e| AppError::Config(format!("接口地址无效: {url} ({e})")))?;
+    BASE_URL_OVERRIDE
+        .set(url)
+        .map_err(|_| AppError::Config("接口地址已设置，不能重复设置".to_string()))
+}
+
+/// 当前生效的接口地址覆盖，优先使用代码中设置的地址，其次为环境变量
+#[cfg(any(test, feature = "mock-server"))]
+fn base_url_override() -> Option<reqwest::Url> {
+    BASE_URL_OVERRIDE.get().cloned().or_else(|| {
+        let value = std::env::var(BASE_URL_ENV).ok()?;
+        reqwest::Url::parse(value.trim())
+            .inspect_err(|e| warn!("{} 无效，已忽略: {}", BASE_URL_ENV, e))
+            .ok()
+    })
+}
+
+/// 单个接口的请求统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointMetrics {
+    pub requests: u64,
+    pub failures: u64,
+    pub retries: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    pub last_error: Option<String>,
+}
+
+/// 带限速、重试与统计的B站接口客户端，每个账号共享一个实例
+#[derive(Clone)]
+pub struct BiliApi {
+    client: Client,
+    /// 设置后请求的协议、域名与端口替换为该地址，路径与参数保持不变
+    #[cfg(any(test, feature = "mock-server"))]
+    base_url: Option<reqwest::Url>,
+    next_slot: Arc<Mutex<HashMap<String, Instant>>>,
+    metrics: Arc<Mutex<HashMap<String, EndpointMetrics>>>,
+}
+
+/// 限速与统计使用的接口标识及最小请求间隔
+///
+/// 命中 `ENDPOINT_INTERVALS` 的接口按前缀归为一组（如所有图片下载共用 `/bfs/`），
+/// 其余接口按域名 + 路径区分。
+fn endpoint_of(url: &reqwest::Url) -> (String, Duration) {
+    let host = url.host_str().unwrap_or_default();
+    let path = url.path();
+    match ENDPOINT_INTERVALS
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+    {
+        Some((prefix, interval)) => (format!("{host}{prefix}"), *interval),
+        None => (format!("{host}{path}"), DEFAULT_MIN_INTERVAL),
+    }
+}
+
+fn retry_delay(attempt: u32) -> Duration {
+    let jitter = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::from(elapsed.subsec_nanos()) % RETRY_JITTER_MS)
+        .unwrap_or(0);
+    RETRY_BASE_DELAY * 2u32.pow(attempt) + Duration::from_millis(jitter)
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::PRECONDITION_FAILED
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
+}
+
+/// 请求失败后是否自动重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryPolicy {
+    /// 按请求方法决定：GET 等幂等请求自动重试，POST 不重试
+    Auto,
+    /// 调用方确认重复提交不会产生副作用（如覆盖式的修改），POST 也自动重试
+    Idempotent,
+    /// 从不重试，用于创建或追加数据的请求
+    Never,
+}
+
+impl RetryPolicy {
+    fn allows(self, method: &Method) -> bool {
+        match self {
+            Self::Auto => method.is_idempotent(),
+            Self::Idempotent => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// `execute` 将无法解析的 HTTP 响应记为 `-状态码`，B站的 -412、-5xx 等错误码也沿用 HTTP 状态码
+fn http_status_of(error: &BiliApiError) -> Option<StatusCode> {
+    let status = u16::try_from(-error.code).ok()?;
+    StatusCode::from_u16(status)
+        .ok()
+        .filter(|status| status.is_client_error() || status.is_server_error())
+}
+
+/// 只重试限流、风控与服务端错误，以及请求未得到响应的网络错误；未收录的错误码不重试
+pub(crate) fn is_retryable_error(error: &AppError) -> bool {
+    match error {
+        AppError::Network(e) => {
+            e.is_timeout() || e.is_connect() || e.status().is_some_and(is_retryable_status)
+        }
+        AppError::Api(e) => {
+            e.category() == ErrorCategory::RateLimited
+                || http_status_of(e).is_some_and(is_retryable_status)
+        }
+        _ => false,
+    }
+}
+
+impl BiliApi {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            #[cfg(any(test, feature = "mock-server"))]
+            base_url: base_url_override(),
+            next_slot: Arc::new(Mutex::new(HashMap::new())),
+            metrics: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 将请求发往指定地址，用于测试时连接本地模拟服务器
+    #[cfg(any(test, feature = "mock-server"))]
+    pub fn with_base_url(mut self, base_url: reqwest::Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    #[cfg(any(test, feature = "mock-server"))]
+    fn redirect(&self, url: &mut reqwest::Url) {
+        let Some(base) = &self.base_url else {
+            return;
+        };
+        if url.set_scheme(base.scheme()).is_err()
+            || url.set_host(base.host_str()).is_err()
+            || url.set_port(base.port()).is_err()
+        {
+            warn!("无法重定向请求 {} 到 {}", url, base);
+        }
+    }
+
+    async fn wait_slot(&self, key: &str, interval: Duration) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot
+                .get(key)
+                .copied()
+                .filter(|slot| *slot > now)
+                .unwrap_or(now);
+            next_slot.insert(key.to_string(), slot + interval);
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+
+    /// GET 请求并检查返回的 `code`
+    pub async fn get(&self, url: &str) -> Result<Value, AppError> {
+        self.execute(|client| client.get(url)).await
+    }
+
+    /// 带查询参数的 GET 请求并检查返回的 `code`
+    pub async fn get_query<Q: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        query: &Q,
+    ) -> Result<Value, AppError> {
+        self.execute(|client| client.get(url).query(query)).await
+    }
+
+    /// 以 JSON 提交的 POST 请求并检查返回的 `code`，不自动重试
+    pub async fn post_json<B: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &B,
+    ) -> Result<Value, AppError> {
+        self.execute(|client| client.post(url).json(body)).await
+    }
+
+    /// 以 JSON 提交且可以安全重复提交的 POST 请求，失败时与 GET 一样自动重试
+    pub async fn post_json_idempotent<B: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &B,
+    ) -> Result<Value, AppError> {
+        self.execute_with(RetryPolicy::Idempotent, |client| {
+            client.post(url).json(body)
+        })
+        .await
+    }
+
+    /// 下载文件内容，同样受限速与重试控制
+    pub async fn download(&self, url: &str) -> Result<bytes::Bytes, AppError> {
+        self.run(
+            RetryPolicy::Auto,
+            |client| client.get(url),
+            |response| async move { Ok(response.error_for_status()?.bytes().await?) },
+        )
+        .await
+    }
+
+    /// 发送请求，解析 JSON 并检查 `code`，幂等请求遇到风控、限流与服务端错误时自动重试
+    pub async fn execute<F>(&self, build: F) -> Result<Value, AppError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        self.execute_with(RetryPolicy::Auto, build).await
+    }
+
+    /// 按指定的重试策略发送请求，解析 JSON 并检查 `code`
+    pub async fn execute_with<F>(&self, policy: RetryPolicy, build: F) -> Result<Value, AppError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        self.run(policy, build, |response| async move {
+            let status = response.status();
+            let res = match response.json::<Value>().await {
+                Ok(res) => res,
+                Err(e) if is_retryable_status(status) => {
+                    debug!("HTTP {} 响应无法解析: {}", status, e);
+                    return Err(AppError::Api(BiliApiError {
+                        code: -i64::from(status.as_u16()),
+                        message: format!("HTTP {status}"),
+                    }));
+                }
+                Err(e) => return Err(e.into()),
+            };
+            AppError::check_response(&res)?;
+            Ok(res)
+        })
+        .await
+    }
+
+    async fn run<T, F, P, Fut>(
+        &self,
+        policy: RetryPolicy,
+        build: F,
+        parse: P,
+    ) -> Result<T, AppError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+        P: Fn(reqwest::Response) -> Fut,
+        Fut: std::future::Future<Output = Result<T, AppError>>,
+    {
+        let request = build(&self.client).build()?;
+        let (key, interval) = endpoint_of(request.url());
+        let max_retries = if policy.allows(request.method()) {
+            MAX_RETRIES
+        } else {
+            0
+        };
+
+        let mut attempt = 0;
+        loop {
+            self.wait_slot(&key, interval).await;
+
+            let started = Instant::now();
+            let request = build(&self.client).build()?;
+            #[cfg(any(test, feature = "mock-server"))]
+            let request = {
+                let mut request = request;
+                self.redirect(request.url_mut());
+                request
+            };
+            let result = match self.client.execute(request).await {
+                Ok(response) => parse(response).await,
+                Err(e) => Err(e.into()),
+            };
+            let elapsed = started.elapsed();
+            let retry =
+                attempt < max_retries && result.as_ref().err().is_some_and(is_retryable_error);
+            self.record(&key, elapsed, result.as_ref().err(), retry)
+                .await;
+
+            match result {
+                Err(e) if retry => {
+                    let delay = retry_delay(attempt);
+                    warn!(
+                        "请求 {} 失败，{}ms 后第 {} 次重试: {}",
+                        key,
+                        delay.as_millis(),
+                        attempt + 1,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => {
+                    debug!("请求 {} 完成，耗时 {}ms", key, elapsed.as_millis());
+                    return result;
+                }
+            }
+        }
+    }
+
+    async fn record(&self, key: &str, elapsed: Duration, error: Option<&AppError>, retry: bool) {
+        let elapsed_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        let mut metrics = self.metrics.lock().await;
+        let entry = metrics.entry(key.to_string()).or_default();
+        entry.requests += 1;
+        entry.total_ms = entry.total_ms.saturating_add(elapsed_ms);
+        entry.max_ms = entry.max_ms.max(elapsed_ms);
+        if retry {
+            entry.retries += 1;
+        }
+        if let Some(error) = error {
+            entry.failures += 1;
+            entry.last_error = Some(error.to_string());
+        }
+    }
+
+    /// 各接口的请求统计
+    pub async fn metrics(&self) -> HashMap<String, EndpointMetrics> {
+        self.metrics.lock().await.clone()
+    }
+}
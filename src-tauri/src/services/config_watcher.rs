@@ -0,0 +1,341 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{Duration, interval};
+use tracing::{debug, info, warn};
+
+use crate::{
+    AppData, MyClient,
+    models::{ConfigRoot, proxy::ProxyRoute},
+    utils::get_config_json_path,
+};
+
+/// 检查配置文件变更的间隔
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 三方合并时整体比较、不逐字段合并的键
+const ATOMIC_KEYS: &[&str] = &["cookie"];
+
+pub const CONFIG_RELOADED_EVENT: &str = "config-reloaded";
+
+/// 外部修改被加载后通知前端的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReloadEvent {
+    /// 本地未保存修改与外部修改冲突的字段路径，冲突时保留本地修改
+    pub conflicts: Vec<String>,
+    /// 登录信息或代理发生变化而重新创建客户端的用户
+    pub reloaded_users: Vec<u64>,
+    /// 被外部删除的用户
+    pub removed_users: Vec<u64>,
+}
+
+/// 轮询 config.json，将外部修改合并到内存中的配置
+///
+/// 按文件内容的摘要判断是否变化，修改时间精度不足时同一时刻的多次写入也能被发现。
+pub struct ConfigWatcher {
+    path: PathBuf,
+    digest: Option<[u8; 32]>,
+    /// 最近一次与磁盘同步的配置，作为三方合并的基准
+    synced: Value,
+}
+
+impl ConfigWatcher {
+    /// 在后台启动配置文件监听
+    pub fn spawn(app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut watcher = match Self::new(&app).await {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    warn!("启动配置文件监听失败: {}", e);
+                    return;
+                }
+            };
+
+            info!("开始监听配置文件变更: {}", watcher.path.display());
+            let mut ticker = interval(CONFIG_POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = watcher.poll(&app).await {
+                    warn!("处理配置文件变更失败: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn new(app: &AppHandle) -> Result<Self> {
+        let path = get_config_json_path()?;
+        let digest = tokio::fs::read(&path)
+            .await
+            .ok()
+            .map(|content| content_digest(&content));
+        let synced = {
+            let app_data = app.state::<AppData>();
+            let config = app_data.config.lock().await;
+            snapshot(&config)?
+        };
+
+        Ok(Self {
+            path,
+            digest,
+            synced,
+        })
+    }
+
+    async fn poll(&mut self, app: &AppHandle) -> Result<()> {
+        let content = match tokio::fs::read(&self.path).await {
+            Ok(content) => content,
+            Err(_) => return Ok(()),
+        };
+        let digest = Some(content_digest(&content));
+        if digest == self.digest {
+            return Ok(());
+        }
+        self.digest = digest;
+
+        let content = String::from_utf8_lossy(&content);
+        let external = match ConfigRoot::from_json_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("外部修改后的配置文件无法解析，忽略本次变更: {}", e);
+                return Ok(());
+            }
+        };
+        let theirs = snapshot(&external)?;
+        if theirs == self.synced {
+            return Ok(());
+        }
+
+        let app_data = app.state::<AppData>();
+        let mut config = app_data.config.lock().await;
+        let ours = snapshot(&config)?;
+
+        // 内容与内存一致，说明是程序自身的保存
+        if ours == theirs {
+            debug!("配置文件已与内存同步");
+            self.synced = theirs;
+            return Ok(());
+        }
+
+        info!("检测到配置文件被外部修改，开始合并");
+        let mut conflicts = Vec::new();
+        let merged = merge_value(
+            "",
+            Some(&self.synced),
+            Some(&ours),
+            Some(&theirs),
+            &mut conflicts,
+        )
+        .ok_or_else(|| anyhow::anyhow!("合并后的配置为空"))?;
+        let merged: ConfigRoot = serde_json::from_value(merged)?;
+        let previous = std::mem::replace(&mut *config, merged);
+
+        let removed_users: Vec<u64> = previous
+            .config
+            .keys()
+            .filter(|uid| !config.config.contains_key(uid))
+            .copied()
+            .collect();
+        let changed_users: Vec<_> = config
+            .config
+            .values()
+            .filter_map(|user_config| {
+                let uid = user_config.user.uid;
+                let proxy = config.proxy_url(uid, ProxyRoute::Api);
+                previous
+                    .config
+                    .get(&uid)
+                    .is_none_or(|old| {
+                        previous.proxy_url(uid, ProxyRoute::Api) != proxy
+                            || serde_json::to_value(&old.user.cookie).ok()
+                                != serde_json::to_value(&user_config.user.cookie).ok()
+                    })
+                    .then(|| (user_config.clone(), proxy))
+            })
+            .collect();
+        let max_curr = config.max_curr;
+        drop(config);
+
+        self.synced = theirs;
+        app_data.upload_service.set_max_concurrent(max_curr).await;
+
+        for uid in &removed_users {
+            app_data.clients.lock().await.remove(uid);
+            info!("用户 {} 已从配置中移除", uid);
+        }
+
+        let mut reloaded_users = Vec::new();
+        for (user_config, proxy) in changed_users {
+            match MyClient::from_user_config(&user_config, proxy.as_deref()).await {
+                Ok(client) => {
+                    info!("用户 {} 的登录信息已更新，重新创建客户端", client.user.uid);
+                    reloaded_users.push(client.user.uid);
+                    app_data
+                        .clients
+                        .lock()
+                        .await
+                        .insert(client.user.uid, client);
+                }
+                Err(e) => {
+                    warn!("重新创建用户 {} 的客户端失败: {}", user_config.user.uid, e);
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            warn!("以下配置项存在冲突，已保留本地修改: {:?}", conflicts);
+        }
+
+        app.emit(
+            CONFIG_RELOADED_EVENT,
+            ConfigReloadEvent {
+                conflicts,
+                reloaded_users,
+                removed_users,
+            },
+        )?;
+        Ok(())
+    }
+}
+
+fn content_digest(content: &[u8]) -> [u8; 32] {
+    Sha256::digest(content).into()
+}
+
+/// 规范化后的配置快照，用于比较与合并
+fn snapshot(config: &ConfigRoot) -> Result<Value> {
+    let mut config = config.clone();
+    let content = config.serialize_normalized()?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// 三方合并：只有一方修改时采用修改方，双方都修改对象时逐键合并，否则记录冲突并保留本地值
+fn merge_value(
+    path: &str,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+
+    let key = path.rsplit('.').next().unwrap_or(path);
+    if let (Some(Value::Object(ours_map)), Some(Value::Object(theirs_map))) = (ours, theirs)
+        && !ATOMIC_KEYS.contains(&key)
+    {
+        let empty = Map::new();
+        let base_map = base.and_then(Value::as_object).unwrap_or(&empty);
+        let keys: BTreeSet<&String> = base_map
+            .keys()
+            .chain(ours_map.keys())
+            .chain(theirs_map.keys())
+            .collect();
+
+        let mut merged = Map::new();
+        for key in keys {
+            let child_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            if let Some(value) = merge_value(
+                &child_path,
+                base_map.get(key),
+                ours_map.get(key),
+                theirs_map.get(key),
+                conflicts,
+            ) {
+                merged.insert(key.clone(), value);
+            }
+        }
+        return Some(Value::Object(merged));
+    }
+
+    conflicts.push(path.to_string());
+    ours.cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn merge(base: Value, ours: Value, theirs: Value) -> (Value, Vec<String>) {
+        let mut conflicts = Vec::new();
+        let merged = merge_value("", Some(&base), Some(&ours), Some(&theirs), &mut conflicts);
+        (merged.unwrap(), conflicts)
+    }
+
+    #[test]
+    fn local_only_change_is_kept() {
+        let (merged, conflicts) = merge(
+            json!({ "max_curr": 1, "line": "bda2" }),
+            json!({ "max_curr": 2, "line": "bda2" }),
+            json!({ "max_curr": 1, "line": "bda2" }),
+        );
+        assert_eq!(merged, json!({ "max_curr": 2, "line": "bda2" }));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn remote_only_change_is_applied() {
+        let (merged, conflicts) = merge(
+            json!({ "max_curr": 1, "templates": { "a": { "title": "旧" } } }),
+            json!({ "max_curr": 2, "templates": { "a": { "title": "旧" } } }),
+            json!({ "max_curr": 1, "templates": { "a": { "title": "新" }, "b": {} } }),
+        );
+        assert_eq!(
+            merged,
+            json!({ "max_curr": 2, "templates": { "a": { "title": "新" }, "b": {} } })
+        );
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn conflict_keeps_local_value() {
+        let (merged, conflicts) = merge(
+            json!({ "templates": { "a": { "title": "原", "tag": "x" } } }),
+            json!({ "templates": { "a": { "title": "本地", "tag": "x" } } }),
+            json!({ "templates": { "a": { "title": "外部", "tag": "y" } } }),
+        );
+        assert_eq!(
+            merged,
+            json!({ "templates": { "a": { "title": "本地", "tag": "y" } } })
+        );
+        assert_eq!(conflicts, vec!["templates.a.title".to_string()]);
+    }
+
+    #[test]
+    fn remote_cookie_is_replaced_as_a_whole() {
+        let (merged, conflicts) = merge(
+            json!({ "user": { "cookie": { "sessdata": "a", "csrf": "a" } } }),
+            json!({ "user": { "cookie": { "sessdata": "a", "csrf": "a" } } }),
+            json!({ "user": { "cookie": { "sessdata": "b" } } }),
+        );
+        assert_eq!(merged, json!({ "user": { "cookie": { "sessdata": "b" } } }));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn concurrent_cookie_changes_are_not_mixed() {
+        let (merged, conflicts) = merge(
+            json!({ "cookie": { "sessdata": "a", "csrf": "a" } }),
+            json!({ "cookie": { "sessdata": "b", "csrf": "a" } }),
+            json!({ "cookie": { "sessdata": "a", "csrf": "c" } }),
+        );
+        assert_eq!(
+            merged,
+            json!({ "cookie": { "sessdata": "b", "csrf": "a" } })
+        );
+        assert_eq!(conflicts, vec!["cookie".to_string()]);
+    }
+}
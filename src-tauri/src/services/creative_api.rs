@@ -0,0 +1,1081 @@
+use biliup::uploader::bilibili::Vid;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::{
+    MyClient,
+    error::AppError,
+    models::{
+        TemplateConfig,
+        user_config::{Credit, Staff},
+    },
+    services::bili_api::{BiliApi, EDIT_URL, RetryPolicy, SUBMIT_URL},
+    utils::subtitle::Bcc,
+};
+
+/// 创作中心接口的域名
+pub const MEMBER_BASE_URL: &str = "https://member.bilibili.com";
+/// 保存并提交分P字幕
+const SUBTITLE_SAVE_URL: &str = "https://api.bilibili.com/x/v2/dm/subtitle/draft/save";
+
+/// 新版分区
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PartitionV2 {
+    #[serde(default)]
+    pub id: u32,
+    #[serde(default)]
+    pub name: String,
+    /// 其余字段原样透传给前端
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TypeListV2 {
+    #[serde(default)]
+    type_list: Vec<PartitionV2>,
+}
+
+/// 活动话题
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Topic {
+    pub topic_id: u64,
+    pub topic_name: String,
+    pub description: String,
+    pub mission_id: u64,
+    pub activity_text: String,
+    pub activity_description: String,
+    pub arc_play_vv: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RecommendTag {
+    tag: String,
+}
+
+/// 投稿活动
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Activity {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    /// 其余字段原样透传给前端
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TopicList {
+    #[serde(default)]
+    topics: Vec<Topic>,
+}
+
+/// 话题搜索结果，字段名与话题列表不同
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TopicSearchItem {
+    id: u64,
+    name: String,
+    description: String,
+    mission_id: u64,
+    activity_text: String,
+    activity_description: String,
+    arc_play_vv: u64,
+}
+
+impl From<TopicSearchItem> for Topic {
+    fn from(item: TopicSearchItem) -> Self {
+        Self {
+            topic_id: item.id,
+            topic_name: item.name,
+            description: item.description,
+            mission_id: item.mission_id,
+            activity_text: item.activity_text,
+            activity_description: item.activity_description,
+            arc_play_vv: item.arc_play_vv,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TopicSearch {
+    #[serde(default)]
+    result: TopicSearchResult,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TopicSearchResult {
+    #[serde(default)]
+    topics: Vec<TopicSearchItem>,
+}
+
+/// 稿件在审核流程中所处的阶段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveStage {
+    /// 上传、转码或分发中
+    Transcoding,
+    /// 等待审核或审核中
+    #[default]
+    Pending,
+    /// 已开放浏览
+    Published,
+    /// 被打回、锁定或转码失败等，需要处理
+    Rejected,
+    /// 未收录的状态码，继续跟踪直到状态明确
+    Unknown,
+}
+
+impl ArchiveStage {
+    /// 按B站稿件状态码划分阶段
+    pub fn from_state(state: i64) -> Self {
+        match state {
+            0 | 1 => Self::Published,
+            -9 | -15 | -20 => Self::Transcoding,
+            -1 | -6 | -7 | -8 | -10 | -13 | -30 | -40 => Self::Pending,
+            -2 | -3 | -4 | -5 | -11 | -12 | -16 => Self::Rejected,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// 是否已到达终态，不再需要跟踪
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Published | Self::Rejected)
+    }
+}
+
+/// 稿件列表中的稿件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveSummary {
+    pub aid: u64,
+    pub bvid: String,
+    pub title: String,
+    pub cover: String,
+    pub state: i64,
+    pub state_desc: String,
+    pub stage: ArchiveStage,
+    /// 稿件及各分P的退回原因
+    pub reject_reasons: Vec<String>,
+    /// 投稿时间（秒级时间戳）
+    pub ctime: i64,
+    /// 发布时间（秒级时间戳）
+    pub ptime: i64,
+    pub duration: u64,
+    pub view: u64,
+    pub danmaku: u64,
+    pub reply: u64,
+    pub like: u64,
+    pub coin: u64,
+    pub favorite: u64,
+    pub share: u64,
+}
+
+/// 稿件列表的一页
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchivePage {
+    pub archives: Vec<ArchiveSummary>,
+    pub pn: u32,
+    pub ps: u32,
+    /// 符合条件的稿件总数
+    pub count: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ArchiveListData {
+    #[serde(default)]
+    arc_audits: Option<Vec<ArcAudit>>,
+    #[serde(default)]
+    page: ArchiveListPage,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveListPage {
+    count: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArcAudit {
+    #[serde(rename = "Archive")]
+    archive: ArcAuditArchive,
+    stat: ArcAuditStat,
+    #[serde(rename = "Videos")]
+    videos: Option<Vec<ArcAuditVideo>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArcAuditArchive {
+    aid: u64,
+    bvid: String,
+    title: String,
+    cover: String,
+    state: i64,
+    state_desc: String,
+    reject_reason: String,
+    ctime: i64,
+    ptime: i64,
+    duration: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArcAuditStat {
+    view: u64,
+    danmaku: u64,
+    reply: u64,
+    like: u64,
+    coin: u64,
+    favorite: u64,
+    share: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArcAuditVideo {
+    title: String,
+    reject_reason: String,
+}
+
+impl From<ArcAudit> for ArchiveSummary {
+    fn from(audit: ArcAudit) -> Self {
+        let ArcAudit {
+            archive,
+            stat,
+            videos,
+        } = audit;
+
+        let mut reject_reasons = Vec::new();
+        if !archive.reject_reason.trim().is_empty() {
+            reject_reasons.push(archive.reject_reason.trim().to_string());
+        }
+        for video in videos.unwrap_or_default() {
+            let reason = video.reject_reason.trim();
+            if !reason.is_empty() {
+                reject_reasons.push(format!("{}: {}", video.title, reason));
+            }
+        }
+
+        Self {
+            aid: archive.aid,
+            bvid: archive.bvid,
+            title: archive.title,
+            cover: archive.cover,
+            state: archive.state,
+            state_desc: archive.state_desc,
+            stage: ArchiveStage::from_state(archive.state),
+            reject_reasons,
+            ctime: archive.ctime,
+            ptime: archive.ptime,
+            duration: archive.duration,
+            view: stat.view,
+            danmaku: stat.danmaku,
+            reply: stat.reply,
+            like: stat.like,
+            coin: stat.coin,
+            favorite: stat.favorite,
+            share: stat.share,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct IdField {
+    id: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveRights {
+    is_360: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveStaff {
+    apply_title: Option<String>,
+    title: Option<String>,
+    apply_staff_mid: Option<u64>,
+    mid: Option<u64>,
+}
+
+impl ArchiveStaff {
+    fn into_staff(self) -> Option<Staff> {
+        let title = self.apply_title.or(self.title).unwrap_or_default();
+        let mid = self.apply_staff_mid.or(self.mid).unwrap_or(0);
+        (!title.is_empty() && mid != 0).then_some(Staff {
+            title,
+            mid,
+            is_del: 0,
+        })
+    }
+}
+
+/// 稿件 web 接口中的稿件信息
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveInfo {
+    desc: Option<String>,
+    human_type2: Option<IdField>,
+    state: Option<i64>,
+    state_desc: Option<String>,
+    desc_v2: Option<Value>,
+    dynamic: Option<String>,
+    mission_id: Option<u32>,
+    topic_id: Option<u32>,
+    topic_name: Option<String>,
+    rights: Option<ArchiveRights>,
+    staffs: Option<Vec<ArchiveStaff>>,
+}
+
+/// 稿件 web 接口的返回数据，部分字段可能在 `archive` 内或外层
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveViewData {
+    archive: Option<ArchiveInfo>,
+    state_desc: Option<String>,
+    topic_name: Option<String>,
+    rights: Option<ArchiveRights>,
+    staffs: Option<Vec<ArchiveStaff>>,
+    staff: Option<Vec<ArchiveStaff>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ArchiveVideos {
+    #[serde(default)]
+    archive: ArchiveVideosArchive,
+    #[serde(default)]
+    videos: Option<Vec<ArchiveVideo>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveVideosArchive {
+    bvid: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArchiveVideo {
+    cid: u64,
+}
+
+/// 稿件的分P信息
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveParts {
+    pub bvid: Option<String>,
+    /// 各分P的 cid，刚投稿的稿件在转码前可能为 0
+    pub cids: Vec<u64>,
+}
+
+/// 稿件 web 接口中补充的投稿信息
+#[derive(Debug, Default)]
+pub struct ArchiveView {
+    archive: ArchiveInfo,
+    outer: ArchiveViewData,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|s| !s.is_empty())
+}
+
+/// 合并相邻的纯文本片段
+pub fn normalize_desc_v2_tokens(tokens: Vec<Credit>) -> Vec<Credit> {
+    let mut normalized: Vec<Credit> = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+        if let Some(last) = normalized.last_mut()
+            && last.r#type == 1
+            && token.r#type == 1
+        {
+            last.raw_text.push_str(&token.raw_text);
+            continue;
+        }
+
+        normalized.push(token);
+    }
+
+    normalized
+}
+
+impl ArchiveView {
+    /// 解析接口的 `data`，没有 `archive` 字段时以外层作为稿件信息
+    pub fn from_data(data: Value) -> Result<Self, AppError> {
+        let mut outer: ArchiveViewData = parse(data.clone(), "稿件信息")?;
+        let archive = match outer.archive.take() {
+            Some(archive) => archive,
+            None => parse(data, "稿件信息")?,
+        };
+        Ok(Self { archive, outer })
+    }
+
+    /// 用 web 接口的数据补充创作者接口缺少的字段
+    pub fn apply_to(self, template: &mut TemplateConfig) {
+        let Self { archive, outer } = self;
+
+        if let Some(desc) = non_empty(archive.desc) {
+            template.desc = desc;
+        }
+        if let Some(id) = archive.human_type2.and_then(|t| t.id) {
+            template.tid_v2 = id as u32;
+        }
+        template.state = archive.state;
+        template.state_desc = archive.state_desc.or(outer.state_desc);
+        if let Some(desc_v2) = archive.desc_v2.filter(|v| !v.is_null()) {
+            match serde_json::from_value::<Vec<Credit>>(desc_v2) {
+                Ok(credits) => {
+                    let cleaned_credits = normalize_desc_v2_tokens(credits);
+                    if !cleaned_credits.is_empty() {
+                        template.desc_v2 = Some(cleaned_credits);
+                    }
+                }
+                Err(e) => warn!("解析 desc_v2 失败: {}", e),
+            }
+        }
+        if let Some(dynamic) = non_empty(archive.dynamic) {
+            template.dynamic = dynamic;
+        }
+        // mission_id：活动 ID
+        if let Some(mission_id) = archive.mission_id {
+            template.mission_id = Some(mission_id);
+        }
+        if let Some(topic_id) = archive.topic_id {
+            template.topic_id = Some(topic_id);
+        }
+        if let Some(topic_name) = non_empty(archive.topic_name).or(non_empty(outer.topic_name)) {
+            template.topic_name = Some(topic_name);
+        }
+        if let Some(rights) = archive.rights.or(outer.rights) {
+            template.is_360 = rights.is_360.unwrap_or(-1);
+        }
+        if let Some(staffs) = outer.staffs.or(archive.staffs).or(outer.staff) {
+            let staff_vec: Vec<Staff> = staffs
+                .into_iter()
+                .filter_map(ArchiveStaff::into_staff)
+                .collect();
+            if !staff_vec.is_empty() {
+                template.staff = Some(staff_vec);
+            }
+        }
+    }
+}
+
+/// 将接口数据解析为指定类型，`null` 视为默认值
+fn parse<T: DeserializeOwned + Default>(data: Value, what: &str) -> Result<T, AppError> {
+    if data.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(data)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("解析{what}失败: {e}")))
+}
+
+/// 检查响应的 `code` 后取出 `data` 并解析
+pub(super) fn parse_data<T: DeserializeOwned + Default>(
+    mut res: Value,
+    what: &str,
+) -> Result<T, AppError> {
+    AppError::check_response(&res)?;
+    parse(res["data"].take(), what)
+}
+
+/// 创作中心（member.bilibili.com）接口
+pub struct CreativeApi<'a> {
+    pub(super) client: &'a MyClient,
+}
+
+/// 提交新稿件，返回响应中的 `data`（含 aid 与 bvid）
+///
+/// 重复提交会生成多个稿件，不自动重试。
+pub(crate) async fn submit_archive(
+    api: &BiliApi,
+    csrf: &str,
+    studio: &impl Serialize,
+) -> Result<Value, AppError> {
+    let mut url = reqwest::Url::parse(SUBMIT_URL)
+        .map_err(|e| AppError::Custom(format!("接口地址无效: {e}")))?;
+    url.query_pairs_mut()
+        .append_pair("ts", &chrono::Utc::now().timestamp_millis().to_string())
+        .append_pair("csrf", csrf);
+    let mut res = api.post_json(url.as_str(), studio).await?;
+    Ok(res["data"].take())
+}
+
+/// 编辑已有稿件，返回完整响应
+pub(crate) async fn edit_archive(
+    api: &BiliApi,
+    csrf: &str,
+    studio: &impl Serialize,
+) -> Result<Value, AppError> {
+    let mut url = reqwest::Url::parse(EDIT_URL)
+        .map_err(|e| AppError::Custom(format!("接口地址无效: {e}")))?;
+    url.query_pairs_mut().append_pair("csrf", csrf);
+    api.post_json(url.as_str(), studio).await
+}
+
+impl MyClient {
+    pub fn creative(&self) -> CreativeApi<'_> {
+        CreativeApi { client: self }
+    }
+}
+
+impl CreativeApi<'_> {
+    /// 拼接接口地址，附加时间戳，`with_csrf` 为 true 时附加 csrf
+    pub(super) fn url(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        with_csrf: bool,
+    ) -> Result<String, AppError> {
+        let mut url = reqwest::Url::parse(MEMBER_BASE_URL)
+            .and_then(|base| base.join(path))
+            .map_err(|e| AppError::Custom(format!("接口地址无效: {e}")))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            for (key, value) in query {
+                pairs.append_pair(key, value);
+            }
+            pairs.append_pair("t", &chrono::Utc::now().timestamp_millis().to_string());
+            if with_csrf {
+                pairs.append_pair("csrf", &self.client.get_csrf()?);
+            }
+        }
+        Ok(url.into())
+    }
+
+    /// 提交新稿件，返回响应中的 `data`（含 aid 与 bvid）
+    pub async fn submit(&self, studio: &impl Serialize) -> Result<Value, AppError> {
+        submit_archive(&self.client.api, &self.client.get_csrf()?, studio).await
+    }
+
+    /// 编辑已有稿件，返回完整响应
+    pub async fn edit(&self, studio: &impl Serialize) -> Result<Value, AppError> {
+        edit_archive(&self.client.api, &self.client.get_csrf()?, studio).await
+    }
+
+    /// 投稿所需的分区、联合投稿配置等，不含新版分区列表
+    pub async fn archive_pre(&self) -> Result<Value, AppError> {
+        let url = self.url("/x/vupre/web/archive/pre", &[], false)?;
+        let res = self.client.api.get(&url).await?;
+        parse_data(res, "投稿配置")
+    }
+
+    /// 新版分区列表
+    pub async fn type_list_v2(&self) -> Result<Vec<PartitionV2>, AppError> {
+        let url = self.url("/x/vupre/web/archive/human/type2/list", &[], false)?;
+        let res = self.client.api.get(&url).await?;
+        Ok(parse_data::<TypeListV2>(res, "新版分区列表")?.type_list)
+    }
+
+    /// 可参与的活动话题
+    pub async fn topics(&self) -> Result<Vec<Topic>, AppError> {
+        let url = self.url(
+            "/x/vupre/web/topic/type",
+            &[("pn", "0".to_string()), ("ps", "999".to_string())],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        Ok(parse_data::<TopicList>(res, "话题列表")?.topics)
+    }
+
+    /// 可参与的投稿活动
+    pub async fn activities(&self) -> Result<Vec<Activity>, AppError> {
+        let url = self.url("/x/web/activity/videoall", &[], false)?;
+        let res = self.client.api.get(&url).await?;
+        parse_data(res, "活动列表")
+    }
+
+    /// 按标题、分区与简介推荐的标签
+    pub async fn recommend_tags(
+        &self,
+        title: &str,
+        tid: u32,
+        desc: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let url = self.url(
+            "/x/vupre/web/tag/recommend",
+            &[
+                ("upload_id", String::new()),
+                ("subtype_id", tid.to_string()),
+                ("title", title.to_string()),
+                ("filename", String::new()),
+                ("description", desc.to_string()),
+                ("cover_url", String::new()),
+            ],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        let tags: Vec<RecommendTag> = parse_data(res, "推荐标签")?;
+        Ok(tags.into_iter().map(|tag| tag.tag).collect())
+    }
+
+    /// 按关键词搜索话题
+    pub async fn search_topics(&self, keywords: &str) -> Result<Vec<Topic>, AppError> {
+        let url = self.url(
+            "/x/vupre/web/topic/search",
+            &[
+                ("keywords", keywords.to_string()),
+                ("page_size", "50".to_string()),
+                ("offset", "0".to_string()),
+            ],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        let search: TopicSearch = parse_data(res, "话题搜索结果")?;
+        Ok(search.result.topics.into_iter().map(Topic::from).collect())
+    }
+
+    /// 分页获取稿件列表，`status` 为 B站的筛选条件，如 `is_pubing,pubed,not_pubed`
+    pub async fn archives(
+        &self,
+        pn: u32,
+        ps: u32,
+        status: Option<&str>,
+    ) -> Result<ArchivePage, AppError> {
+        let url = self.url(
+            "/x/web/archives",
+            &[
+                (
+                    "status",
+                    status.unwrap_or("is_pubing,pubed,not_pubed").to_string(),
+                ),
+                ("pn", pn.to_string()),
+                ("ps", ps.to_string()),
+                ("coop", "1".to_string()),
+                ("interactive", "1".to_string()),
+            ],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        let data: ArchiveListData = parse_data(res, "稿件列表")?;
+        Ok(ArchivePage {
+            archives: data
+                .arc_audits
+                .unwrap_or_default()
+                .into_iter()
+                .map(ArchiveSummary::from)
+                .collect(),
+            pn,
+            ps,
+            count: data.page.count,
+        })
+    }
+
+    /// 稿件各分P的 cid，刚投稿的稿件在转码前可能为 0
+    pub async fn archive_cids(&self, aid: u64) -> Result<Vec<u64>, AppError> {
+        Ok(self.archive_parts(aid).await?.cids)
+    }
+
+    /// 稿件的 BV 号与各分P的 cid
+    pub async fn archive_parts(&self, aid: u64) -> Result<ArchiveParts, AppError> {
+        let url = self.url("/x/web/archive/videos", &[("aid", aid.to_string())], false)?;
+        let res = self.client.api.get(&url).await?;
+        let data: ArchiveVideos = parse_data(res, "稿件分P列表")?;
+        Ok(ArchiveParts {
+            bvid: non_empty(Some(data.archive.bvid)),
+            cids: data
+                .videos
+                .unwrap_or_default()
+                .into_iter()
+                .map(|video| video.cid)
+                .collect(),
+        })
+    }
+
+    /// 为分P上传字幕并提交审核，`lan` 为语言代码，如 `zh-CN`
+    ///
+    /// 每次提交都会生成新的字幕草稿，不自动重试
+    pub async fn save_subtitle(
+        &self,
+        cid: u64,
+        bvid: Option<&str>,
+        lan: &str,
+        bcc: &Bcc,
+    ) -> Result<u64, AppError> {
+        let url = self.url(SUBTITLE_SAVE_URL, &[], false)?;
+        let data = serde_json::to_string(bcc).map_err(|e| AppError::Internal(e.into()))?;
+        let mut form = vec![
+            ("type", "1".to_string()),
+            ("oid", cid.to_string()),
+            ("lan", lan.to_string()),
+            ("data", data),
+            ("submit", "true".to_string()),
+            ("sign", "false".to_string()),
+            ("csrf", self.client.get_csrf()?),
+        ];
+        if let Some(bvid) = bvid {
+            form.push(("bvid", bvid.to_string()));
+        }
+        let res = self
+            .client
+            .api
+            .execute_with(RetryPolicy::Never, |client| client.post(&url).form(&form))
+            .await?;
+        Ok(res["data"]["subtitle_id"].as_u64().unwrap_or_default())
+    }
+
+    /// 稿件 web 接口数据，用于补充创作者接口缺少的字段
+    pub async fn archive_view(&self, vid: &Vid) -> Result<ArchiveView, AppError> {
+        let vid = vid.to_string();
+        let (key, value) = vid
+            .split_once('=')
+            .ok_or_else(|| AppError::Custom(format!("视频 ID 无效: {vid}")))?;
+        let url = self.url(
+            "/x/vupre/web/archive/view",
+            &[("topic_grey", "1".to_string()), (key, value.to_string())],
+            false,
+        )?;
+        let mut res = self.client.api.get(&url).await?;
+        ArchiveView::from_data(res["data"].take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{error::ErrorCategory, services::mock_server::MockServer};
+
+    async fn mock_api() -> (MockServer, BiliApi) {
+        let server = MockServer::start().await.unwrap();
+        let api = BiliApi::new(reqwest::Client::new()).with_base_url(server.url().parse().unwrap());
+        (server, api)
+    }
+
+    /// 录制的 /x/vupre/web/archive/pre 响应，省略了与解析无关的字段
+    fn archive_pre_response() -> Value {
+        json!({
+            "code": 0,
+            "message": "0",
+            "ttl": 1,
+            "data": {
+                "typelist": [{
+                    "id": 160,
+                    "parent": 0,
+                    "name": "生活",
+                    "desc": "",
+                    "children": [{
+                        "id": 138,
+                        "parent": 160,
+                        "name": "搞笑",
+                        "desc": "搞笑的、轻松有趣的、具有独特笑点或娱乐精神的作品",
+                        "intro_original": "",
+                        "notice": "",
+                        "show": true
+                    }],
+                    "show": true
+                }],
+                "activities": [],
+                "myinfo": { "mid": 10001, "uname": "测试", "level": 5 },
+                "videojam": { "level": 1, "state": 0 },
+                "staff_conf": { "max_staff": 10 }
+            }
+        })
+    }
+
+    #[test]
+    fn parses_archive_pre() {
+        let data: Value = parse_data(archive_pre_response(), "投稿配置").unwrap();
+        let typelist: Vec<PartitionV2> = serde_json::from_value(data["typelist"].clone()).unwrap();
+        assert_eq!(typelist[0].id, 160);
+        assert_eq!(typelist[0].name, "生活");
+        assert_eq!(typelist[0].extra["children"][0]["id"], json!(138));
+        assert_eq!(data["myinfo"]["mid"], json!(10001));
+    }
+
+    #[test]
+    fn parses_archives() {
+        let res = json!({
+            "code": 0,
+            "message": "0",
+            "ttl": 1,
+            "data": {
+                "class": { "pubed": 1, "not_pubed": 1, "is_pubing": 0 },
+                "arc_audits": [{
+                    "Archive": {
+                        "aid": 100002,
+                        "bvid": "BV1xx411c7mD",
+                        "mid": 10001,
+                        "tid": 138,
+                        "title": "被退回的稿件",
+                        "cover": "https://i0.hdslb.com/bfs/archive/2.jpg",
+                        "reject_reason": "标题含有不适宜内容 ",
+                        "duration": 125,
+                        "copyright": 1,
+                        "state": -2,
+                        "state_desc": "已退回",
+                        "ptime": 0,
+                        "ctime": 1760000100,
+                        "mtime": 1760000200
+                    },
+                    "stat": {
+                        "aid": 100002,
+                        "view": 12,
+                        "danmaku": 1,
+                        "reply": 2,
+                        "favorite": 3,
+                        "coin": 4,
+                        "share": 5,
+                        "like": 6
+                    },
+                    "Videos": [
+                        { "cid": 200002, "index": 1, "title": "P1", "reject_reason": "画面违规" },
+                        { "cid": 200003, "index": 2, "title": "P2", "reject_reason": "" }
+                    ]
+                }, {
+                    "Archive": {
+                        "aid": 100001,
+                        "bvid": "BV1xx411c7mE",
+                        "title": "已发布的稿件",
+                        "state": 0,
+                        "state_desc": "开放浏览",
+                        "ptime": 1759990000,
+                        "ctime": 1759980000
+                    },
+                    "stat": { "view": 100 },
+                    "Videos": null
+                }],
+                "page": { "pn": 1, "ps": 10, "count": 2 }
+            }
+        });
+
+        let data: ArchiveListData = parse_data(res, "稿件列表").unwrap();
+        assert_eq!(data.page.count, 2);
+        let archives: Vec<ArchiveSummary> = data
+            .arc_audits
+            .unwrap()
+            .into_iter()
+            .map(ArchiveSummary::from)
+            .collect();
+
+        let rejected = &archives[0];
+        assert_eq!(rejected.aid, 100002);
+        assert_eq!(rejected.stage, ArchiveStage::Rejected);
+        assert_eq!(
+            rejected.reject_reasons,
+            vec!["标题含有不适宜内容".to_string(), "P1: 画面违规".to_string()]
+        );
+        assert_eq!(rejected.like, 6);
+        assert_eq!(rejected.duration, 125);
+
+        let published = &archives[1];
+        assert_eq!(published.stage, ArchiveStage::Published);
+        assert!(published.reject_reasons.is_empty());
+        assert_eq!(published.view, 100);
+    }
+
+    #[test]
+    fn parses_topics() {
+        let res = json!({
+            "code": 0,
+            "message": "0",
+            "ttl": 1,
+            "data": {
+                "topics": [{
+                    "topic_id": 1001,
+                    "topic_name": "日常vlog",
+                    "description": "记录生活",
+                    "mission_id": 2002,
+                    "activity_text": "活动进行中",
+                    "activity_description": "参与活动赢奖励",
+                    "arc_play_vv": 123456,
+                    "tag_id": 9
+                }, {
+                    "topic_id": 1002,
+                    "topic_name": "没有活动的话题"
+                }],
+                "maxpage": 1
+            }
+        });
+
+        let topics = parse_data::<TopicList>(res, "话题列表").unwrap().topics;
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].topic_id, 1001);
+        assert_eq!(topics[0].mission_id, 2002);
+        assert_eq!(topics[0].arc_play_vv, 123456);
+        assert_eq!(topics[1].mission_id, 0);
+        assert!(topics[1].description.is_empty());
+    }
+
+    #[test]
+    fn parses_archive_view() {
+        let res = json!({
+            "code": 0,
+            "message": "0",
+            "ttl": 1,
+            "data": {
+                "archive": {
+                    "aid": 100001,
+                    "bvid": "BV1xx411c7mE",
+                    "title": "已发布的稿件",
+                    "desc": "简介 @测试好友 ",
+                    "desc_v2": [
+                        { "raw_text": "简介 ", "type": 1, "biz_id": "" },
+                        { "raw_text": "测试好友", "type": 2, "biz_id": "10002" },
+                        { "raw_text": " ", "type": 1, "biz_id": "" }
+                    ],
+                    "human_type2": { "id": 2021, "name": "日常" },
+                    "dynamic": "发布动态",
+                    "mission_id": 2002,
+                    "topic_id": 1001,
+                    "topic_name": "",
+                    "state": 0,
+                    "state_desc": "开放浏览",
+                    "rights": { "is_360": 0 }
+                },
+                "topic_name": "日常vlog",
+                "staffs": [
+                    { "apply_title": "剪辑", "apply_staff_mid": 10003 },
+                    { "title": "", "mid": 10004 }
+                ]
+            }
+        });
+
+        let view = ArchiveView::from_data(parse_data(res, "稿件信息").unwrap()).unwrap();
+        let mut template: TemplateConfig = serde_json::from_value(json!({})).unwrap();
+        view.apply_to(&mut template);
+
+        assert_eq!(template.desc, "简介 @测试好友 ");
+        assert_eq!(template.tid_v2, 2021);
+        assert_eq!(template.dynamic, "发布动态");
+        assert_eq!(template.mission_id, Some(2002));
+        assert_eq!(template.topic_id, Some(1001));
+        assert_eq!(template.topic_name.as_deref(), Some("日常vlog"));
+        assert_eq!(template.is_360, 0);
+        assert_eq!(template.state, Some(0));
+
+        let desc_v2 = template.desc_v2.unwrap();
+        assert_eq!(desc_v2.len(), 3);
+        assert_eq!(desc_v2[1].biz_id, "10002");
+
+        let staff = template.staff.unwrap();
+        assert_eq!(staff.len(), 1);
+        assert_eq!(staff[0].mid, 10003);
+    }
+
+    #[test]
+    fn archive_view_without_archive_field() {
+        let view = ArchiveView::from_data(json!({
+            "desc": "外层简介",
+            "human_type2": { "id": 2021 },
+            "state": -30
+        }))
+        .unwrap();
+        let mut template: TemplateConfig = serde_json::from_value(json!({})).unwrap();
+        view.apply_to(&mut template);
+
+        assert_eq!(template.desc, "外层简介");
+        assert_eq!(template.tid_v2, 2021);
+        assert_eq!(template.state, Some(-30));
+    }
+
+    #[test]
+    fn parse_data_rejects_error_envelope() {
+        let res = json!({ "code": -101, "message": "账号未登录", "ttl": 1, "data": null });
+        let error = parse_data::<TopicList>(res, "话题列表").unwrap_err();
+        match error {
+            AppError::Api(e) => {
+                assert_eq!(e.code, -101);
+                assert_eq!(e.category(), ErrorCategory::AuthExpired);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_data_reports_malformed_data() {
+        let res = json!({ "code": 0, "message": "0", "data": { "topics": "not a list" } });
+        let error = parse_data::<TopicList>(res, "话题列表").unwrap_err();
+        assert!(matches!(error, AppError::Internal(_)));
+        assert!(error.to_string().contains("话题列表"));
+    }
+
+    #[test]
+    fn parse_data_treats_null_data_as_default() {
+        let res = json!({ "code": 0, "message": "0", "data": null });
+        let topics = parse_data::<TopicList>(res, "话题列表").unwrap().topics;
+        assert!(topics.is_empty());
+    }
+
+    #[tokio::test]
+    async fn submit_posts_studio_with_csrf() {
+        let (server, api) = mock_api().await;
+        let studio = json!({ "title": "测试稿件", "tid": 138 });
+
+        let data = submit_archive(&api, "token", &studio).await.unwrap();
+        assert_eq!(data["aid"], json!(100001));
+        assert_eq!(data["bvid"], json!("BV1mock000001"));
+
+        let requests = server.requests().await;
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/x/vu/web/add/v3");
+        assert!(
+            requests[0]
+                .query
+                .split('&')
+                .any(|pair| pair == "csrf=token")
+        );
+        assert!(
+            requests[0]
+                .query
+                .split('&')
+                .any(|pair| pair.starts_with("ts="))
+        );
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body, studio);
+    }
+
+    #[tokio::test]
+    async fn submit_is_not_retried() {
+        let (server, api) = mock_api().await;
+        server
+            .push_response(
+                "POST",
+                "/x/vu/web/add/v3",
+                503,
+                json!("Service Unavailable"),
+            )
+            .await;
+
+        assert!(submit_archive(&api, "token", &json!({})).await.is_err());
+        assert_eq!(server.requests().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn edit_returns_full_response() {
+        let (server, api) = mock_api().await;
+        let studio = json!({ "aid": 100001, "title": "修改后的标题" });
+
+        let res = edit_archive(&api, "token", &studio).await.unwrap();
+        assert_eq!(res["data"]["bvid"], json!("BV1mock000001"));
+
+        let requests = server.requests().await;
+        assert_eq!(requests[0].path, "/x/vu/web/edit");
+        assert_eq!(requests[0].query, "csrf=token");
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body, studio);
+    }
+
+    #[tokio::test]
+    async fn edit_error_code_is_surfaced() {
+        let (server, api) = mock_api().await;
+        server
+            .push_response(
+                "POST",
+                "/x/vu/web/edit",
+                200,
+                json!({ "code": 21015, "message": "稿件正在审核中，暂不能编辑" }),
+            )
+            .await;
+
+        let error = edit_archive(&api, "token", &json!({})).await.unwrap_err();
+        assert!(matches!(error, AppError::Api(_)));
+    }
+}
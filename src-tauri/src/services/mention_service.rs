@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{LazyLock, Mutex as StdMutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::{
+    error::AppError,
+    models::{
+        user_config::Credit,
+        validation::{FieldError, MENTION_MAX_COUNT},
+    },
+    services::bili_api::BiliApi,
+    utils::{
+        desc_v2::{self, DescSegment},
+        get_mention_cache_path, write_file_atomic,
+    },
+};
+
+pub const MENTION_SEARCH_URL: &str =
+    "https://api.bilibili.com/x/polymer/web-dynamic/v1/mention/search";
+
+/// 账号搜索@用户时识别出的用户名与 UID，减少解析简介时的请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MentionCache {
+    pub uid: u64,
+    /// 用户名到 UID
+    pub users: HashMap<String, String>,
+}
+
+impl MentionCache {
+    fn path(uid: u64) -> anyhow::Result<PathBuf> {
+        Ok(get_mention_cache_path()?.join(format!("{uid}.json")))
+    }
+
+    /// 读取缓存，不存在或无法解析时返回空缓存
+    pub fn load(uid: u64) -> Self {
+        let empty = Self {
+            uid,
+            ..Default::default()
+        };
+        let Ok(path) = Self::path(uid) else {
+            return empty;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("@用户缓存 {} 无法解析，已忽略: {}", path.display(), e);
+                empty
+            }),
+            Err(_) => empty,
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = serde_json::to_vec(self)?;
+        write_file_atomic(&Self::path(self.uid)?, &content)
+    }
+}
+
+/// 已读取的各账号缓存
+static MENTION_CACHE: LazyLock<StdMutex<HashMap<u64, MentionCache>>> =
+    LazyLock::new(|| StdMutex::new(HashMap::new()));
+
+/// 记录账号搜索到的用户名与 UID，有新内容时写入磁盘
+pub fn remember(uid: u64, users: &[(String, String)]) {
+    let Ok(mut caches) = MENTION_CACHE.lock() else {
+        return;
+    };
+    let cache = caches.entry(uid).or_insert_with(|| MentionCache::load(uid));
+    let mut changed = false;
+    for (name, mid) in users {
+        if name.is_empty() || mid.is_empty() || mid == "0" {
+            continue;
+        }
+        if cache.users.get(name) != Some(mid) {
+            cache.users.insert(name.clone(), mid.clone());
+            changed = true;
+        }
+    }
+    if changed && let Err(e) = cache.save() {
+        warn!("保存@用户缓存失败: {}", e);
+    }
+}
+
+fn cached(uid: u64, name: &str) -> Option<String> {
+    let mut caches = MENTION_CACHE.lock().ok()?;
+    caches
+        .entry(uid)
+        .or_insert_with(|| MentionCache::load(uid))
+        .users
+        .get(name)
+        .cloned()
+}
+
+/// 纯文本简介的解析结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedDesc {
+    /// 由 desc_v2 还原的简介
+    pub desc: String,
+    /// 没有识别出@用户时为空
+    pub desc_v2: Option<Vec<Credit>>,
+    /// 未找到的用户名，保留为纯文本
+    pub unresolved: Vec<String>,
+}
+
+/// 将含有 `@用户名` 的纯文本简介解析为 desc_v2
+pub async fn parse_desc(api: &BiliApi, uid: u64, desc: &str) -> Result<ParsedDesc, AppError> {
+    let segments = desc_v2::split_mentions(desc);
+    let names: Vec<&str> = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            DescSegment::Mention { name, .. } => Some(*name),
+            DescSegment::Text(_) => None,
+        })
+        .collect();
+    if names.len() > MENTION_MAX_COUNT {
+        return Err(AppError::Validation(vec![FieldError::new(
+            "desc_v2",
+            format!(
+                "简介中@用户数量 {} 超过 {MENTION_MAX_COUNT} 个",
+                names.len()
+            ),
+        )]));
+    }
+
+    let mut resolved: HashMap<&str, Option<String>> = HashMap::new();
+    for name in names {
+        if resolved.contains_key(name) {
+            continue;
+        }
+        let mid = match cached(uid, name) {
+            Some(mid) => Some(mid),
+            // 搜索失败时按未找到处理，保留为纯文本
+            None => search(api, uid, name).await.unwrap_or_else(|e| {
+                warn!("搜索@用户 {} 失败: {}", name, e);
+                None
+            }),
+        };
+        resolved.insert(name, mid);
+    }
+
+    let (credits, unresolved) =
+        desc_v2::build_desc_v2(&segments, |name| resolved.get(name).cloned().flatten());
+    debug!(
+        "解析简介@用户: {} 个已识别，{} 个未找到",
+        desc_v2::mention_count(&credits),
+        unresolved.len()
+    );
+    let desc_v2 = (desc_v2::mention_count(&credits) > 0).then_some(credits);
+    Ok(ParsedDesc {
+        desc: desc_v2
+            .as_deref()
+            .map_or_else(|| desc.to_string(), desc_v2::render_desc),
+        desc_v2,
+        unresolved,
+    })
+}
+
+/// 搜索用户名，返回名称完全一致的用户 UID
+async fn search(api: &BiliApi, uid: u64, name: &str) -> Result<Option<String>, AppError> {
+    let res = api
+        .get_query(
+            MENTION_SEARCH_URL,
+            &[("uid", uid.to_string()), ("keyword", name.to_string())],
+        )
+        .await?;
+
+    let mut found = None;
+    let mut users = Vec::new();
+    let items = res["data"]["groups"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|group| group["items"].as_array().into_iter().flatten());
+    for item in items {
+        let item_name = item["name"].as_str().unwrap_or_default();
+        let item_uid = match &item["uid"] {
+            Value::String(uid) => uid.clone(),
+            Value::Number(uid) => uid.to_string(),
+            _ => continue,
+        };
+        if found.is_none() && item_name == name {
+            found = Some(item_uid.clone());
+        }
+        users.push((item_name.to_string(), item_uid));
+    }
+    remember(uid, &users);
+    Ok(found)
+}
@@ -0,0 +1,442 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use crate::{
+    MyClient,
+    error::{AppError, ErrorCategory},
+    services::{
+        bili_api::is_retryable_error,
+        creative_api::{Activity, Topic},
+    },
+    utils::{get_metadata_cache_path, write_file_atomic},
+};
+
+/// 缓存的元数据种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataKind {
+    /// 投稿所需的分区、联合投稿配置等
+    ArchivePre,
+    /// 新版分区列表
+    TypeListV2,
+    /// 活动话题
+    Topics,
+    /// 投稿活动
+    Activities,
+}
+
+impl MetadataKind {
+    pub const ALL: [Self; 4] = [
+        Self::ArchivePre,
+        Self::TypeListV2,
+        Self::Topics,
+        Self::Activities,
+    ];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::ArchivePre => "archive_pre",
+            Self::TypeListV2 => "type_list_v2",
+            Self::Topics => "topics",
+            Self::Activities => "activities",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::ArchivePre => "投稿配置",
+            Self::TypeListV2 => "新版分区列表",
+            Self::Topics => "话题列表",
+            Self::Activities => "活动列表",
+        }
+    }
+
+    /// 缓存有效期（秒），分区很少变化，话题与活动更新较频繁
+    fn ttl(self) -> i64 {
+        match self {
+            Self::ArchivePre | Self::TypeListV2 => 24 * 60 * 60,
+            Self::Topics | Self::Activities => 60 * 60,
+        }
+    }
+
+    async fn fetch(self, client: &MyClient) -> Result<Value, AppError> {
+        let creative = client.creative();
+        match self {
+            Self::ArchivePre => creative.archive_pre().await,
+            Self::TypeListV2 => to_value(creative.type_list_v2().await?),
+            Self::Topics => to_value(creative.topics().await?),
+            Self::Activities => to_value(creative.activities().await?),
+        }
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::Internal(e.into()))
+}
+
+/// 磁盘上的元数据缓存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    uid: u64,
+    kind: MetadataKind,
+    /// 最近一次获取到新内容的时间（秒级时间戳）
+    fetched_at: i64,
+    /// 最近一次成功请求接口的时间，内容未变化时只更新该时间
+    #[serde(default)]
+    checked_at: i64,
+    /// 内容摘要，用于判断接口返回的内容是否变化
+    #[serde(default)]
+    etag: String,
+    data: Value,
+}
+
+impl CacheEntry {
+    fn path(uid: u64, kind: MetadataKind) -> Result<PathBuf> {
+        Ok(get_metadata_cache_path()?.join(format!("{uid}_{}.json", kind.file_name())))
+    }
+
+    /// 读取缓存，不存在或无法解析时返回 None
+    fn load(uid: u64, kind: MetadataKind) -> Option<Self> {
+        let path = Self::path(uid, kind).ok()?;
+        let content = std::fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&content)
+            .inspect_err(|e| warn!("元数据缓存 {} 无法解析，已忽略: {}", path.display(), e))
+            .ok()
+    }
+
+    fn save(&self) -> Result<()> {
+        let content = serde_json::to_vec(self)?;
+        write_file_atomic(&Self::path(self.uid, self.kind)?, &content)
+    }
+
+    fn is_fresh(&self, now: i64) -> bool {
+        (0..self.kind.ttl()).contains(&(now - self.checked_at))
+    }
+
+    /// 用接口返回的数据更新缓存，内容未变化时保留原有数据与获取时间
+    fn refreshed(
+        previous: Option<Self>,
+        uid: u64,
+        kind: MetadataKind,
+        data: Value,
+        now: i64,
+    ) -> Self {
+        let etag = etag(&data);
+        match previous {
+            Some(previous) if previous.etag == etag => Self {
+                checked_at: now,
+                ..previous
+            },
+            _ => Self {
+                uid,
+                kind,
+                fetched_at: now,
+                checked_at: now,
+                etag,
+                data,
+            },
+        }
+    }
+}
+
+fn etag(data: &Value) -> String {
+    let digest = Sha256::digest(data.to_string().as_bytes());
+    digest[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// 获取失败时是否可以回退到过期的缓存，登录失效、没有权限等错误需要用户处理，不回退
+fn can_use_stale(error: &AppError) -> bool {
+    is_retryable_error(error)
+        || matches!(
+            error.category(),
+            ErrorCategory::Network | ErrorCategory::Server | ErrorCategory::RateLimited
+        )
+}
+
+/// 读取元数据，缓存未过期时直接返回，否则重新获取
+///
+/// `force` 为 true 时忽略有效期。网络、限流或服务端错误时回退到过期的缓存。
+pub async fn get(
+    client: &MyClient,
+    uid: u64,
+    kind: MetadataKind,
+    force: bool,
+) -> Result<Value, AppError> {
+    let cached = CacheEntry::load(uid, kind);
+    let now = chrono::Utc::now().timestamp();
+    if !force && let Some(entry) = cached.as_ref().filter(|entry| entry.is_fresh(now)) {
+        return Ok(entry.data.clone());
+    }
+
+    let data = match (kind.fetch(client).await, cached.as_ref()) {
+        (Ok(data), _) => data,
+        (Err(e), Some(entry)) if can_use_stale(&e) => {
+            warn!("获取{}失败，使用本地缓存: {}", kind.label(), e);
+            return Ok(entry.data.clone());
+        }
+        (Err(e), _) => return Err(e),
+    };
+
+    let entry = CacheEntry::refreshed(cached, uid, kind, data, now);
+    if entry.fetched_at == now {
+        info!("用户 {} 的{}已更新", uid, kind.label());
+    } else {
+        debug!("用户 {} 的{}没有变化", uid, kind.label());
+    }
+    if let Err(e) = entry.save() {
+        warn!("保存{}缓存失败: {}", kind.label(), e);
+    }
+    Ok(entry.data)
+}
+
+/// 投稿配置，附带新版分区列表
+pub async fn archive_pre(client: &MyClient, uid: u64) -> Result<Value, AppError> {
+    let mut archive_pre = get(client, uid, MetadataKind::ArchivePre, false).await?;
+    match get(client, uid, MetadataKind::TypeListV2, false).await {
+        Ok(type_list) => archive_pre["type_list_v2"] = type_list,
+        Err(e) => warn!("获取新版分区列表失败: {}", e),
+    }
+    Ok(archive_pre)
+}
+
+pub async fn topics(client: &MyClient, uid: u64) -> Result<Vec<Topic>, AppError> {
+    let data = get(client, uid, MetadataKind::Topics, false).await?;
+    serde_json::from_value(data).map_err(|e| AppError::Internal(e.into()))
+}
+
+pub async fn activities(client: &MyClient, uid: u64) -> Result<Vec<Activity>, AppError> {
+    let data = get(client, uid, MetadataKind::Activities, false).await?;
+    serde_json::from_value(data).map_err(|e| AppError::Internal(e.into()))
+}
+
+/// 单项元数据的缓存状态
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataStatus {
+    pub kind: MetadataKind,
+    /// 最近一次获取到新内容的时间，尚未缓存时为 None
+    pub fetched_at: Option<i64>,
+    /// 最近一次成功请求接口的时间
+    pub checked_at: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub etag: Option<String>,
+    pub fresh: bool,
+}
+
+/// 各项元数据的缓存状态
+pub fn status(uid: u64) -> Vec<MetadataStatus> {
+    let now = chrono::Utc::now().timestamp();
+    MetadataKind::ALL
+        .into_iter()
+        .map(|kind| match CacheEntry::load(uid, kind) {
+            Some(entry) => MetadataStatus {
+                kind,
+                fetched_at: Some(entry.fetched_at),
+                checked_at: Some(entry.checked_at),
+                expires_at: Some(entry.checked_at + kind.ttl()),
+                fresh: entry.is_fresh(now),
+                etag: Some(entry.etag),
+            },
+            None => MetadataStatus {
+                kind,
+                fetched_at: None,
+                checked_at: None,
+                expires_at: None,
+                etag: None,
+                fresh: false,
+            },
+        })
+        .collect()
+}
+
+/// 重新获取全部元数据，单项失败时保留原有缓存
+pub async fn refresh(client: &MyClient, uid: u64) -> Vec<MetadataStatus> {
+    for kind in MetadataKind::ALL {
+        if let Err(e) = get(client, uid, kind, true).await {
+            warn!("刷新{}失败: {}", kind.label(), e);
+        }
+    }
+    status(uid)
+}
+
+/// 分区搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryMatch {
+    pub id: u64,
+    pub name: String,
+    pub desc: String,
+    /// 子分区所属的主分区
+    pub parent_id: Option<u64>,
+    pub parent_name: Option<String>,
+    /// 是否为新版分区
+    pub v2: bool,
+}
+
+/// 在本地缓存的分区中按名称搜索，不发起请求
+///
+/// 名称完全一致的排在最前，其次是以关键词开头的分区。
+pub fn search_categories(uid: u64, keyword: &str) -> Vec<CategoryMatch> {
+    let archive_pre = CacheEntry::load(uid, MetadataKind::ArchivePre);
+    let type_list_v2 = CacheEntry::load(uid, MetadataKind::TypeListV2);
+    match_categories(
+        archive_pre.as_ref().map(|entry| &entry.data),
+        type_list_v2.as_ref().map(|entry| &entry.data),
+        keyword,
+    )
+}
+
+fn match_categories(
+    archive_pre: Option<&Value>,
+    type_list_v2: Option<&Value>,
+    keyword: &str,
+) -> Vec<CategoryMatch> {
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.is_empty() {
+        return Vec::new();
+    }
+
+    let mut categories = Vec::new();
+    if let Some(archive_pre) = archive_pre {
+        collect_categories(&archive_pre["typelist"], None, false, &mut categories);
+    }
+    if let Some(type_list_v2) = type_list_v2 {
+        collect_categories(type_list_v2, None, true, &mut categories);
+    }
+
+    let mut matches: Vec<(u8, CategoryMatch)> = categories
+        .into_iter()
+        .filter_map(|category| {
+            let name = category.name.to_lowercase();
+            let rank = if name == keyword {
+                0
+            } else if name.starts_with(&keyword) {
+                1
+            } else if name.contains(&keyword) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, category))
+        })
+        .collect();
+    matches.sort_by_key(|(rank, _)| *rank);
+    matches.into_iter().map(|(_, category)| category).collect()
+}
+
+/// 展开分区树，`children` 中为子分区
+fn collect_categories(
+    list: &Value,
+    parent: Option<(u64, &str)>,
+    v2: bool,
+    output: &mut Vec<CategoryMatch>,
+) {
+    for item in list.as_array().into_iter().flatten() {
+        let (Some(id), Some(name)) = (item["id"].as_u64(), item["name"].as_str()) else {
+            continue;
+        };
+        output.push(CategoryMatch {
+            id,
+            name: name.to_string(),
+            desc: item["desc"].as_str().unwrap_or_default().to_string(),
+            parent_id: parent.map(|(id, _)| id),
+            parent_name: parent.map(|(_, name)| name.to_string()),
+            v2,
+        });
+        collect_categories(&item["children"], Some((id, name)), v2, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::error::BiliApiError;
+
+    const UID: u64 = 10001;
+    const NOW: i64 = 1_760_000_000;
+
+    fn api_error(code: i64) -> AppError {
+        AppError::Api(BiliApiError {
+            code,
+            message: String::new(),
+        })
+    }
+
+    #[test]
+    fn freshness_follows_ttl_from_last_check() {
+        let entry = CacheEntry::refreshed(None, UID, MetadataKind::Topics, json!([]), NOW);
+        let ttl = MetadataKind::Topics.ttl();
+        assert!(entry.is_fresh(NOW));
+        assert!(entry.is_fresh(NOW + ttl - 1));
+        assert!(!entry.is_fresh(NOW + ttl));
+        // 系统时间回拨时视为过期
+        assert!(!entry.is_fresh(NOW - 1));
+        assert!(MetadataKind::TypeListV2.ttl() > ttl);
+    }
+
+    #[test]
+    fn unchanged_data_keeps_fetched_at() {
+        let kind = MetadataKind::Activities;
+        let first = CacheEntry::refreshed(None, UID, kind, json!([{ "id": 1 }]), NOW);
+
+        let same = CacheEntry::refreshed(
+            Some(first.clone()),
+            UID,
+            kind,
+            json!([{ "id": 1 }]),
+            NOW + 10,
+        );
+        assert_eq!(same.fetched_at, NOW);
+        assert_eq!(same.checked_at, NOW + 10);
+        assert_eq!(same.etag, first.etag);
+
+        let changed = CacheEntry::refreshed(Some(same), UID, kind, json!([{ "id": 2 }]), NOW + 20);
+        assert_eq!(changed.fetched_at, NOW + 20);
+        assert_eq!(changed.checked_at, NOW + 20);
+        assert_ne!(changed.etag, first.etag);
+        assert_eq!(changed.data, json!([{ "id": 2 }]));
+    }
+
+    #[test]
+    fn stale_cache_is_used_only_for_transient_errors() {
+        for code in [-412, -500, -503, -504, -799] {
+            assert!(can_use_stale(&api_error(code)), "{code}");
+        }
+        for code in [-101, -111, -403, -404, 21999] {
+            assert!(!can_use_stale(&api_error(code)), "{code}");
+        }
+        assert!(!can_use_stale(&AppError::UserNotFound(UID)));
+        assert!(!can_use_stale(&AppError::Custom("解析失败".to_string())));
+    }
+
+    #[test]
+    fn categories_are_ranked_by_match() {
+        let archive_pre = json!({
+            "typelist": [{
+                "id": 160,
+                "name": "生活",
+                "desc": "",
+                "children": [
+                    { "id": 138, "name": "搞笑", "desc": "搞笑的作品" },
+                    { "id": 21, "name": "日常生活", "desc": "" }
+                ]
+            }]
+        });
+        let type_list_v2 = json!([{ "id": 1008, "name": "生活经验" }]);
+
+        let matches = match_categories(Some(&archive_pre), Some(&type_list_v2), " 生活 ");
+        let ids: Vec<u64> = matches.iter().map(|category| category.id).collect();
+        assert_eq!(ids, [160, 1008, 21]);
+        assert!(matches[1].v2);
+        assert_eq!(matches[2].parent_id, Some(160));
+        assert_eq!(matches[2].parent_name.as_deref(), Some("生活"));
+
+        assert!(match_categories(Some(&archive_pre), None, "  ").is_empty());
+        assert!(match_categories(None, None, "生活").is_empty());
+    }
+}
@@ -0,0 +1,367 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+use tracing::{debug, warn};
+
+#[cfg(feature = "mock-server")]
+pub use crate::services::bili_api::set_base_url_override;
+
+/// 内置夹具
+const DEFAULT_FIXTURES: &str = include_str!("mock_fixtures.json");
+
+/// 单条夹具，`path` 以 `*` 结尾时按前缀匹配
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    pub method: String,
+    pub path: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    pub body: Value,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+/// 服务器收到的请求
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub body: Vec<u8>,
+}
+
+#[derive(Default)]
+struct MockState {
+    fixtures: Vec<Fixture>,
+    /// 一次性响应，优先于夹具返回，用于模拟限流、服务端错误等
+    queued: HashMap<(String, String), VecDeque<(u16, Value)>>,
+    requests: Vec<RecordedRequest>,
+}
+
+impl MockState {
+    fn respond(&mut self, method: &str, path: &str) -> (u16, Value) {
+        let key = (method.to_string(), path.to_string());
+        if let Some(response) = self.queued.get_mut(&key).and_then(VecDeque::pop_front) {
+            return response;
+        }
+
+        // 后添加的夹具优先，便于覆盖内置夹具
+        self.fixtures
+            .iter()
+            .rev()
+            .find(|fixture| {
+                fixture.method.eq_ignore_ascii_case(method)
+                    && match fixture.path.strip_suffix('*') {
+                        Some(prefix) => path.starts_with(prefix),
+                        None => fixture.path == path,
+                    }
+            })
+            .map(|fixture| (fixture.status, fixture.body.clone()))
+            .unwrap_or_else(|| {
+                warn!("模拟服务器没有匹配的夹具: {} {}", method, path);
+                (
+                    404,
+                    serde_json::json!({ "code": -404, "message": "啥都木有" }),
+                )
+            })
+    }
+}
+
+/// 本地模拟B站接口的服务器，按夹具返回固定响应并记录收到的请求
+///
+/// 内置夹具覆盖 my_info、投稿与编辑以及创作中心接口。
+/// 经过 `BiliApi` 的请求可通过 [`set_base_url_override`] 指向该服务器；
+/// 视频分片上传与二维码登录由 biliup 使用固定域名发起，不会被重定向，也不在覆盖范围内。
+/// drop 后继续运行直到进程退出。
+#[derive(Clone)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockServer {
+    /// 使用内置夹具在随机端口启动
+    pub async fn start() -> Result<Self> {
+        Self::start_with(parse_fixtures(DEFAULT_FIXTURES)?).await
+    }
+
+    /// 使用内置夹具与目录中的 `*.json` 夹具启动，目录中的夹具优先
+    #[cfg_attr(not(feature = "mock-server"), allow(dead_code))]
+    pub async fn start_with_dir(dir: &Path) -> Result<Self> {
+        let mut fixtures = parse_fixtures(DEFAULT_FIXTURES)?;
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let content = std::fs::read_to_string(&path)?;
+            fixtures.extend(
+                parse_fixtures(&content)
+                    .map_err(|e| anyhow::anyhow!("解析夹具 {} 失败: {e}", path.display()))?,
+            );
+        }
+        Self::start_with(fixtures).await
+    }
+
+    pub async fn start_with(fixtures: Vec<Fixture>) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState {
+            fixtures,
+            ..Default::default()
+        }));
+
+        let server = Self { addr, state };
+        let accept_server = server.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let server = accept_server.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = server.handle(stream).await {
+                                debug!("模拟服务器处理请求失败: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        warn!("模拟服务器接受连接失败: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        debug!("模拟服务器已启动: {}", server.url());
+        Ok(server)
+    }
+
+    /// 服务器地址，如 `http://127.0.0.1:12345`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// 添加或覆盖夹具
+    pub async fn set_fixture(&self, method: &str, path: &str, status: u16, body: Value) {
+        self.state.lock().await.fixtures.push(Fixture {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            status,
+            body,
+        });
+    }
+
+    /// 为接口排队一次性响应，按顺序在夹具之前返回
+    pub async fn push_response(&self, method: &str, path: &str, status: u16, body: Value) {
+        self.state
+            .lock()
+            .await
+            .queued
+            .entry((method.to_uppercase(), path.to_string()))
+            .or_default()
+            .push_back((status, body));
+    }
+
+    /// 已收到的请求
+    pub async fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().await.requests.clone()
+    }
+
+    async fn handle(&self, stream: TcpStream) -> Result<()> {
+        let mut reader = BufReader::new(stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_uppercase();
+        let target = parts.next().unwrap_or("/").to_string();
+        let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':')
+                && name.trim().eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+
+        let (status, response) = {
+            let mut state = self.state.lock().await;
+            state.requests.push(RecordedRequest {
+                method: method.clone(),
+                path: path.to_string(),
+                query: query.to_string(),
+                body,
+            });
+            state.respond(&method, path)
+        };
+
+        let (content_type, payload) = match response {
+            Value::String(text) => ("text/plain", text),
+            value => ("application/json", value.to_string()),
+        };
+        let payload = payload.replace("{base}", &self.url());
+
+        let mut stream = reader.into_inner();
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 {status} {}\r\ncontent-type: {content_type}; charset=utf-8\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{payload}",
+                    reason(status),
+                    payload.len(),
+                )
+                .as_bytes(),
+            )
+            .await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+fn parse_fixtures(content: &str) -> Result<Vec<Fixture>> {
+    Ok(serde_json::from_str(content)?)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        412 => "Precondition Failed",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Client;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        error::{AppError, ErrorCategory},
+        services::bili_api::BiliApi,
+    };
+
+    const URL: &str = "https://member.bilibili.com/x/mock/test?id=1";
+    const PATH: &str = "/x/mock/test";
+
+    async fn setup() -> (MockServer, BiliApi) {
+        let server = MockServer::start().await.unwrap();
+        server
+            .set_fixture(
+                "GET",
+                PATH,
+                200,
+                json!({ "code": 0, "data": { "ok": true } }),
+            )
+            .await;
+        server
+            .set_fixture("POST", PATH, 200, json!({ "code": 0, "data": { "id": 1 } }))
+            .await;
+        let api = BiliApi::new(Client::new()).with_base_url(server.url().parse().unwrap());
+        (server, api)
+    }
+
+    async fn request_count(server: &MockServer) -> usize {
+        server
+            .requests()
+            .await
+            .iter()
+            .filter(|request| request.path == PATH)
+            .count()
+    }
+
+    #[tokio::test]
+    async fn redirects_path_and_query() {
+        let (server, api) = setup().await;
+        let res = api.get(URL).await.unwrap();
+        assert_eq!(res["data"]["ok"], json!(true));
+
+        let requests = server.requests().await;
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].query, "id=1");
+    }
+
+    #[tokio::test]
+    async fn get_is_retried_after_rate_limit() {
+        let (server, api) = setup().await;
+        server
+            .push_response("GET", PATH, 412, json!({ "code": -412, "message": "风控" }))
+            .await;
+
+        api.get(URL).await.unwrap();
+        assert_eq!(request_count(&server).await, 2);
+
+        let metrics = api.metrics().await;
+        let metrics = metrics.values().next().unwrap();
+        assert_eq!(metrics.retries, 1);
+        assert_eq!(metrics.failures, 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_code_is_not_retried() {
+        let (server, api) = setup().await;
+        server
+            .push_response(
+                "GET",
+                PATH,
+                200,
+                json!({ "code": 21999, "message": "未知错误" }),
+            )
+            .await;
+
+        let error = api.get(URL).await.unwrap_err();
+        assert!(matches!(&error, AppError::Api(e) if e.category() == ErrorCategory::Unknown));
+        assert_eq!(request_count(&server).await, 1);
+    }
+
+    #[tokio::test]
+    async fn post_is_not_retried() {
+        let (server, api) = setup().await;
+        server
+            .push_response("POST", PATH, 503, json!("Service Unavailable"))
+            .await;
+
+        assert!(api.post_json(URL, &json!({ "id": 1 })).await.is_err());
+        assert_eq!(request_count(&server).await, 1);
+        assert_eq!(server.requests().await[0].body, br#"{"id":1}"#);
+    }
+
+    #[tokio::test]
+    async fn idempotent_post_is_retried() {
+        let (server, api) = setup().await;
+        server
+            .push_response("POST", PATH, 503, json!("Service Unavailable"))
+            .await;
+
+        let res = api.post_json_idempotent(URL, &json!({})).await.unwrap();
+        assert_eq!(res["data"]["id"], json!(1));
+        assert_eq!(request_count(&server).await, 2);
+    }
+}
@@ -1,4 +1,18 @@
+pub mod archive_service;
 pub mod auth_service;
+pub mod backup_service;
+pub mod bili_api;
+pub mod config_watcher;
+pub mod creative_api;
+pub mod mention_service;
+pub mod metadata_service;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
+pub mod notification_service;
+pub mod season_api;
+pub mod season_service;
+pub mod subtitle_service;
+pub mod tag_service;
 pub mod upload_service;
 
 pub use auth_service::*;
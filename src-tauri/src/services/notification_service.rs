@@ -0,0 +1,400 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::{
+    AppData,
+    error::AppError,
+    models::notification::{NotificationSink, NotifyEvent, SinkKind},
+    utils::crypto::encode_base64,
+};
+
+/// 发送通知的请求超时
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(15);
+/// Bark 官方服务器
+const BARK_DEFAULT_SERVER: &str = "https://api.day.app";
+
+/// 一条待发送的通知
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub title: String,
+    pub message: String,
+    /// 相关的链接，如稿件地址
+    pub url: Option<String>,
+}
+
+impl Notification {
+    /// 以事件名称作为标题创建通知
+    pub fn new(event: NotifyEvent, message: impl Into<String>) -> Self {
+        Self {
+            event,
+            title: format!("biliup: {}", event.label()),
+            message: message.into(),
+            url: None,
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// 标题与内容合并后的纯文本，用于只支持一段文字的渠道
+    fn text(&self) -> String {
+        match &self.url {
+            Some(url) => format!("{}\n{}\n{}", self.title, self.message, url),
+            None => format!("{}\n{}", self.title, self.message),
+        }
+    }
+
+    /// 替换模板中的 `{title}`、`{message}`、`{event}`、`{url}`，替换值按 JSON 字符串转义
+    fn render(&self, template: &str) -> String {
+        let escape = |value: &str| {
+            let quoted = Value::String(value.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        };
+        let event = serde_json::to_value(self.event)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        template
+            .replace("{title}", &escape(&self.title))
+            .replace("{message}", &escape(&self.message))
+            .replace("{event}", &escape(&event))
+            .replace("{url}", &escape(self.url.as_deref().unwrap_or_default()))
+    }
+}
+
+/// 通知发送端，可在任意位置克隆使用，通知在后台按配置分发
+#[derive(Clone)]
+pub struct Notifier {
+    tx: mpsc::UnboundedSender<Notification>,
+}
+
+impl Notifier {
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<Notification>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+
+    pub fn notify(&self, notification: Notification) {
+        if self.tx.send(notification).is_err() {
+            debug!("通知服务未运行，已丢弃通知");
+        }
+    }
+}
+
+/// 按当前配置将通知分发到各个渠道
+pub struct NotificationService;
+
+impl NotificationService {
+    /// 在后台启动通知分发
+    pub fn spawn(app: AppHandle, mut rx: mpsc::UnboundedReceiver<Notification>) {
+        tauri::async_runtime::spawn(async move {
+            let client = http_client();
+            while let Some(notification) = rx.recv().await {
+                Self::dispatch(&app, &client, &notification).await;
+            }
+        });
+    }
+
+    async fn dispatch(app: &AppHandle, client: &Client, notification: &Notification) {
+        let config = app
+            .state::<AppData>()
+            .config
+            .lock()
+            .await
+            .notifications
+            .clone();
+        if !config.enabled {
+            return;
+        }
+
+        for sink in config
+            .sinks
+            .iter()
+            .filter(|sink| sink.accepts(notification.event))
+        {
+            match send(app, client, sink, notification).await {
+                Ok(()) => debug!("通知已发送到 {}: {}", sink.name, notification.title),
+                Err(e) => warn!("发送通知到 {} 失败: {}", sink.name, e),
+            }
+        }
+    }
+}
+
+pub fn http_client() -> Client {
+    Client::builder()
+        .timeout(NOTIFY_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// 发送通知到单个渠道，不检查渠道是否启用及订阅的事件
+pub async fn send(
+    app: &AppHandle,
+    client: &Client,
+    sink: &NotificationSink,
+    notification: &Notification,
+) -> Result<(), AppError> {
+    match &sink.kind {
+        SinkKind::Desktop => app
+            .notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.message)
+            .show()
+            .map_err(|e| AppError::Custom(format!("发送桌面通知失败: {e}"))),
+        SinkKind::Webhook {
+            url,
+            body_template,
+            headers,
+        } => {
+            require(url, "Webhook 地址")?;
+            let body = match body_template {
+                Some(template) if !template.trim().is_empty() => notification.render(template),
+                _ => json!(notification).to_string(),
+            };
+            let mut request = client
+                .post(url)
+                .header("content-type", "application/json")
+                .body(body);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            request.send().await?.error_for_status()?;
+            Ok(())
+        }
+        SinkKind::ServerChan { send_key } => {
+            require(send_key, "SendKey")?;
+            let res = post_json(
+                client,
+                &server_chan_url(send_key),
+                &json!({ "title": notification.title, "desp": notification.text() }),
+            )
+            .await?;
+            ensure(res["code"].as_i64() == Some(0), "Server酱", &res)
+        }
+        SinkKind::Bark { server, device_key } => {
+            require(device_key, "Bark 设备 Key")?;
+            let server = match server.trim() {
+                "" => BARK_DEFAULT_SERVER,
+                server => server.trim_end_matches('/'),
+            };
+            let mut body = json!({
+                "device_key": device_key,
+                "title": notification.title,
+                "body": notification.message,
+                "group": "biliup",
+            });
+            if let Some(url) = &notification.url {
+                body["url"] = json!(url);
+            }
+            let res = post_json(client, &format!("{server}/push"), &body).await?;
+            ensure(res["code"].as_i64() == Some(200), "Bark", &res)
+        }
+        SinkKind::Telegram { bot_token, chat_id } => {
+            require(bot_token, "Telegram Bot Token")?;
+            require(chat_id, "Telegram Chat ID")?;
+            let res = post_json(
+                client,
+                &format!("https://api.telegram.org/bot{bot_token}/sendMessage"),
+                &json!({ "chat_id": chat_id, "text": notification.text() }),
+            )
+            .await?;
+            ensure(res["ok"].as_bool() == Some(true), "Telegram", &res)
+        }
+        SinkKind::DingTalk { webhook, secret } => {
+            require(webhook, "钉钉 Webhook 地址")?;
+            let mut url = reqwest::Url::parse(webhook)
+                .map_err(|e| AppError::Config(format!("钉钉 Webhook 地址无效: {e}")))?;
+            if let Some(secret) = secret.as_deref().filter(|s| !s.is_empty()) {
+                let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+                let sign = dingtalk_sign(secret, &timestamp);
+                url.query_pairs_mut()
+                    .append_pair("timestamp", &timestamp)
+                    .append_pair("sign", &sign);
+            }
+            let res = post_json(
+                client,
+                url.as_str(),
+                &json!({ "msgtype": "text", "text": { "content": notification.text() } }),
+            )
+            .await?;
+            ensure(res["errcode"].as_i64() == Some(0), "钉钉", &res)
+        }
+        SinkKind::Feishu { webhook, secret } => {
+            require(webhook, "飞书 Webhook 地址")?;
+            let mut body = json!({
+                "msg_type": "text",
+                "content": { "text": notification.text() },
+            });
+            if let Some(secret) = secret.as_deref().filter(|s| !s.is_empty()) {
+                let timestamp = chrono::Utc::now().timestamp().to_string();
+                body["timestamp"] = json!(timestamp);
+                body["sign"] = json!(feishu_sign(secret, &timestamp));
+            }
+            let res = post_json(client, webhook, &body).await?;
+            ensure(res["code"].as_i64() == Some(0), "飞书", &res)
+        }
+    }
+}
+
+/// 发送测试通知到指定渠道
+pub async fn send_test(app: &AppHandle, sink: &NotificationSink) -> Result<(), AppError> {
+    let notification = Notification::new(
+        sink.events
+            .first()
+            .copied()
+            .unwrap_or(NotifyEvent::UploadFinished),
+        format!("这是一条来自 {} 的测试通知", sink.name),
+    );
+    send(app, &http_client(), sink, &notification).await?;
+    info!("测试通知已发送到 {}", sink.name);
+    Ok(())
+}
+
+/// 检查 Webhook 请求体模板替换变量后是否为有效的 JSON
+pub fn check_body_template(template: &str) -> Result<(), serde_json::Error> {
+    if template.trim().is_empty() {
+        return Ok(());
+    }
+    let sample = Notification::new(NotifyEvent::UploadFinished, "包含 \"引号\" 与\n换行的内容")
+        .with_url("https://www.bilibili.com/video/BV1xx411c7mD");
+    serde_json::from_str::<Value>(&sample.render(template)).map(|_| ())
+}
+
+fn require(value: &str, field: &str) -> Result<(), AppError> {
+    if value.trim().is_empty() {
+        Err(AppError::Config(format!("未设置{field}")))
+    } else {
+        Ok(())
+    }
+}
+
+fn ensure(ok: bool, sink: &str, res: &Value) -> Result<(), AppError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(AppError::Custom(format!("{sink} 返回错误: {res}")))
+    }
+}
+
+async fn post_json(client: &Client, url: &str, body: &Value) -> Result<Value, AppError> {
+    let response = client
+        .post(url)
+        .json(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.json().await?)
+}
+
+/// Server酱 Turbo 与 Server酱³ 的 SendKey 使用不同的接口地址
+fn server_chan_url(send_key: &str) -> String {
+    match send_key.strip_prefix("sctp") {
+        Some(rest) => {
+            let uid: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            format!("https://{uid}.push.ft07.com/send/{send_key}.send")
+        }
+        None => format!("https://sctapi.ftqq.com/{send_key}.send"),
+    }
+}
+
+/// 钉钉机器人签名，以密钥对 `timestamp\nsecret` 签名，`timestamp` 为毫秒
+fn dingtalk_sign(secret: &str, timestamp: &str) -> String {
+    hmac_sha256(secret, &format!("{timestamp}\n{secret}"))
+}
+
+/// 飞书机器人签名，以 `timestamp\nsecret` 为密钥对空串签名，`timestamp` 为秒
+fn feishu_sign(secret: &str, timestamp: &str) -> String {
+    hmac_sha256(&format!("{timestamp}\n{secret}"), "")
+}
+
+/// 钉钉与飞书机器人使用的 HmacSHA256 签名，结果为 base64
+fn hmac_sha256(key: &str, message: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC 可以接受任意长度的密钥");
+    mac.update(message.as_bytes());
+    encode_base64(&mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_escapes_values_as_json_strings() {
+        let notification = Notification::new(
+            NotifyEvent::SubmitFailed,
+            "标题 \"引号\" 与 \\ 反斜杠\n第二行",
+        )
+        .with_url("https://www.bilibili.com/video/BV1xx411c7mD");
+        let body = notification
+            .render(r#"{"text": "{title}: {message}", "event": "{event}", "link": "{url}"}"#);
+
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            value["text"],
+            json!(format!("{}: {}", notification.title, notification.message))
+        );
+        assert_eq!(value["event"], json!(notification.event));
+        assert_eq!(value["link"], json!(notification.url.unwrap()));
+
+        // 没有链接时替换为空串
+        let body =
+            Notification::new(NotifyEvent::UploadFinished, "完成").render(r#"{"u":"{url}"}"#);
+        assert_eq!(body, r#"{"u":""}"#);
+    }
+
+    #[test]
+    fn body_template_must_be_json() {
+        assert!(check_body_template("").is_ok());
+        assert!(check_body_template(r#"{"msg": "{title}\n{message}"}"#).is_ok());
+        assert!(check_body_template(r#"{"msg": {message}}"#).is_err());
+        assert!(check_body_template("{title}").is_err());
+    }
+
+    #[test]
+    fn server_chan_url_by_key_type() {
+        assert_eq!(
+            server_chan_url("SCT123456TAbCdEf"),
+            "https://sctapi.ftqq.com/SCT123456TAbCdEf.send"
+        );
+        assert_eq!(
+            server_chan_url("sctp1234tabcdef"),
+            "https://1234.push.ft07.com/send/sctp1234tabcdef.send"
+        );
+    }
+
+    #[test]
+    fn hmac_sha256_matches_rfc4231() {
+        // RFC 4231 测试用例 2
+        assert_eq!(
+            hmac_sha256("Jefe", "what do ya want for nothing?"),
+            "W9zBRr9gdU5qBCQmCJV1x1oAPwidJzmDnexYuWTsOEM="
+        );
+    }
+
+    #[test]
+    fn robot_signatures() {
+        let secret = "SEC0123456789abcdef";
+        assert_eq!(
+            dingtalk_sign(secret, "1700000000000"),
+            "TSZbRFUuvaSQaRKUpF970OPCb2/LcQAP3wOvwZIzBZk="
+        );
+        assert_eq!(
+            feishu_sign(secret, "1700000000"),
+            "PiO7POLlSx/DM2qf8Dy/XCWeJK3exVnevp5G99LEN2M="
+        );
+    }
+}
@@ -0,0 +1,586 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use crate::{
+    error::AppError,
+    services::creative_api::{CreativeApi, parse_data},
+};
+
+/// 合集列表接口每页的最大数量
+const SEASON_PAGE_SIZE: u32 = 50;
+/// 获取全部合集时的最大页数
+const SEASON_MAX_PAGES: u32 = 20;
+/// 普通小节的类型
+const SECTION_TYPE_NORMAL: i64 = 1;
+
+/// 合集
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all(deserialize = "camelCase"))]
+pub struct Season {
+    pub id: u64,
+    pub title: String,
+    pub desc: String,
+    pub cover: String,
+    /// 是否已完结，0 为连载中
+    pub is_end: u8,
+    /// 审核状态，-6 为审核中，0 为已通过
+    pub state: i64,
+    #[serde(alias = "ep_num")]
+    pub ep_num: u64,
+    pub ctime: i64,
+    pub mtime: i64,
+}
+
+/// 合集中的小节
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all(deserialize = "camelCase"))]
+pub struct Section {
+    pub id: u64,
+    pub season_id: u64,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub section_type: i64,
+    pub order: i64,
+    pub ep_count: u64,
+    pub state: i64,
+}
+
+/// 小节中的分集，`id` 为分集 ID，删除与排序时使用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all(deserialize = "camelCase"))]
+pub struct SectionEpisode {
+    pub id: u64,
+    pub title: String,
+    pub aid: u64,
+    pub bvid: String,
+    pub cid: u64,
+    pub season_id: u64,
+    pub section_id: u64,
+    pub order: i64,
+    pub archive_title: String,
+    pub state: i64,
+}
+
+/// 合集及其小节
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SeasonDetail {
+    pub season: Season,
+    pub sections: Vec<Section>,
+}
+
+/// 合集列表的一页
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SeasonPage {
+    pub seasons: Vec<SeasonDetail>,
+    pub pn: u32,
+    pub ps: u32,
+    /// 合集总数
+    pub total: u64,
+}
+
+/// 小节及其分集
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SectionDetail {
+    pub section: Section,
+    pub episodes: Vec<SectionEpisode>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SeasonListData {
+    #[serde(default)]
+    seasons: Option<Vec<SeasonItem>>,
+    #[serde(default)]
+    total: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SeasonItem {
+    #[serde(default)]
+    season: Season,
+    #[serde(default)]
+    sections: SeasonSections,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SeasonSections {
+    #[serde(default)]
+    sections: Option<Vec<Section>>,
+}
+
+impl From<SeasonItem> for SeasonDetail {
+    fn from(item: SeasonItem) -> Self {
+        Self {
+            season: item.season,
+            sections: item.sections.sections.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SectionData {
+    #[serde(default)]
+    section: Section,
+    #[serde(default)]
+    episodes: Option<Vec<SectionEpisode>>,
+}
+
+/// 投稿时选择合集使用的小节
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionOption {
+    pub section_id: Option<u64>,
+    pub title: String,
+}
+
+/// 投稿时选择合集使用的合集，`section_id` 为默认小节
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonOption {
+    pub season_id: Option<u64>,
+    pub section_id: Option<u64>,
+    pub title: String,
+    pub sections: Vec<SectionOption>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonList {
+    pub seasons: Vec<SeasonOption>,
+}
+
+impl From<SeasonDetail> for SeasonOption {
+    fn from(detail: SeasonDetail) -> Self {
+        let title = detail.season.title;
+        let sections: Vec<SectionOption> = detail
+            .sections
+            .into_iter()
+            .map(|section| SectionOption {
+                section_id: Some(section.id).filter(|id| *id != 0),
+                title: Some(section.title)
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| title.clone()),
+            })
+            .collect();
+
+        Self {
+            season_id: Some(detail.season.id).filter(|id| *id != 0),
+            section_id: sections.first().and_then(|section| section.section_id),
+            title,
+            sections,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct VideoSeason {
+    #[serde(default)]
+    id: u64,
+}
+
+/// 创建或修改合集时填写的信息
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SeasonEdit {
+    pub title: String,
+    #[serde(default)]
+    pub desc: String,
+    /// 封面地址，可先通过 `upload_cover` 上传
+    #[serde(default)]
+    pub cover: String,
+    #[serde(default)]
+    pub is_end: bool,
+}
+
+/// 加入合集小节的分集
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Episode {
+    pub title: String,
+    pub aid: u64,
+    pub cid: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EpisodeAdd<'a> {
+    episodes: &'a [Episode],
+    section_id: u64,
+    csrf: &'a str,
+}
+
+/// 切换稿件所属合集，合集与小节为空时移出合集
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonSwitch {
+    pub season_id: Option<u64>,
+    pub section_id: Option<u64>,
+    pub title: String,
+    pub aid: u64,
+    pub cid: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct SeasonSwitchBody<'a> {
+    #[serde(flatten)]
+    switch: &'a SeasonSwitch,
+    csrf: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct SeasonAdd<'a> {
+    title: &'a str,
+    desc: &'a str,
+    cover: &'a str,
+    season_price: u32,
+    csrf: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SeasonMeta<'a> {
+    id: u64,
+    title: &'a str,
+    desc: &'a str,
+    cover: &'a str,
+    is_end: u8,
+    #[serde(rename = "season_price")]
+    season_price: u32,
+}
+
+/// 排序项，`sort` 从 1 开始
+#[derive(Debug, Serialize)]
+struct SortItem {
+    id: u64,
+    sort: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SeasonEditBody<'a> {
+    season: SeasonMeta<'a>,
+    sorts: Vec<SortItem>,
+    csrf: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SectionAdd<'a> {
+    season_id: u64,
+    title: &'a str,
+    csrf: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SectionMeta<'a> {
+    id: u64,
+    season_id: u64,
+    title: &'a str,
+    #[serde(rename = "type")]
+    section_type: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct SectionEditBody<'a> {
+    section: SectionMeta<'a>,
+    sorts: Vec<SortItem>,
+    csrf: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct DeleteBody<'a> {
+    id: u64,
+    csrf: &'a str,
+}
+
+/// 按新顺序生成排序项，`order` 必须包含且只包含 `current` 中的全部 ID
+fn sort_items(current: &[u64], order: &[u64], what: &str) -> Result<Vec<SortItem>, AppError> {
+    let mut expected = current.to_vec();
+    let mut actual = order.to_vec();
+    expected.sort_unstable();
+    actual.sort_unstable();
+    if expected != actual {
+        return Err(AppError::Custom(format!(
+            "{what}排序与现有{what}不一致，请刷新后重试"
+        )));
+    }
+    Ok(order
+        .iter()
+        .enumerate()
+        .map(|(index, id)| SortItem {
+            id: *id,
+            sort: index + 1,
+        })
+        .collect())
+}
+
+/// 创建接口返回的新 ID
+fn created_id(res: &Value, what: &str) -> Result<u64, AppError> {
+    res["data"]
+        .as_u64()
+        .ok_or_else(|| AppError::Biliup(format!("创建{what}返回值错误: {res}")))
+}
+
+/// 合集管理，接口均位于创作中心
+impl CreativeApi<'_> {
+    /// 分页获取合集及其小节
+    pub async fn season_page(&self, pn: u32, ps: u32) -> Result<SeasonPage, AppError> {
+        let url = self.url(
+            "/x2/creative/web/seasons",
+            &[
+                ("pn", pn.to_string()),
+                ("ps", ps.to_string()),
+                ("order", "desc".to_string()),
+                ("sort", "mtime".to_string()),
+                ("filter", "1".to_string()),
+            ],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        let data: SeasonListData = parse_data(res, "合集列表")?;
+        Ok(SeasonPage {
+            seasons: data
+                .seasons
+                .unwrap_or_default()
+                .into_iter()
+                .map(SeasonDetail::from)
+                .collect(),
+            pn,
+            ps,
+            total: data.total,
+        })
+    }
+
+    /// 账号的全部合集，用于投稿时选择
+    pub async fn seasons(&self) -> Result<SeasonList, AppError> {
+        let mut seasons = Vec::new();
+        for pn in 1..=SEASON_MAX_PAGES {
+            let page = self.season_page(pn, SEASON_PAGE_SIZE).await?;
+            let fetched = page.seasons.len();
+            seasons.extend(page.seasons.into_iter().map(SeasonOption::from));
+            if fetched < SEASON_PAGE_SIZE as usize || seasons.len() as u64 >= page.total {
+                break;
+            }
+        }
+        Ok(SeasonList { seasons })
+    }
+
+    /// 合集详情
+    pub async fn season(&self, season_id: u64) -> Result<SeasonDetail, AppError> {
+        let url = self.url(
+            "/x2/creative/web/season",
+            &[("id", season_id.to_string())],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        Ok(parse_data::<SeasonItem>(res, "合集详情")?.into())
+    }
+
+    /// 创建合集，返回合集 ID
+    ///
+    /// 重复提交会创建多个同名合集，请求失败时不自动重试。
+    pub async fn create_season(&self, season: &SeasonEdit) -> Result<u64, AppError> {
+        let url = self.url("/x2/creative/web/season/add", &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = SeasonAdd {
+            title: &season.title,
+            desc: &season.desc,
+            cover: &season.cover,
+            season_price: 0,
+            csrf: &csrf,
+        };
+        let res = self.client.api.post_json(&url, &body).await?;
+        let season_id = created_id(&res, "合集")?;
+        info!("已创建合集 {}: {}", season_id, season.title);
+        Ok(season_id)
+    }
+
+    /// 修改合集信息，小节顺序保持不变
+    pub async fn edit_season(&self, season_id: u64, season: &SeasonEdit) -> Result<(), AppError> {
+        let current = self.season(season_id).await?;
+        let order: Vec<u64> = current.sections.iter().map(|section| section.id).collect();
+        self.post_season_edit(season_id, season, &order, &order)
+            .await
+    }
+
+    /// 调整合集中小节的顺序
+    pub async fn sort_sections(&self, season_id: u64, order: &[u64]) -> Result<(), AppError> {
+        let current = self.season(season_id).await?;
+        let ids: Vec<u64> = current.sections.iter().map(|section| section.id).collect();
+        let season = SeasonEdit {
+            title: current.season.title,
+            desc: current.season.desc,
+            cover: current.season.cover,
+            is_end: current.season.is_end != 0,
+        };
+        self.post_season_edit(season_id, &season, &ids, order).await
+    }
+
+    async fn post_season_edit(
+        &self,
+        season_id: u64,
+        season: &SeasonEdit,
+        current: &[u64],
+        order: &[u64],
+    ) -> Result<(), AppError> {
+        let url = self.url("/x2/creative/web/season/edit", &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = SeasonEditBody {
+            season: SeasonMeta {
+                id: season_id,
+                title: &season.title,
+                desc: &season.desc,
+                cover: &season.cover,
+                is_end: u8::from(season.is_end),
+                season_price: 0,
+            },
+            sorts: sort_items(current, order, "小节")?,
+            csrf: &csrf,
+        };
+        // 提交的是完整的合集信息与顺序，重复提交结果相同
+        self.client.api.post_json_idempotent(&url, &body).await?;
+        Ok(())
+    }
+
+    /// 删除合集，合集中的稿件不会被删除
+    pub async fn delete_season(&self, season_id: u64) -> Result<(), AppError> {
+        self.post_delete("/x2/creative/web/season/del", season_id)
+            .await
+    }
+
+    /// 在合集中创建小节，返回小节 ID
+    ///
+    /// 重复提交会创建多个同名小节，请求失败时不自动重试。
+    pub async fn create_section(&self, season_id: u64, title: &str) -> Result<u64, AppError> {
+        let url = self.url("/x2/creative/web/season/section/add", &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = SectionAdd {
+            season_id,
+            title,
+            csrf: &csrf,
+        };
+        let res = self.client.api.post_json(&url, &body).await?;
+        created_id(&res, "小节")
+    }
+
+    /// 小节详情及其中的分集
+    pub async fn section(&self, section_id: u64) -> Result<SectionDetail, AppError> {
+        let url = self.url(
+            "/x2/creative/web/season/section",
+            &[("id", section_id.to_string())],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        let data: SectionData = parse_data(res, "小节详情")?;
+        Ok(SectionDetail {
+            section: data.section,
+            episodes: data.episodes.unwrap_or_default(),
+        })
+    }
+
+    /// 重命名小节，分集顺序保持不变
+    pub async fn rename_section(&self, section_id: u64, title: &str) -> Result<(), AppError> {
+        let current = self.section(section_id).await?;
+        let order: Vec<u64> = current.episodes.iter().map(|episode| episode.id).collect();
+        self.post_section_edit(&current.section, title, &order, &order)
+            .await
+    }
+
+    /// 调整小节中分集的顺序，`order` 为分集 ID
+    pub async fn sort_episodes(&self, section_id: u64, order: &[u64]) -> Result<(), AppError> {
+        let current = self.section(section_id).await?;
+        let ids: Vec<u64> = current.episodes.iter().map(|episode| episode.id).collect();
+        self.post_section_edit(&current.section, &current.section.title, &ids, order)
+            .await
+    }
+
+    async fn post_section_edit(
+        &self,
+        section: &Section,
+        title: &str,
+        current: &[u64],
+        order: &[u64],
+    ) -> Result<(), AppError> {
+        let url = self.url("/x2/creative/web/season/section/edit", &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = SectionEditBody {
+            section: SectionMeta {
+                id: section.id,
+                season_id: section.season_id,
+                title,
+                section_type: match section.section_type {
+                    0 => SECTION_TYPE_NORMAL,
+                    section_type => section_type,
+                },
+            },
+            sorts: sort_items(current, order, "分集")?,
+            csrf: &csrf,
+        };
+        // 提交的是完整的小节信息与顺序，重复提交结果相同
+        self.client.api.post_json_idempotent(&url, &body).await?;
+        Ok(())
+    }
+
+    /// 删除小节，小节中需要没有分集
+    pub async fn delete_section(&self, section_id: u64) -> Result<(), AppError> {
+        self.post_delete("/x2/creative/web/season/section/del", section_id)
+            .await
+    }
+
+    /// 稿件所属合集，不在合集中时返回 0
+    pub async fn video_season(&self, aid: u64) -> Result<u64, AppError> {
+        let url = self.url(
+            "/x2/creative/web/season/aid",
+            &[("id", aid.to_string())],
+            false,
+        )?;
+        let res = self.client.api.get(&url).await?;
+        Ok(parse_data::<VideoSeason>(res, "稿件合集信息")?.id)
+    }
+
+    /// 将分集加入合集小节
+    ///
+    /// 请求失败时不自动重试，重新提交前需确认分集是否已经加入小节。
+    pub async fn add_episodes(
+        &self,
+        section_id: u64,
+        episodes: &[Episode],
+    ) -> Result<Value, AppError> {
+        let url = self.url("/x2/creative/web/season/section/episodes/add", &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = EpisodeAdd {
+            episodes,
+            section_id,
+            csrf: &csrf,
+        };
+        self.client.api.post_json(&url, &body).await
+    }
+
+    /// 将分集移出小节，`episode_id` 为分集 ID 而非 aid
+    pub async fn remove_episode(&self, episode_id: u64) -> Result<(), AppError> {
+        self.post_delete("/x2/creative/web/season/section/episode/del", episode_id)
+            .await
+    }
+
+    /// 切换稿件所属合集
+    pub async fn switch_season(&self, switch: &SeasonSwitch) -> Result<Value, AppError> {
+        let url = self.url("/x2/creative/web/season/switch", &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = SeasonSwitchBody {
+            switch,
+            csrf: &csrf,
+        };
+        self.client.api.post_json(&url, &body).await
+    }
+
+    /// 上传合集封面，返回封面地址
+    pub async fn upload_cover(&self, cover: &[u8]) -> Result<String, AppError> {
+        self.client
+            .bilibili
+            .cover_up(cover)
+            .await
+            .map_err(AppError::from_biliup)
+    }
+
+    async fn post_delete(&self, path: &str, id: u64) -> Result<(), AppError> {
+        let url = self.url(path, &[], true)?;
+        let csrf = self.client.get_csrf()?;
+        let body = DeleteBody { id, csrf: &csrf };
+        self.client.api.post_json(&url, &body).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{Duration, sleep};
+use tracing::{info, warn};
+
+use crate::{
+    AppData, MyClient,
+    error::{AppError, ErrorCategory},
+    services::{bili_api::is_retryable_error, season_api::Episode},
+};
+
+pub const SEASON_JOIN_EVENT: &str = "season-join-finished";
+
+/// 投稿后首次尝试加入合集前的等待时间，等待分P生成 cid
+const JOIN_FIRST_DELAY: Duration = Duration::from_secs(10);
+/// 加入合集失败后的重试间隔
+const JOIN_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// 加入合集的最大尝试次数
+const JOIN_MAX_ATTEMPTS: u32 = 10;
+
+/// 投稿成功后将稿件加入模板配置的合集
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonJoin {
+    pub uid: u64,
+    pub aid: u64,
+    pub season_id: u64,
+    /// 为空时加入合集的第一个小节
+    pub section_id: Option<u64>,
+    /// 分集标题
+    pub title: String,
+}
+
+/// 加入合集结束后通知前端的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonJoinEvent {
+    #[serde(flatten)]
+    pub join: SeasonJoin,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl SeasonJoin {
+    /// 在后台等待稿件可加入合集后添加，结束后发送事件
+    pub fn spawn(self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let result = self.run(&app).await;
+            match &result {
+                Ok(()) => info!("稿件 av{} 已加入合集 {}", self.aid, self.season_id),
+                Err(e) => warn!(
+                    "稿件 av{} 加入合集 {} 失败: {}",
+                    self.aid, self.season_id, e
+                ),
+            }
+            let event = SeasonJoinEvent {
+                join: self,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            };
+            if let Err(e) = app.emit(SEASON_JOIN_EVENT, event) {
+                warn!("发送合集事件失败: {}", e);
+            }
+        });
+    }
+
+    async fn run(&self, app: &AppHandle) -> Result<(), AppError> {
+        sleep(JOIN_FIRST_DELAY).await;
+
+        let mut attempt = 1;
+        loop {
+            let client = app.state::<AppData>().get_client(self.uid).await?;
+            match self.try_join(&client).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < JOIN_MAX_ATTEMPTS && is_retryable(&e) => {
+                    info!(
+                        "稿件 av{} 暂时无法加入合集（第 {} 次尝试）: {}",
+                        self.aid, attempt, e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+            sleep(JOIN_RETRY_DELAY).await;
+            attempt += 1;
+        }
+    }
+
+    async fn try_join(&self, client: &MyClient) -> Result<(), AppError> {
+        let creative = client.creative();
+
+        // 上次提交可能已经成功但没有收到响应，已在合集中时不再重复添加
+        if creative.video_season(self.aid).await? == self.season_id {
+            info!("稿件 av{} 已在合集 {} 中", self.aid, self.season_id);
+            return Ok(());
+        }
+
+        let cid = creative
+            .archive_cids(self.aid)
+            .await
+            .map_err(not_ready_if_missing)?
+            .first()
+            .copied()
+            .filter(|cid| *cid != 0)
+            .ok_or_else(|| AppError::NotReady("稿件分P尚未生成".to_string()))?;
+
+        let section_id = match self.section_id {
+            Some(section_id) => section_id,
+            None => creative
+                .season(self.season_id)
+                .await?
+                .sections
+                .first()
+                .map(|section| section.id)
+                .ok_or_else(|| AppError::Custom("合集中没有小节".to_string()))?,
+        };
+
+        let episode = Episode {
+            title: self.title.clone(),
+            aid: self.aid,
+            cid,
+        };
+        creative
+            .add_episodes(section_id, std::slice::from_ref(&episode))
+            .await?;
+        Ok(())
+    }
+}
+
+/// 刚投稿的稿件在创作中心接口中可能暂时查不到
+pub(crate) fn not_ready_if_missing(error: AppError) -> AppError {
+    match error.category() {
+        ErrorCategory::NotFound => AppError::NotReady(error.to_string()),
+        _ => error,
+    }
+}
+
+/// 只重试分P尚未生成以及网络、限流等暂时性错误
+pub(crate) fn is_retryable(error: &AppError) -> bool {
+    matches!(error, AppError::NotReady(_)) || is_retryable_error(error)
+}
@@ -0,0 +1,347 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex as StdMutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{Duration, sleep};
+use tracing::{info, warn};
+
+use crate::{
+    AppData,
+    error::AppError,
+    models::{TemplateConfig, validation::FieldError},
+    services::{
+        creative_api::ArchiveParts,
+        season_service::{is_retryable, not_ready_if_missing},
+    },
+    utils::{
+        get_subtitle_record_path,
+        subtitle::{self, Bcc},
+        write_file_atomic,
+    },
+};
+
+pub const SUBTITLE_UPLOAD_EVENT: &str = "subtitle-upload-finished";
+
+/// 模板未设置字幕语言时使用的语言
+const DEFAULT_SUBTITLE_LAN: &str = "zh-CN";
+/// 投稿后首次获取分P cid 前的等待时间
+const UPLOAD_FIRST_DELAY: Duration = Duration::from_secs(10);
+/// 分P cid 尚未生成时的重试间隔
+const UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// 获取分P cid 的最大尝试次数
+const UPLOAD_MAX_ATTEMPTS: u32 = 10;
+
+/// 串行化字幕记录的读写，避免多个上传任务互相覆盖
+static RECORD_LOCK: StdMutex<()> = StdMutex::new(());
+
+/// 已上传的字幕，编辑稿件时跳过内容未变化的字幕
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubtitleRecord {
+    pub uid: u64,
+    /// 以 `cid:语言` 为键，记录最近一次上传的字幕内容摘要
+    pub digests: HashMap<String, String>,
+}
+
+impl SubtitleRecord {
+    fn path(uid: u64) -> anyhow::Result<PathBuf> {
+        Ok(get_subtitle_record_path()?.join(format!("{uid}.json")))
+    }
+
+    /// 读取记录，不存在或无法解析时返回空记录
+    pub fn load(uid: u64) -> Self {
+        let empty = Self {
+            uid,
+            ..Default::default()
+        };
+        let Ok(path) = Self::path(uid) else {
+            return empty;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("字幕记录 {} 无法解析，已忽略: {}", path.display(), e);
+                empty
+            }),
+            Err(_) => empty,
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = serde_json::to_vec(self)?;
+        write_file_atomic(&Self::path(self.uid)?, &content)
+    }
+
+    /// 字幕与最近一次上传的内容相同
+    fn is_uploaded(&self, cid: u64, lan: &str, digest: &str) -> bool {
+        self.digests
+            .get(&record_key(cid, lan))
+            .is_some_and(|uploaded| uploaded == digest)
+    }
+
+    /// 导入备份中的字幕记录，本地已有的记录保持不变
+    pub fn import(backup: &Self) -> anyhow::Result<()> {
+        let _guard = RECORD_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut record = Self::load(backup.uid);
+        for (key, digest) in &backup.digests {
+            record
+                .digests
+                .entry(key.clone())
+                .or_insert_with(|| digest.clone());
+        }
+        record.save()
+    }
+
+    /// 记录上传成功的字幕
+    fn record(uid: u64, cid: u64, lan: &str, digest: String) -> anyhow::Result<()> {
+        let _guard = RECORD_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut record = Self::load(uid);
+        record.digests.insert(record_key(cid, lan), digest);
+        record.save()
+    }
+}
+
+fn record_key(cid: u64, lan: &str) -> String {
+    format!("{cid}:{lan}")
+}
+
+/// 字幕内容的摘要，转换为 BCC 后计算，与原始文件格式无关
+fn bcc_digest(bcc: &Bcc) -> Option<String> {
+    let content = serde_json::to_vec(bcc).ok()?;
+    Some(
+        Sha256::digest(&content)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect(),
+    )
+}
+
+/// 单个分P的字幕
+#[derive(Debug, Clone, Serialize)]
+pub struct PartSubtitle {
+    /// 分P下标，从 0 开始
+    pub index: usize,
+    pub path: String,
+    #[serde(skip)]
+    pub bcc: Bcc,
+}
+
+/// 读取表单中各分P关联的字幕文件并转换为 BCC，无效的字幕作为字段错误返回
+pub fn prepare(form: &TemplateConfig) -> Result<Vec<PartSubtitle>, Vec<FieldError>> {
+    let mut parts = Vec::new();
+    let mut errors = Vec::new();
+    for (index, video) in form.videos.iter().enumerate() {
+        if video.subtitle_path.trim().is_empty() {
+            continue;
+        }
+        match subtitle::load_subtitle(Path::new(&video.subtitle_path)) {
+            Ok(bcc) => parts.push(PartSubtitle {
+                index,
+                path: video.subtitle_path.clone(),
+                bcc,
+            }),
+            Err(e) => errors.push(FieldError::new(
+                format!("videos[{index}].subtitle_path"),
+                format!("第 {} 个分P的字幕无效: {e}", index + 1),
+            )),
+        }
+    }
+    if errors.is_empty() {
+        Ok(parts)
+    } else {
+        Err(errors)
+    }
+}
+
+/// 模板设置的字幕语言
+pub fn subtitle_lan(form: &TemplateConfig) -> String {
+    match form.subtitle.lan.trim() {
+        "" => DEFAULT_SUBTITLE_LAN.to_string(),
+        lan => lan.to_string(),
+    }
+}
+
+/// 投稿或编辑成功后为各分P上传字幕
+#[derive(Debug, Clone, Serialize)]
+pub struct SubtitleUpload {
+    pub uid: u64,
+    pub aid: u64,
+    pub bvid: Option<String>,
+    pub lan: String,
+    pub parts: Vec<PartSubtitle>,
+}
+
+/// 单个分P的上传结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PartSubtitleResult {
+    pub index: usize,
+    pub path: String,
+    pub error: Option<String>,
+    /// 字幕与已上传的内容相同，未重复提交
+    pub skipped: bool,
+}
+
+/// 字幕上传结束后通知前端的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct SubtitleUploadEvent {
+    pub uid: u64,
+    pub aid: u64,
+    pub results: Vec<PartSubtitleResult>,
+}
+
+impl SubtitleUpload {
+    /// 在后台等待分P生成 cid 后上传字幕，结束后发送事件
+    pub fn spawn(self, app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let results = self.run(&app).await;
+            let failed = results.iter().filter(|r| r.error.is_some()).count();
+            let skipped = results.iter().filter(|r| r.skipped).count();
+            if failed == 0 {
+                info!(
+                    "稿件 av{} 的 {} 个字幕已上传，{} 个未变化",
+                    self.aid,
+                    results.len() - skipped,
+                    skipped
+                );
+            } else {
+                warn!(
+                    "稿件 av{} 有 {}/{} 个字幕上传失败",
+                    self.aid,
+                    failed,
+                    results.len()
+                );
+            }
+            let event = SubtitleUploadEvent {
+                uid: self.uid,
+                aid: self.aid,
+                results,
+            };
+            if let Err(e) = app.emit(SUBTITLE_UPLOAD_EVENT, event) {
+                warn!("发送字幕事件失败: {}", e);
+            }
+        });
+    }
+
+    async fn run(&self, app: &AppHandle) -> Vec<PartSubtitleResult> {
+        let archive = match self.wait_for_parts(app).await {
+            Ok(archive) => archive,
+            Err(e) => return self.results(|_| Some(e.to_string())),
+        };
+        // 编辑稿件时表单中只有 aid，使用分P列表中的 BV 号
+        let bvid = self.bvid.clone().or(archive.bvid);
+
+        let client = match app.state::<AppData>().get_client(self.uid).await {
+            Ok(client) => client,
+            Err(e) => return self.results(|_| Some(e.to_string())),
+        };
+        let record = SubtitleRecord::load(self.uid);
+        let mut results = Vec::with_capacity(self.parts.len());
+        for part in &self.parts {
+            let cid = archive.cids.get(part.index).copied().unwrap_or_default();
+            let digest = bcc_digest(&part.bcc);
+            if digest
+                .as_deref()
+                .is_some_and(|digest| record.is_uploaded(cid, &self.lan, digest))
+            {
+                info!(
+                    "第 {} 个分P的字幕 {} 未变化，跳过上传",
+                    part.index + 1,
+                    part.path
+                );
+                results.push(PartSubtitleResult {
+                    index: part.index,
+                    path: part.path.clone(),
+                    error: None,
+                    skipped: true,
+                });
+                continue;
+            }
+
+            let error = match client
+                .creative()
+                .save_subtitle(cid, bvid.as_deref(), &self.lan, &part.bcc)
+                .await
+            {
+                Ok(subtitle_id) => {
+                    info!(
+                        "已上传第 {} 个分P的字幕 {} (cid {}, 字幕 {})",
+                        part.index + 1,
+                        part.path,
+                        cid,
+                        subtitle_id
+                    );
+                    if let Some(digest) = digest
+                        && let Err(e) = SubtitleRecord::record(self.uid, cid, &self.lan, digest)
+                    {
+                        warn!("保存字幕记录失败: {}", e);
+                    }
+                    None
+                }
+                Err(e) => {
+                    warn!("上传第 {} 个分P的字幕失败: {}", part.index + 1, e);
+                    Some(e.to_string())
+                }
+            };
+            results.push(PartSubtitleResult {
+                index: part.index,
+                path: part.path.clone(),
+                error,
+                skipped: false,
+            });
+        }
+        results
+    }
+
+    /// 等待需要上传字幕的分P全部生成 cid
+    async fn wait_for_parts(&self, app: &AppHandle) -> Result<ArchiveParts, AppError> {
+        sleep(UPLOAD_FIRST_DELAY).await;
+
+        let mut attempt = 1;
+        loop {
+            let client = app.state::<AppData>().get_client(self.uid).await?;
+            let result = client
+                .creative()
+                .archive_parts(self.aid)
+                .await
+                .map_err(not_ready_if_missing)
+                .and_then(|archive| {
+                    let ready = self
+                        .parts
+                        .iter()
+                        .all(|part| archive.cids.get(part.index).is_some_and(|cid| *cid != 0));
+                    if ready {
+                        Ok(archive)
+                    } else {
+                        Err(AppError::NotReady("稿件分P尚未生成".to_string()))
+                    }
+                });
+            match result {
+                Ok(archive) => return Ok(archive),
+                Err(e) if attempt < UPLOAD_MAX_ATTEMPTS && is_retryable(&e) => {
+                    info!(
+                        "稿件 av{} 暂时无法上传字幕（第 {} 次尝试）: {}",
+                        self.aid, attempt, e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+            sleep(UPLOAD_RETRY_DELAY).await;
+            attempt += 1;
+        }
+    }
+
+    fn results(&self, error: impl Fn(&PartSubtitle) -> Option<String>) -> Vec<PartSubtitleResult> {
+        self.parts
+            .iter()
+            .map(|part| PartSubtitleResult {
+                index: part.index,
+                path: part.path.clone(),
+                error: error(part),
+                skipped: false,
+            })
+            .collect()
+    }
+}
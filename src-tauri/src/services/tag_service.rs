@@ -0,0 +1,195 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    models::{TemplateConfig, upload_form::TAG_MAX_CHARS},
+    utils::{get_tag_history_path, write_file_atomic},
+};
+
+/// 返回的推荐标签数量上限
+const SUGGESTION_LIMIT: usize = 30;
+
+/// 标签的使用情况
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagUsage {
+    pub count: u32,
+    /// 最近一次投稿使用的时间（秒级时间戳）
+    pub last_used: i64,
+}
+
+/// 投稿成功时记录的标签
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagHistory {
+    pub uid: u64,
+    pub tags: HashMap<String, TagUsage>,
+}
+
+impl TagHistory {
+    fn path(uid: u64) -> Result<PathBuf> {
+        Ok(get_tag_history_path()?.join(format!("{uid}.json")))
+    }
+
+    /// 读取记录，不存在或无法解析时返回空记录
+    pub fn load(uid: u64) -> Self {
+        let empty = Self {
+            uid,
+            ..Default::default()
+        };
+        let Ok(path) = Self::path(uid) else {
+            return empty;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("标签记录 {} 无法解析，已忽略: {}", path.display(), e);
+                empty
+            }),
+            Err(_) => empty,
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_vec(self)?;
+        write_file_atomic(&Self::path(self.uid)?, &content)
+    }
+}
+
+/// 记录一次投稿使用的标签
+pub fn record(uid: u64, tags: &[String]) -> Result<()> {
+    let mut history = TagHistory::load(uid);
+    let now = chrono::Utc::now().timestamp();
+    for tag in tags.iter().filter(|tag| is_valid(tag)) {
+        let usage = history.tags.entry(tag.clone()).or_default();
+        usage.count += 1;
+        usage.last_used = now;
+    }
+    history.save()
+}
+
+/// 合并备份中的标签记录，同一标签取较大的使用次数与较晚的使用时间
+pub fn merge_history(backup: &TagHistory) -> Result<()> {
+    let mut history = TagHistory::load(backup.uid);
+    for (tag, backup_usage) in &backup.tags {
+        let usage = history.tags.entry(tag.clone()).or_default();
+        usage.count = usage.count.max(backup_usage.count);
+        usage.last_used = usage.last_used.max(backup_usage.last_used);
+    }
+    history.save()
+}
+
+/// 统计各模板中标签出现的次数
+pub fn template_tag_counts<'a>(
+    templates: impl IntoIterator<Item = &'a TemplateConfig>,
+) -> HashMap<String, u32> {
+    let mut counts = HashMap::new();
+    for template in templates {
+        for tag in template.tag_list() {
+            *counts.entry(tag.to_string()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// 推荐标签的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSource {
+    /// B站按标题推荐
+    Recommend,
+    /// 本地模板与投稿记录中常用的标签
+    Local,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagSuggestion {
+    pub tag: String,
+    pub source: TagSource,
+    /// 在模板与投稿记录中出现的次数
+    pub uses: u32,
+}
+
+/// 可以作为标签提交：非空、不超长、不含分隔符与未展开的模板变量
+fn is_valid(tag: &str) -> bool {
+    !tag.is_empty() && tag.chars().count() <= TAG_MAX_CHARS && !tag.contains([',', '，', '{', '}'])
+}
+
+/// 合并B站推荐与本地常用的标签，去掉重复及已填写的标签
+///
+/// B站推荐的排在前面，本地标签按使用次数与最近使用时间排序。
+pub fn suggest(
+    uid: u64,
+    recommended: Vec<String>,
+    template_counts: HashMap<String, u32>,
+    current: &[String],
+) -> Vec<TagSuggestion> {
+    let history = TagHistory::load(uid);
+
+    // 大小写不同的标签视为同一个，以小写为键
+    let mut local: HashMap<String, LocalTag> = HashMap::new();
+    let usages = template_counts
+        .into_iter()
+        .map(|(tag, count)| (tag, count, 0))
+        .chain(
+            history
+                .tags
+                .into_iter()
+                .map(|(tag, usage)| (tag, usage.count, usage.last_used)),
+        );
+    for (tag, count, last_used) in usages {
+        let entry = local.entry(tag.to_lowercase()).or_insert_with(|| LocalTag {
+            tag,
+            uses: 0,
+            last_used: 0,
+        });
+        entry.uses += count;
+        entry.last_used = entry.last_used.max(last_used);
+    }
+    let mut ranked: Vec<&LocalTag> = local.values().collect();
+    ranked.sort_by(|a, b| {
+        b.uses
+            .cmp(&a.uses)
+            .then(b.last_used.cmp(&a.last_used))
+            .then_with(|| a.tag.cmp(&b.tag))
+    });
+
+    let mut seen: HashSet<String> = current
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .collect();
+    let recommended = recommended
+        .iter()
+        .map(|tag| (tag.as_str(), TagSource::Recommend));
+    let local_tags = ranked
+        .into_iter()
+        .map(|local| (local.tag.as_str(), TagSource::Local));
+
+    let mut suggestions = Vec::new();
+    for (tag, source) in recommended.chain(local_tags) {
+        let tag = tag.trim();
+        let key = tag.to_lowercase();
+        if !is_valid(tag) || seen.contains(&key) {
+            continue;
+        }
+        suggestions.push(TagSuggestion {
+            tag: tag.to_string(),
+            source,
+            uses: local.get(&key).map_or(0, |local| local.uses),
+        });
+        seen.insert(key);
+        if suggestions.len() >= SUGGESTION_LIMIT {
+            break;
+        }
+    }
+    suggestions
+}
+
+struct LocalTag {
+    tag: String,
+    uses: u32,
+    last_used: i64,
+}
@@ -2,7 +2,11 @@ use std::{collections::HashMap, path::PathBuf, pin::Pin, sync::Arc, task::Poll};
 
 use crate::{
     MyClient,
-    models::{ConfigRoot, UploadTask, User, VideoInfo},
+    models::{
+        ConfigRoot, UploadTask, User, VideoInfo, notification::NotifyEvent, proxy::ProxyRoute,
+    },
+    services::notification_service::{Notification, Notifier},
+    utils::media,
 };
 use anyhow::Result;
 use biliup::{
@@ -13,7 +17,10 @@ use bytes::{Buf, Bytes};
 use futures::Stream;
 use futures::StreamExt;
 use indexmap::IndexMap;
-use reqwest::Body;
+use reqwest::{
+    Body,
+    header::{self, HeaderMap, HeaderValue},
+};
 use tauri::async_runtime::block_on;
 use tokio::{
     select,
@@ -46,7 +53,7 @@ impl UploadService {
         debug!("更新最大并发上传数: {} -> {}", old, max_curr);
     }
 
-    pub fn new(max_curr: u32) -> Self {
+    pub fn new(max_curr: u32, notifier: Notifier) -> Self {
         let max_running = Arc::new(Mutex::new(max_curr));
         let max_running_clone = Arc::clone(&max_running);
 
@@ -67,6 +74,7 @@ impl UploadService {
                     upload_handle_clone,
                     max_running_clone,
                     stop_rx,
+                    notifier,
                 )
                 .await;
             }),
@@ -197,6 +205,7 @@ async fn upload_background(
     handle: Arc<Mutex<HashMap<String, task::JoinHandle<()>>>>,
     max_running: Arc<Mutex<u32>>,
     mut stop_rx: mpsc::Receiver<()>,
+    notifier: Notifier,
 ) {
     let mut one_sec = tokio::time::interval(tokio::time::Duration::from_secs(1));
     loop {
@@ -209,7 +218,7 @@ async fn upload_background(
                 return;
             }
             _ = one_sec.tick() => {
-                upload_background_interval(queue_clone, handle_clone, max_running_clone).await;
+                upload_background_interval(queue_clone, handle_clone, max_running_clone, &notifier).await;
             }
         }
     }
@@ -219,6 +228,7 @@ async fn upload_background_interval(
     queue: Arc<Mutex<IndexMap<String, Arc<Mutex<UploadTask>>>>>,
     handle: Arc<Mutex<HashMap<String, task::JoinHandle<()>>>>,
     max_running: Arc<Mutex<u32>>,
+    notifier: &Notifier,
 ) {
     let current_running = handle.lock().await.len() as u32;
     let mut remain = max_running.lock().await.saturating_sub(current_running);
@@ -252,13 +262,25 @@ async fn upload_background_interval(
                 continue;
             }
             let task_mutex_clone = Arc::clone(&task_mutex);
+            let notifier = notifier.clone();
             handle.lock().await.insert(
                 task_id,
                 task::spawn(async move {
                     let task = Arc::clone(&task_mutex_clone);
-                    if let Err(e) = upload_impl(task).await {
-                        error!("上传任务失败: {}", e);
-                        task_mutex_clone.lock().await.fail(e.to_string());
+                    let result = upload_impl(task).await;
+                    let mut task = task_mutex_clone.lock().await;
+                    match result {
+                        Ok(()) if task.is_completed() => notifier
+                            .notify(Notification::new(NotifyEvent::UploadFinished, task.title())),
+                        Ok(()) => {}
+                        Err(e) => {
+                            error!("上传任务失败: {}", e);
+                            task.fail(e.to_string());
+                            notifier.notify(Notification::new(
+                                NotifyEvent::UploadFailed,
+                                format!("{}: {}", task.title(), e),
+                            ));
+                        }
                     }
                 }),
             );
@@ -320,12 +342,26 @@ impl From<ChunkedBuffer> for Body {
     }
 }
 
+/// 创建分片上传使用的客户端，与默认客户端的区别只在于代理
+fn upload_client(proxy: Option<&str>) -> StatelessClient {
+    let Some(proxy) = proxy else {
+        return StatelessClient::default();
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::REFERER,
+        HeaderValue::from_static("https://www.bilibili.com/"),
+    );
+    headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive"));
+    StatelessClient::new(headers, Some(proxy))
+}
+
 async fn upload_impl(task_mutex: Arc<Mutex<UploadTask>>) -> Result<()> {
     info!("开始上传任务: {}", task_title!(task_mutex));
     task_mutex.lock().await.start();
     let uid = task_mutex.lock().await.user.uid;
 
-    let (line, _proxy, limit) = {
+    let (line, upload_proxy, limit) = {
         let lock = task_mutex.lock().await;
         let config_root = lock.config().lock().await;
         let config = config_root
@@ -333,7 +369,11 @@ async fn upload_impl(task_mutex: Arc<Mutex<UploadTask>>) -> Result<()> {
             .get(&uid)
             .ok_or_else(|| anyhow::anyhow!("用户未登录或不存在"))?;
 
-        (config.line.clone(), config.proxy.clone(), config.limit)
+        (
+            config.line.clone(),
+            config_root.proxy_url(uid, ProxyRoute::Upload),
+            config.limit,
+        )
     };
 
     let client = &task_mutex
@@ -371,6 +411,9 @@ async fn upload_impl(task_mutex: Arc<Mutex<UploadTask>>) -> Result<()> {
     };
 
     info!("选择线路: {} (重试次数: {})", selected_line, retry_count);
+    if upload_proxy.is_some() {
+        info!("分片上传使用代理");
+    }
 
     let probe = match selected_line.as_str() {
         "bda2" => line::bda2(),
@@ -383,14 +426,31 @@ async fn upload_impl(task_mutex: Arc<Mutex<UploadTask>>) -> Result<()> {
     };
 
     let filepath = PathBuf::from(&task_mutex.lock().await.video.path);
+
+    // 记录本地文件信息，上传完成后 path 会被清空
+    let local_name = filepath
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_mtime = media::file_mtime(&filepath).unwrap_or(0);
+    let duration = media::probe_duration(&filepath).await.unwrap_or(0);
+    {
+        let mut task = task_mutex.lock().await;
+        task.video.local_name = local_name;
+        task.video.file_mtime = file_mtime;
+        task.video.duration = duration;
+    }
+
     let video_file = VideoFile::new(&filepath)?;
     let total_size = task_mutex.lock().await.total_size;
     let parcel = probe.pre_upload(client, video_file).await?;
     let (file_read_tx, mut file_read_rx) = mpsc::unbounded_channel();
     let (net_send_tx, mut net_send_rx) = mpsc::unbounded_channel();
     let mut uploaded = 0;
-    let mut video_fut = Box::pin(
-        parcel.upload(StatelessClient::default(), limit as usize, |vs| {
+    let mut video_fut = Box::pin(parcel.upload(
+        upload_client(upload_proxy.as_deref()),
+        limit as usize,
+        |vs| {
             vs.map(|chunk| {
                 // parcel.upload 将文件分成各个chunk
                 // 用ChunkedBuffer 将每个chunk再拆分成1MB一小段
@@ -403,8 +463,8 @@ async fn upload_impl(task_mutex: Arc<Mutex<UploadTask>>) -> Result<()> {
                 let chunked_buffer = ChunkedBuffer::new(chunk, net_send_tx.clone());
                 Ok((chunked_buffer, len))
             })
-        }),
-    );
+        },
+    ));
 
     let mut paused = false;
     loop {
@@ -1,7 +1,9 @@
 use crate::{
     models::{ConfigRoot, Subtitle, TemplateConfig, UserConfig, UserInfo, VideoInfo},
     services::validate_cookie_in_old_config,
-    utils::{get_config_json_path, get_config_yaml_path, get_old_cookie_file_path},
+    utils::{
+        get_config_json_path, get_config_yaml_path, get_old_cookie_file_path, write_file_atomic,
+    },
 };
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -99,7 +101,7 @@ impl CompatibilityConverter {
             let json_content = Self::convert_yaml_to_json(&yaml_content).await?;
 
             // 写入JSON文件
-            fs::write(&json_path, &json_content)?;
+            write_file_atomic(&json_path, json_content.as_bytes())?;
             Ok(true)
         } else {
             Ok(false)
@@ -150,6 +152,10 @@ impl CompatibilityConverter {
                             finished_at: 0,      // 旧版配置没有finished_at字段，默认0
                             encoding_status: 0,  // 旧版配置没有encoding_status字段，默认0
                             status_desc: String::new(), // 旧版配置没有status_desc字段，留空
+                            local_name: String::new(),
+                            file_mtime: 0,
+                            duration: 0,
+                            subtitle_path: String::new(),
                         });
                     }
                     vids
@@ -163,6 +169,7 @@ impl CompatibilityConverter {
                 season_id: None,  // 旧版配置没有season_id
                 section_id: None, // 旧版配置没有section_id
                 is_only_self: 0,  // 旧版配置没有is_only_self
+                season_episode_title: None,
                 dolby: streamer_config.dolby,
                 lossless_music: streamer_config.lossless_music,
                 no_reprint: streamer_config.no_reprint,
@@ -182,6 +189,11 @@ impl CompatibilityConverter {
                 staff: None,  // 旧版配置没有staff字段
                 state: None,
                 state_desc: None,
+                filename_pattern: None,
+                part_title_rule: None,
+                cover_spec: None,
+                parent: None,
+                inherit_fields: Vec::new(),
             };
 
             template.insert(streamer_name, template_config);
@@ -208,6 +220,8 @@ impl CompatibilityConverter {
                 cookie: bilibili.login_info,
             },
             proxy: None, // 旧版配置没有代理设置
+            api_proxy: None,
+            upload_proxy: None,
             line: legacy.line,
             limit: legacy.limit,
             watermark: 0,
@@ -0,0 +1,349 @@
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+use tracing::{info, warn};
+
+/// 当前配置结构版本，新增迁移步骤时递增
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// 配置文件由更高版本的程序写入
+#[derive(Debug, thiserror::Error)]
+#[error("配置文件版本 v{found} 高于当前程序支持的 v{supported}，请升级程序后再使用")]
+pub struct SchemaTooNew {
+    pub found: u32,
+    pub supported: u32,
+}
+
+type MigrationStep = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// 按版本顺序排列的迁移步骤，(起始版本, 说明, 迁移函数)
+const MIGRATIONS: &[(u32, &str, MigrationStep)] = &[
+    (0, "规范化旧版模板字段", migrate_v0_to_v1),
+    (1, "迁移新版分区字段", migrate_v1_to_v2),
+];
+
+/// 读取原始配置中的结构版本，缺失时视为 v0
+pub fn schema_version_of(value: &Value) -> Result<u32> {
+    match value.get("schema_version") {
+        None | Some(Value::Null) => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow::anyhow!("无效的配置版本: {version}")),
+    }
+}
+
+/// 确认磁盘上已有的配置可以被当前程序覆盖
+///
+/// 由更高版本写入的配置返回 [`SchemaTooNew`]；无法解析的内容交由调用方的恢复逻辑处理。
+pub fn ensure_overwritable(json_content: &str) -> Result<()> {
+    let Ok(value) = serde_json::from_str::<Value>(json_content) else {
+        return Ok(());
+    };
+    match schema_version_of(&value) {
+        Ok(version) if version > CURRENT_SCHEMA_VERSION => Err(SchemaTooNew {
+            found: version,
+            supported: CURRENT_SCHEMA_VERSION,
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// 将原始配置迁移到当前结构版本，返回是否执行了迁移
+pub fn migrate_config(value: &mut Value) -> Result<bool> {
+    let version = schema_version_of(value)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(SchemaTooNew {
+            found: version,
+            supported: CURRENT_SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("配置文件根节点不是对象"))?;
+
+    let mut migrated = false;
+    for (from, description, step) in MIGRATIONS.iter().filter(|(from, ..)| *from >= version) {
+        info!("迁移配置: v{} -> v{} ({})", from, from + 1, description);
+        step(root).map_err(|e| anyhow::anyhow!("配置迁移 v{from} 失败: {e}"))?;
+        root.insert("schema_version".to_string(), json!(from + 1));
+        migrated = true;
+    }
+
+    Ok(migrated)
+}
+
+/// 将键名从 `from` 改为 `to`，目标键已存在时丢弃旧值
+fn rename_key(object: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(value) = object.remove(from) {
+        object.entry(to.to_string()).or_insert(value);
+    }
+}
+
+/// 将布尔值形式的开关字段转换为 0/1
+fn bool_to_flag(object: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(Value::Bool(flag)) = object.get(*key) {
+            let flag = u8::from(*flag);
+            object.insert(key.to_string(), json!(flag));
+        }
+    }
+}
+
+fn user_configs_mut(
+    root: &mut Map<String, Value>,
+) -> impl Iterator<Item = &mut Map<String, Value>> {
+    root.get_mut("config")
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|users| users.values_mut())
+        .filter_map(Value::as_object_mut)
+}
+
+/// v0 -> v1：兼容旧版本及 B 站接口写入的字段名与布尔开关
+fn migrate_v0_to_v1(root: &mut Map<String, Value>) -> Result<()> {
+    const TEMPLATE_FLAGS: &[&str] = &[
+        "interactive",
+        "dolby",
+        "lossless_music",
+        "no_reprint",
+        "open_elec",
+        "no_disturbance",
+        "up_selection_reply",
+        "up_close_reply",
+        "up_close_danmu",
+        "is_only_self",
+        "watermark",
+    ];
+
+    for user_config in user_configs_mut(root) {
+        bool_to_flag(user_config, &["watermark", "auto_edit"]);
+
+        let Some(templates) = user_config
+            .get_mut("templates")
+            .and_then(Value::as_object_mut)
+        else {
+            continue;
+        };
+
+        for template in templates.values_mut().filter_map(Value::as_object_mut) {
+            rename_key(template, "atomicInt", "atomic_int");
+            rename_key(template, "charging_pay", "open_elec");
+            bool_to_flag(template, TEMPLATE_FLAGS);
+
+            // 旧版简介 v2 以字符串保存，无法还原为 Credit 列表
+            if template.get("desc_v2").is_some_and(Value::is_string) {
+                template.insert("desc_v2".to_string(), Value::Null);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取分区ID，兼容数字、数字字符串与 B 站接口返回的 `{"id": ...}` 对象
+fn partition_id(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(id) => id.as_u64(),
+        Value::String(id) => id.trim().parse().ok(),
+        Value::Object(object) => object.get("id").and_then(partition_id),
+        _ => None,
+    }
+}
+
+/// v1 -> v2：B 站接口写入的 `human_type2` 迁移到 `tid_v2`，字符串形式的分区ID转换为数字
+fn migrate_v1_to_v2(root: &mut Map<String, Value>) -> Result<()> {
+    for user_config in user_configs_mut(root) {
+        let Some(templates) = user_config
+            .get_mut("templates")
+            .and_then(Value::as_object_mut)
+        else {
+            continue;
+        };
+
+        for (name, template) in templates.iter_mut() {
+            let Some(template) = template.as_object_mut() else {
+                continue;
+            };
+
+            for key in ["tid", "tid_v2"] {
+                let Some(value) = template.get(key) else {
+                    continue;
+                };
+                match partition_id(value) {
+                    Some(id) => {
+                        template.insert(key.to_string(), json!(id));
+                    }
+                    None => {
+                        warn!("模板 {} 的 {} 无效，已重置: {}", name, key, value);
+                        template.remove(key);
+                    }
+                }
+            }
+
+            let human_type2 = template
+                .remove("human_type2")
+                .as_ref()
+                .and_then(partition_id);
+            let has_tid_v2 = template
+                .get("tid_v2")
+                .and_then(Value::as_u64)
+                .is_some_and(|id| id > 0);
+            if let Some(id) = human_type2.filter(|_| !has_tid_v2) {
+                template.insert("tid_v2".to_string(), json!(id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 与 biliup 登录后保存的 cookies.json 结构一致
+    fn login_info() -> Value {
+        json!({
+            "cookie_info": { "cookies": [] },
+            "sso": [],
+            "token_info": {
+                "access_token": "",
+                "expires_in": 0,
+                "mid": 10001,
+                "refresh_token": ""
+            },
+            "platform": null
+        })
+    }
+
+    /// 旧版程序写入的配置：没有版本号，字段名与开关沿用 B 站接口的写法
+    fn v0_fixture() -> Value {
+        json!({
+            "max_curr": 2,
+            "config": {
+                "10001": {
+                    "user": { "uid": 10001, "name": "测试", "cookie": login_info() },
+                    "watermark": true,
+                    "auto_edit": false,
+                    "templates": {
+                        "录播": {
+                            "title": "标题",
+                            "tid": "171",
+                            "human_type2": { "id": 2021 },
+                            "atomicInt": 1,
+                            "charging_pay": true,
+                            "dolby": false,
+                            "desc_v2": "旧版简介"
+                        },
+                        "已有新版分区": {
+                            "tid": 21,
+                            "tid_v2": 1001,
+                            "human_type2": 2021
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn template<'a>(value: &'a Value, name: &str) -> &'a Value {
+        &value["config"]["10001"]["templates"][name]
+    }
+
+    #[test]
+    fn migrates_v0_to_current() {
+        let mut value = v0_fixture();
+        assert!(migrate_config(&mut value).unwrap());
+        assert_eq!(value["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+
+        let user = &value["config"]["10001"];
+        assert_eq!(user["watermark"], json!(1));
+        assert_eq!(user["auto_edit"], json!(0));
+
+        let live = template(&value, "录播");
+        assert_eq!(live["atomic_int"], json!(1));
+        assert!(live.get("atomicInt").is_none());
+        assert_eq!(live["open_elec"], json!(1));
+        assert!(live.get("charging_pay").is_none());
+        assert_eq!(live["dolby"], json!(0));
+        assert_eq!(live["desc_v2"], Value::Null);
+        assert_eq!(live["tid"], json!(171));
+        assert_eq!(live["tid_v2"], json!(2021));
+        assert!(live.get("human_type2").is_none());
+
+        // 已有的新版分区不会被接口字段覆盖
+        let existing = template(&value, "已有新版分区");
+        assert_eq!(existing["tid_v2"], json!(1001));
+        assert!(existing.get("human_type2").is_none());
+    }
+
+    #[test]
+    fn migrated_config_can_be_loaded() {
+        let config = crate::models::ConfigRoot::from_json_str(&v0_fixture().to_string()).unwrap();
+        let template = &config.config[&10001].templates["录播"];
+        assert_eq!(template.tid, 171);
+        assert_eq!(template.tid_v2, 2021);
+        assert_eq!(config.config[&10001].watermark, 1);
+    }
+
+    #[test]
+    fn migrates_v1_to_v2_only() {
+        let mut value = json!({
+            "schema_version": 1,
+            "config": {
+                "10001": {
+                    "user": { "uid": 10001, "name": "测试", "cookie": login_info() },
+                    "templates": { "录播": { "tid": 171, "tid_v2": "abc", "human_type2": "2021" } }
+                }
+            }
+        });
+        assert!(migrate_config(&mut value).unwrap());
+        assert_eq!(value["schema_version"], json!(2));
+        assert_eq!(template(&value, "录播")["tid_v2"], json!(2021));
+    }
+
+    #[test]
+    fn migration_is_idempotent() {
+        let mut value = v0_fixture();
+        migrate_config(&mut value).unwrap();
+        let migrated = value.clone();
+
+        assert!(!migrate_config(&mut value).unwrap());
+        assert_eq!(value, migrated);
+    }
+
+    #[test]
+    fn rejects_newer_schema() {
+        let mut value = json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        let error = migrate_config(&mut value).unwrap_err();
+        let too_new = error.downcast_ref::<SchemaTooNew>().unwrap();
+        assert_eq!(too_new.found, CURRENT_SCHEMA_VERSION + 1);
+        assert_eq!(too_new.supported, CURRENT_SCHEMA_VERSION);
+        // 原始内容保持不变
+        assert_eq!(value["schema_version"], json!(CURRENT_SCHEMA_VERSION + 1));
+    }
+
+    #[test]
+    fn refuses_to_overwrite_newer_schema() {
+        let newer = json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 }).to_string();
+        assert!(
+            ensure_overwritable(&newer)
+                .unwrap_err()
+                .downcast_ref::<SchemaTooNew>()
+                .is_some()
+        );
+
+        let current = json!({ "schema_version": CURRENT_SCHEMA_VERSION }).to_string();
+        assert!(ensure_overwritable(&current).is_ok());
+        assert!(ensure_overwritable("{ 损坏的内容").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_schema_version() {
+        let mut value = json!({ "schema_version": "v1" });
+        assert!(migrate_config(&mut value).is_err());
+    }
+}
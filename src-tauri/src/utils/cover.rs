@@ -0,0 +1,214 @@
+use anyhow::{Result, bail};
+use image::{
+    DynamicImage, GenericImageView, ImageFormat, RgbImage, codecs::jpeg::JpegEncoder,
+    imageops::FilterType,
+};
+use serde::{Deserialize, Serialize};
+
+/// B站封面的大小上限
+pub const COVER_MAX_BYTES: usize = 2 * 1024 * 1024;
+/// B站要求的封面最小宽度与高度
+const COVER_MIN_SIZE: (u32, u32) = (960, 600);
+/// 重新编码 JPEG 时的初始质量，超出大小上限时逐步降低
+const JPEG_QUALITY_START: u8 = 92;
+const JPEG_QUALITY_MIN: u8 = 60;
+const JPEG_QUALITY_STEP: u8 = 8;
+
+/// 封面比例
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverAspect {
+    /// 16:9，输出 1920x1080
+    #[default]
+    Wide,
+    /// 4:3，输出 1440x1080
+    Standard,
+}
+
+impl CoverAspect {
+    fn ratio(self) -> (u32, u32) {
+        match self {
+            Self::Wide => (16, 9),
+            Self::Standard => (4, 3),
+        }
+    }
+
+    fn target_size(self) -> (u32, u32) {
+        match self {
+            Self::Wide => (1920, 1080),
+            Self::Standard => (1440, 1080),
+        }
+    }
+}
+
+/// 图片比例与目标比例不一致时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverFit {
+    /// 居中裁剪多余部分
+    #[default]
+    Crop,
+    /// 保留完整画面，用黑边填充
+    Pad,
+}
+
+/// 封面处理选项
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CoverOptions {
+    #[serde(default)]
+    pub aspect: CoverAspect,
+    #[serde(default)]
+    pub fit: CoverFit,
+}
+
+/// 处理后的封面
+#[derive(Debug, Clone)]
+pub struct ProcessedCover {
+    /// JPEG 数据
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+    /// 原图格式，如 `png`
+    pub original_format: String,
+    /// 不影响上传的提示，如原图分辨率过低
+    pub warnings: Vec<String>,
+}
+
+/// HEIC/HEIF/AVIF 等 ISO BMFF 图片的品牌，`image` 无法解码这些格式
+fn heif_brand(data: &[u8]) -> Option<&str> {
+    if data.get(4..8)? != b"ftyp" {
+        return None;
+    }
+    std::str::from_utf8(data.get(8..12)?).ok()
+}
+
+/// 识别图片格式，只接受常见的位图格式
+fn detect_format(data: &[u8]) -> Result<ImageFormat> {
+    if let Some(brand) = heif_brand(data)
+        && matches!(
+            brand,
+            "heic" | "heix" | "hevc" | "hevx" | "heim" | "heis" | "mif1" | "msf1"
+        )
+    {
+        bail!("不支持 HEIC/HEIF 格式的封面，请先转换为 JPG 或 PNG");
+    }
+
+    let format = image::guess_format(data).map_err(|_| anyhow::anyhow!("无法识别的图片格式"))?;
+    match format {
+        ImageFormat::Jpeg
+        | ImageFormat::Png
+        | ImageFormat::WebP
+        | ImageFormat::Bmp
+        | ImageFormat::Gif
+        | ImageFormat::Tiff => Ok(format),
+        _ => bail!("不支持 {:?} 格式的封面，请使用 JPG、PNG 或 WebP", format),
+    }
+}
+
+/// 将图片裁剪或填充为目标比例
+fn fit_aspect(image: DynamicImage, aspect: CoverAspect, fit: CoverFit) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let (rw, rh) = aspect.ratio();
+    let (width64, height64) = (u64::from(width), u64::from(height));
+    let (rw64, rh64) = (u64::from(rw), u64::from(rh));
+
+    let too_wide = width64 * rh64 > height64 * rw64;
+    match (fit, too_wide) {
+        (_, _) if width64 * rh64 == height64 * rw64 => image,
+        (CoverFit::Crop, true) => {
+            let new_width = (height64 * rw64 / rh64) as u32;
+            image.crop_imm((width - new_width) / 2, 0, new_width, height)
+        }
+        (CoverFit::Crop, false) => {
+            let new_height = (width64 * rh64 / rw64) as u32;
+            image.crop_imm(0, (height - new_height) / 2, width, new_height)
+        }
+        (CoverFit::Pad, too_wide) => {
+            let (canvas_width, canvas_height) = if too_wide {
+                (width, (width64 * rh64).div_ceil(rw64) as u32)
+            } else {
+                ((height64 * rw64).div_ceil(rh64) as u32, height)
+            };
+            let mut canvas = RgbImage::new(canvas_width, canvas_height);
+            image::imageops::overlay(
+                &mut canvas,
+                &image.to_rgb8(),
+                i64::from((canvas_width - width) / 2),
+                i64::from((canvas_height - height) / 2),
+            );
+            DynamicImage::ImageRgb8(canvas)
+        }
+    }
+}
+
+/// 编码为 JPEG，超出大小上限时降低质量
+fn encode_jpeg(image: &RgbImage) -> Result<Vec<u8>> {
+    let mut quality = JPEG_QUALITY_START;
+    loop {
+        let mut data = Vec::new();
+        JpegEncoder::new_with_quality(&mut data, quality).encode_image(image)?;
+        if data.len() <= COVER_MAX_BYTES {
+            return Ok(data);
+        }
+        if quality <= JPEG_QUALITY_MIN {
+            bail!(
+                "封面压缩后仍超过 {}MB，请更换图片",
+                COVER_MAX_BYTES / 1024 / 1024
+            );
+        }
+        quality = quality
+            .saturating_sub(JPEG_QUALITY_STEP)
+            .max(JPEG_QUALITY_MIN);
+    }
+}
+
+/// 校验格式并解码图片，同时返回原图格式的扩展名
+pub fn decode_image(data: &[u8]) -> Result<(DynamicImage, &'static str)> {
+    let format = detect_format(data)?;
+    let image = image::load_from_memory_with_format(data, format)
+        .map_err(|e| anyhow::anyhow!("解码图片失败: {e}"))?;
+    Ok((image, format.extensions_str()[0]))
+}
+
+/// 校验格式，裁剪或填充为目标比例，缩放到推荐尺寸并重新编码为 JPEG
+pub fn process_cover(data: &[u8], options: &CoverOptions) -> Result<ProcessedCover> {
+    let (image, format) = decode_image(data)?;
+    process_image(image, format, options)
+}
+
+/// 对已解码的图片执行与 [`process_cover`] 相同的处理，`original_format` 仅用于展示
+pub fn process_image(
+    image: DynamicImage,
+    original_format: &str,
+    options: &CoverOptions,
+) -> Result<ProcessedCover> {
+    let (original_width, original_height) = image.dimensions();
+    if original_width == 0 || original_height == 0 {
+        bail!("封面图片为空");
+    }
+
+    let mut warnings = Vec::new();
+    let (min_width, min_height) = COVER_MIN_SIZE;
+    if original_width < min_width || original_height < min_height {
+        warnings.push(format!(
+            "原图分辨率 {original_width}x{original_height} 低于 {min_width}x{min_height}，放大后可能模糊"
+        ));
+    }
+
+    let fitted = fit_aspect(image, options.aspect, options.fit);
+    let (width, height) = options.aspect.target_size();
+    let resized = fitted.resize_exact(width, height, FilterType::Lanczos3);
+    let data = encode_jpeg(&resized.to_rgb8())?;
+
+    Ok(ProcessedCover {
+        data,
+        width,
+        height,
+        original_width,
+        original_height,
+        original_format: original_format.to_string(),
+        warnings,
+    })
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use ab_glyph::{Font, FontVec, OutlinedGlyph, PxScale, Rect, ScaleFont, point};
+use anyhow::{Context, Result, anyhow, bail};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::{
+    models::cover_spec::{CoverSpec, TextAlign, TextLayer},
+    utils::cover,
+};
+
+/// 描边宽度上限，避免过大的描边占用大量内存
+const MAX_STROKE_WIDTH: u32 = 64;
+/// 字号上限，与编辑器中的上限一致，单个字形的栅格化范围随字号平方增长
+const MAX_FONT_SIZE: f32 = 1000.0;
+
+/// 在底图上绘制全部文字图层，返回合成后的图片、底图格式与提示信息
+///
+/// 图层中的模板变量需要在调用前展开。
+pub fn compose_cover(spec: &CoverSpec) -> Result<(DynamicImage, &'static str, Vec<String>)> {
+    if spec.base_image.trim().is_empty() {
+        bail!("未设置封面底图");
+    }
+    let data = std::fs::read(&spec.base_image)
+        .with_context(|| format!("读取封面底图失败: {}", spec.base_image))?;
+    let (base, format) = cover::decode_image(&data)?;
+    let mut canvas = base.to_rgba8();
+
+    let mut fonts: HashMap<&str, FontVec> = HashMap::new();
+    let mut warnings = Vec::new();
+    for (index, layer) in spec.layers.iter().enumerate() {
+        if layer.text.trim().is_empty() {
+            continue;
+        }
+        if layer.font.trim().is_empty() {
+            bail!("第 {} 个文字图层未设置字体", index + 1);
+        }
+        if !fonts.contains_key(layer.font.as_str()) {
+            let data = std::fs::read(&layer.font)
+                .with_context(|| format!("读取字体文件失败: {}", layer.font))?;
+            let font = FontVec::try_from_vec(data)
+                .map_err(|_| anyhow!("无法解析字体文件: {}", layer.font))?;
+            fonts.insert(layer.font.as_str(), font);
+        }
+        let font = &fonts[layer.font.as_str()];
+
+        let missing = draw_layer(&mut canvas, font, layer)
+            .with_context(|| format!("绘制第 {} 个文字图层失败", index + 1))?;
+        if !missing.is_empty() {
+            warnings.push(format!(
+                "字体 {} 中缺少字符: {}",
+                layer.font,
+                missing.into_iter().collect::<String>()
+            ));
+        }
+    }
+
+    Ok((DynamicImage::ImageRgba8(canvas), format, warnings))
+}
+
+/// 绘制单个文字图层，返回字体中缺少的字符
+fn draw_layer(canvas: &mut RgbaImage, font: &FontVec, layer: &TextLayer) -> Result<Vec<char>> {
+    if !(layer.size.is_finite() && layer.size > 0.0) {
+        bail!("字号无效: {}", layer.size);
+    }
+    let color = parse_color(&layer.color)?;
+    let stroke = match layer.stroke_color.as_deref().map(str::trim) {
+        Some(stroke_color) if !stroke_color.is_empty() && layer.stroke_width > 0 => Some((
+            parse_color(stroke_color)?,
+            layer.stroke_width.min(MAX_STROKE_WIDTH),
+        )),
+        _ => None,
+    };
+
+    let size = layer.size.min(MAX_FONT_SIZE);
+    let (glyphs, missing) = layout(font, layer, size);
+    let Some(first) = glyphs.first() else {
+        return Ok(missing);
+    };
+    let bounds = glyphs.iter().fold(first.px_bounds(), |bounds, glyph| {
+        let rect = glyph.px_bounds();
+        Rect {
+            min: point(bounds.min.x.min(rect.min.x), bounds.min.y.min(rect.min.y)),
+            max: point(bounds.max.x.max(rect.max.x), bounds.max.y.max(rect.max.y)),
+        }
+    });
+
+    // 文字区域的覆盖度，四周留出描边的空间，并裁剪到画布范围内
+    // （画布外一个描边宽度内的文字仍会在画布内产生描边）
+    let pad = i64::from(stroke.map_or(0, |(_, width)| width));
+    let (canvas_width, canvas_height) = (i64::from(canvas.width()), i64::from(canvas.height()));
+    let (origin_x, origin_y) = (
+        (bounds.min.x.floor() as i64 - pad).max(-pad),
+        (bounds.min.y.floor() as i64 - pad).max(-pad),
+    );
+    let (end_x, end_y) = (
+        (bounds.max.x.ceil() as i64 + pad).min(canvas_width + pad),
+        (bounds.max.y.ceil() as i64 + pad).min(canvas_height + pad),
+    );
+    if end_x <= origin_x || end_y <= origin_y {
+        return Ok(missing);
+    }
+    let width = (end_x - origin_x) as usize;
+    let height = (end_y - origin_y) as usize;
+    let mut mask = vec![0f32; width * height];
+    for glyph in &glyphs {
+        let rect = glyph.px_bounds();
+        // 完全落在裁剪范围外的字形不需要栅格化
+        if rect.max.x.ceil() as i64 <= origin_x
+            || rect.max.y.ceil() as i64 <= origin_y
+            || rect.min.x.floor() as i64 >= end_x
+            || rect.min.y.floor() as i64 >= end_y
+        {
+            continue;
+        }
+        let left = rect.min.x.floor() as i64 - origin_x;
+        let top = rect.min.y.floor() as i64 - origin_y;
+        glyph.draw(|x, y, coverage| {
+            let (x, y) = (left + i64::from(x), top + i64::from(y));
+            if (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+                let pixel = &mut mask[y as usize * width + x as usize];
+                *pixel = (*pixel + coverage).min(1.0);
+            }
+        });
+    }
+
+    if let Some((stroke_color, stroke_width)) = stroke {
+        let outline = dilate(&mask, width, height, stroke_width as usize);
+        blend_mask(canvas, &outline, width, (origin_x, origin_y), stroke_color);
+    }
+    blend_mask(canvas, &mask, width, (origin_x, origin_y), color);
+    Ok(missing)
+}
+
+/// 按行排版，返回可绘制的字形与字体中缺少的字符
+fn layout(font: &FontVec, layer: &TextLayer, size: f32) -> (Vec<OutlinedGlyph>, Vec<char>) {
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.ascent() - scaled.descent() + scaled.line_gap();
+
+    let mut glyphs = Vec::new();
+    let mut missing = Vec::new();
+    for (line_index, line) in layer.text.lines().enumerate() {
+        let mut caret = 0f32;
+        let mut previous = None;
+        let mut positioned = Vec::new();
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if id.0 == 0 && !c.is_whitespace() && !missing.contains(&c) {
+                missing.push(c);
+            }
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            positioned.push((id, caret));
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+        }
+
+        let offset = match layer.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => -caret / 2.0,
+            TextAlign::Right => -caret,
+        };
+        let baseline = layer.y as f32 + scaled.ascent() + line_height * line_index as f32;
+        for (id, x) in positioned {
+            let glyph =
+                id.with_scale_and_position(scale, point(layer.x as f32 + offset + x, baseline));
+            glyphs.extend(font.outline_glyph(glyph));
+        }
+    }
+    (glyphs, missing)
+}
+
+/// 以圆形为结构元素膨胀覆盖度，用于生成描边
+///
+/// 先逐级计算半宽为 k 的水平窗口最大值，再按圆在每一行的半宽取对应级别合并。
+fn dilate(mask: &[f32], width: usize, height: usize, radius: usize) -> Vec<f32> {
+    let r = radius as i64;
+    let half_widths: Vec<(i64, usize)> = (-r..=r)
+        .map(|dy| (dy, ((r * r - dy * dy) as f64).sqrt() as usize))
+        .collect();
+
+    let mut output = vec![0f32; mask.len()];
+    let mut level = mask.to_vec();
+    for k in 0..=radius {
+        if k > 0 {
+            let previous = level.clone();
+            for (previous, current) in previous.chunks(width).zip(level.chunks_mut(width)) {
+                for x in 0..width {
+                    if x > 0 {
+                        current[x] = current[x].max(previous[x - 1]);
+                    }
+                    if x + 1 < width {
+                        current[x] = current[x].max(previous[x + 1]);
+                    }
+                }
+            }
+        }
+
+        for &(dy, _) in half_widths.iter().filter(|(_, half)| *half == k) {
+            for y in 0..height as i64 {
+                let source = y + dy;
+                if !(0..height as i64).contains(&source) {
+                    continue;
+                }
+                let (y, source) = (y as usize * width, source as usize * width);
+                for x in 0..width {
+                    output[y + x] = output[y + x].max(level[source + x]);
+                }
+            }
+        }
+    }
+    output
+}
+
+/// 按覆盖度将颜色叠加到画布上，`origin` 为覆盖度左上角在画布中的位置
+fn blend_mask(
+    canvas: &mut RgbaImage,
+    mask: &[f32],
+    width: usize,
+    origin: (i64, i64),
+    color: Rgba<u8>,
+) {
+    let (canvas_width, canvas_height) = (i64::from(canvas.width()), i64::from(canvas.height()));
+    for (index, coverage) in mask.iter().enumerate() {
+        let alpha = coverage * f32::from(color[3]) / 255.0;
+        if alpha <= 0.0 {
+            continue;
+        }
+        let x = origin.0 + (index % width) as i64;
+        let y = origin.1 + (index / width) as i64;
+        if !(0..canvas_width).contains(&x) || !(0..canvas_height).contains(&y) {
+            continue;
+        }
+
+        let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+        let base_alpha = f32::from(pixel[3]) / 255.0;
+        let out_alpha = alpha + base_alpha * (1.0 - alpha);
+        for channel in 0..3 {
+            let value = (f32::from(color[channel]) * alpha
+                + f32::from(pixel[channel]) * base_alpha * (1.0 - alpha))
+                / out_alpha;
+            pixel[channel] = value.round().clamp(0.0, 255.0) as u8;
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+/// 解析 `#RRGGBB` 或 `#RRGGBBAA` 格式的颜色
+fn parse_color(text: &str) -> Result<Rgba<u8>> {
+    let hex = text.trim().trim_start_matches('#');
+    let channel = |index: usize| {
+        hex.get(index * 2..index * 2 + 2)
+            .and_then(|value| u8::from_str_radix(value, 16).ok())
+    };
+    let alpha = match hex.len() {
+        6 => Some(255),
+        8 => channel(3),
+        _ => None,
+    };
+    match (channel(0), channel(1), channel(2), alpha) {
+        (Some(r), Some(g), Some(b), Some(a)) => Ok(Rgba([r, g, b, a])),
+        _ => bail!("颜色格式无效: {text}，应为 #RRGGBB 或 #RRGGBBAA"),
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::models::user_config::Credit;
+
+/// desc_v2 中的纯文本片段
+pub const CREDIT_TYPE_TEXT: u8 = 1;
+/// desc_v2 中的@用户片段，`biz_id` 为用户 UID
+pub const CREDIT_TYPE_MENTION: u8 = 2;
+
+/// 简介中的 `@用户名`，用户名遇到空白或标点结束
+static MENTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"@([\p{L}\p{N}_-]+)").unwrap());
+
+/// 纯文本简介中的片段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescSegment<'a> {
+    Text(&'a str),
+    /// `text` 为原文，包括 `@` 及其后的一个空格
+    Mention {
+        name: &'a str,
+        text: &'a str,
+    },
+}
+
+/// 按 `@用户名` 拆分纯文本简介
+pub fn split_mentions(desc: &str) -> Vec<DescSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for captures in MENTION.captures_iter(desc) {
+        let (Some(whole), Some(name)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        // 邮箱等 `@` 前紧跟文字的情况不是@用户
+        let preceding = desc[..whole.start()].chars().next_back();
+        if preceding.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+
+        if whole.start() > last {
+            segments.push(DescSegment::Text(&desc[last..whole.start()]));
+        }
+        // 还原纯文本时@用户后会带一个空格，拆分时一并去掉
+        let end = if desc[whole.end()..].starts_with(' ') {
+            whole.end() + 1
+        } else {
+            whole.end()
+        };
+        segments.push(DescSegment::Mention {
+            name: name.as_str(),
+            text: &desc[whole.start()..end],
+        });
+        last = end;
+    }
+    if last < desc.len() {
+        segments.push(DescSegment::Text(&desc[last..]));
+    }
+    segments
+}
+
+/// 将拆分后的简介转换为 desc_v2，返回无法识别的用户名
+///
+/// `resolve` 返回用户名对应的 UID，无法识别的@用户按原文保留为纯文本。
+pub fn build_desc_v2(
+    segments: &[DescSegment<'_>],
+    resolve: impl Fn(&str) -> Option<String>,
+) -> (Vec<Credit>, Vec<String>) {
+    let mut credits: Vec<Credit> = Vec::with_capacity(segments.len());
+    let mut unresolved: Vec<String> = Vec::new();
+
+    let push_text = |credits: &mut Vec<Credit>, text: &str| {
+        if let Some(last) = credits.last_mut()
+            && last.r#type == CREDIT_TYPE_TEXT
+        {
+            last.raw_text.push_str(text);
+        } else {
+            credits.push(Credit {
+                r#type: CREDIT_TYPE_TEXT,
+                raw_text: text.to_string(),
+                ..Default::default()
+            });
+        }
+    };
+
+    for segment in segments {
+        match *segment {
+            DescSegment::Text(text) => push_text(&mut credits, text),
+            DescSegment::Mention { name, text } => match resolve(name) {
+                Some(uid) => credits.push(Credit {
+                    r#type: CREDIT_TYPE_MENTION,
+                    raw_text: name.to_string(),
+                    biz_id: uid,
+                    ..Default::default()
+                }),
+                None => {
+                    if !unresolved.iter().any(|n| n == name) {
+                        unresolved.push(name.to_string());
+                    }
+                    push_text(&mut credits, text);
+                }
+            },
+        }
+    }
+    (credits, unresolved)
+}
+
+/// 投稿时会提交的片段，与 `into_bilibili_form` 的筛选一致
+pub fn is_submittable(credit: &Credit) -> bool {
+    (credit.r#type == CREDIT_TYPE_TEXT || credit.r#type == CREDIT_TYPE_MENTION)
+        && !credit.raw_text.is_empty()
+}
+
+/// @用户的数量
+pub fn mention_count(credits: &[Credit]) -> usize {
+    credits
+        .iter()
+        .filter(|credit| is_submittable(credit) && credit.r#type == CREDIT_TYPE_MENTION)
+        .count()
+}
+
+/// 将会提交的 desc_v2 片段还原为纯文本简介，@用户显示为 `@用户名 `
+pub fn render_desc(credits: &[Credit]) -> String {
+    let mut desc = String::new();
+    for credit in credits.iter().filter(|credit| is_submittable(credit)) {
+        if credit.r#type == CREDIT_TYPE_MENTION {
+            if !credit.raw_text.starts_with('@') {
+                desc.push('@');
+            }
+            desc.push_str(&credit.raw_text);
+            desc.push(' ');
+        } else {
+            desc.push_str(&credit.raw_text);
+        }
+    }
+    desc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TemplateConfig;
+
+    fn resolve(name: &str) -> Option<String> {
+        match name {
+            "张三" => Some("10001".to_string()),
+            "li_si" => Some("10002".to_string()),
+            _ => None,
+        }
+    }
+
+    fn roundtrip(desc: &str) -> (String, Vec<Credit>, Vec<String>) {
+        let (credits, unresolved) = build_desc_v2(&split_mentions(desc), resolve);
+        (render_desc(&credits), credits, unresolved)
+    }
+
+    /// 按 `into_bilibili_form` 实际提交的 desc_v2 还原简介
+    fn submitted_desc(desc: String, credits: Vec<Credit>) -> (String, String) {
+        let form = TemplateConfig {
+            desc,
+            desc_v2: Some(credits),
+            ..Default::default()
+        }
+        .into_bilibili_form();
+        let submitted: Vec<Credit> = form
+            .desc_v2
+            .unwrap_or_default()
+            .into_iter()
+            .map(|credit| Credit {
+                r#type: credit.type_id,
+                raw_text: credit.raw_text,
+                biz_id: credit.biz_id.unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect();
+        (form.desc, render_desc(&submitted))
+    }
+
+    #[test]
+    fn rendered_desc_matches_submitted_desc_v2() {
+        for desc in [
+            "感谢 @张三 的剪辑，@li_si 负责字幕",
+            "@张三 开头",
+            "未知用户 @王五 保留为文本",
+            "邮箱 someone@example.com 不是@用户",
+            "没有@用户的简介",
+        ] {
+            let (rendered, credits, _) = roundtrip(desc);
+            assert_eq!(rendered, desc);
+            let (form_desc, submitted) = submitted_desc(rendered.clone(), credits);
+            assert_eq!(form_desc, rendered);
+            assert_eq!(submitted, rendered, "{desc}");
+        }
+    }
+
+    #[test]
+    fn mentions_keep_uid_and_unresolved_names() {
+        let (_, credits, unresolved) = roundtrip("@张三 @王五 @张三 @王五 结束");
+        assert_eq!(mention_count(&credits), 2);
+        assert!(
+            credits
+                .iter()
+                .filter(|credit| credit.r#type == CREDIT_TYPE_MENTION)
+                .all(|credit| credit.raw_text == "张三" && credit.biz_id == "10001")
+        );
+        assert_eq!(unresolved, ["王五"]);
+    }
+
+    #[test]
+    fn rendering_is_stable_after_normalizing_spaces() {
+        // @用户后紧跟标点或位于末尾时还原会补一个空格，再次解析结果不变
+        let (once, _, _) = roundtrip("谢谢 @张三，还有 @li_si");
+        assert_eq!(once, "谢谢 @张三 ，还有 @li_si ");
+        let (twice, credits, _) = roundtrip(&once);
+        assert_eq!(twice, once);
+        assert_eq!(submitted_desc(twice.clone(), credits).1, twice);
+    }
+}
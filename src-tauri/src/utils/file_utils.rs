@@ -2,10 +2,14 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::{
     fs,
+    io::Write,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
 };
 use tracing::{error, info};
 
+use crate::utils::part_title::natural_cmp;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FileEntry {
     pub name: String,
@@ -19,7 +23,7 @@ pub fn get_file_size(path: &Path) -> Result<u64> {
     Ok(metadata.len())
 }
 
-/// 递归读取目录，返回所有文件（可选择是否包含子目录）
+/// 递归读取目录，返回按自然顺序排序的所有文件（可选择是否包含子目录）
 pub fn read_dir_recursive(
     dir_path: &Path,
     include_subdirs: bool,
@@ -33,6 +37,7 @@ pub fn read_dir_recursive(
         0,
         &mut result,
     )?;
+    result.sort_by(|a, b| natural_cmp(&a.path, &b.path));
     Ok(result)
 }
 
@@ -146,6 +151,115 @@ pub fn get_avatar_cache_path() -> Result<PathBuf> {
     Ok(avatar_cache_dir)
 }
 
+/// 获取稿件列表缓存路径
+pub fn get_archive_cache_path() -> Result<PathBuf> {
+    let config_dir = get_config_dir()?;
+    let archive_cache_dir = config_dir.join("archive_cache");
+
+    // 确保稿件缓存目录存在
+    if !archive_cache_dir.exists() {
+        fs::create_dir_all(&archive_cache_dir)?;
+    }
+
+    Ok(archive_cache_dir)
+}
+
+/// 获取分区、话题等元数据的缓存路径
+pub fn get_metadata_cache_path() -> Result<PathBuf> {
+    let config_dir = get_config_dir()?;
+    let metadata_cache_dir = config_dir.join("metadata_cache");
+
+    // 确保元数据缓存目录存在
+    if !metadata_cache_dir.exists() {
+        fs::create_dir_all(&metadata_cache_dir)?;
+    }
+
+    Ok(metadata_cache_dir)
+}
+
+/// 获取标签使用记录路径
+pub fn get_tag_history_path() -> Result<PathBuf> {
+    let config_dir = get_config_dir()?;
+    let tag_history_dir = config_dir.join("tag_history");
+
+    // 确保标签记录目录存在
+    if !tag_history_dir.exists() {
+        fs::create_dir_all(&tag_history_dir)?;
+    }
+
+    Ok(tag_history_dir)
+}
+
+/// 获取@用户缓存路径
+pub fn get_mention_cache_path() -> Result<PathBuf> {
+    let config_dir = get_config_dir()?;
+    let mention_cache_dir = config_dir.join("mention_cache");
+
+    // 确保@用户缓存目录存在
+    if !mention_cache_dir.exists() {
+        fs::create_dir_all(&mention_cache_dir)?;
+    }
+
+    Ok(mention_cache_dir)
+}
+
+/// 获取已上传字幕记录路径
+pub fn get_subtitle_record_path() -> Result<PathBuf> {
+    let config_dir = get_config_dir()?;
+    let subtitle_record_dir = config_dir.join("subtitle_record");
+
+    // 确保字幕记录目录存在
+    if !subtitle_record_dir.exists() {
+        fs::create_dir_all(&subtitle_record_dir)?;
+    }
+
+    Ok(subtitle_record_dir)
+}
+
+/// 获取配置备份路径
+pub fn get_config_backup_path() -> Result<PathBuf> {
+    let config_dir = get_config_dir()?;
+    let backup_dir = config_dir.join("backups");
+
+    // 确保备份目录存在
+    if !backup_dir.exists() {
+        fs::create_dir_all(&backup_dir)?;
+    }
+
+    Ok(backup_dir)
+}
+
+/// 临时文件名中的序号，保证同时写入同一文件时各自使用不同的临时文件
+static TMP_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 原子写入文件：先写入同目录下的临时文件并刷盘，再重命名覆盖目标文件
+///
+/// 同时写入同一文件时以最后完成重命名的内容为准，不会写出混合的内容。
+pub fn write_file_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("无效的文件路径: {:?}", path))?
+        .to_string_lossy();
+    let tmp_path = path.with_file_name(format!(
+        ".{file_name}.{}-{}.tmp",
+        std::process::id(),
+        TMP_FILE_SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    {
+        let mut tmp_file = fs::File::create(&tmp_path)?;
+        tmp_file.write_all(content)?;
+        tmp_file.sync_all()?;
+    }
+
+    if let Err(e) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
 /// 获取应用配置目录路径
 pub fn get_config_dir() -> Result<PathBuf> {
     let appdata_dir = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("无法获取 APPDATA 目录"))?;
@@ -192,3 +306,41 @@ pub fn get_old_cookie_file_path() -> Result<PathBuf> {
         Err(anyhow::anyhow!("没有找到cookie文件"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_replaces_content_and_leaves_no_temp_files() {
+        let dir = std::env::temp_dir().join(format!("biliup-app-atomic-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        fs::write(&path, "old").unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    write_file_atomic(&path, i.to_string().repeat(4096).as_bytes())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        // 内容来自某一次完整的写入，不会混合
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.len(), 4096);
+        assert!(
+            content
+                .chars()
+                .all(|c| c == content.chars().next().unwrap())
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
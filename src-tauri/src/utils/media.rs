@@ -0,0 +1,101 @@
+use std::{io::ErrorKind, path::Path};
+
+use anyhow::{Result, anyhow, bail};
+use tokio::process::Command;
+use tracing::debug;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 创建不弹出控制台窗口的外部命令
+pub fn hidden_command(program: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(program);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+/// 获取文件的修改时间（秒级时间戳）
+pub fn file_mtime(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .ok()?
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|elapsed| elapsed.as_secs())
+}
+
+/// 使用本地 ffprobe 获取视频时长（秒），未安装 ffprobe 或解析失败时返回 None
+pub async fn probe_duration(path: &Path) -> Option<u64> {
+    let output = hidden_command("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| debug!("调用 ffprobe 失败: {}", e))
+        .ok()?;
+
+    if !output.status.success() {
+        debug!(
+            "ffprobe 返回错误: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|duration| duration.is_finite() && *duration >= 0.0)
+        .map(|duration| duration.round() as u64)
+}
+
+/// 从视频中截取一帧，`width` 为输出宽度（保持比例），为 None 时保留原始分辨率
+///
+/// 输出 JPEG 缩略图或 PNG 原图，未安装 ffmpeg 时返回明确的错误。
+pub async fn extract_frame(path: &Path, timestamp: f64, width: Option<u32>) -> Result<Vec<u8>> {
+    let mut command = hidden_command("ffmpeg");
+    command
+        .args(["-v", "error", "-ss", &format!("{timestamp:.3}"), "-i"])
+        .arg(path)
+        .args(["-frames:v", "1", "-an", "-f", "image2pipe"]);
+    match width {
+        Some(width) => command.args(["-vf", &format!("scale={width}:-2"), "-c:v", "mjpeg"]),
+        None => command.args(["-c:v", "png"]),
+    };
+    let output = command.arg("-").output().await.map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            anyhow!("未找到 ffmpeg，请安装 ffmpeg 并将其加入 PATH 后重试")
+        } else {
+            anyhow!("调用 ffmpeg 失败: {e}")
+        }
+    })?;
+
+    if !output.status.success() {
+        bail!(
+            "ffmpeg 截取画面失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    if output.stdout.is_empty() {
+        bail!("在 {timestamp:.1} 秒处没有截取到画面");
+    }
+    Ok(output.stdout)
+}
+
+/// 在视频中均匀分布的 `count` 个时间点（秒），避开开头和结尾
+pub fn frame_timestamps(duration: f64, count: u32) -> Vec<f64> {
+    (1..=count)
+        .map(|i| duration * f64::from(i) / f64::from(count + 1))
+        .collect()
+}
@@ -1,6 +1,14 @@
 pub mod compatible;
+pub mod config_migration;
+pub mod cover;
+pub mod cover_compose;
 pub mod crypto;
+pub mod desc_v2;
 pub mod file_utils;
+pub mod media;
+pub mod part_title;
+pub mod placeholder;
+pub mod subtitle;
 
 pub use compatible::*;
 pub use file_utils::*;
@@ -0,0 +1,289 @@
+use std::{cmp::Ordering, fmt::Write, path::Path, sync::LazyLock};
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{models::upload_form::TITLE_MAX_CHARS, utils::media};
+
+/// 录播软件常见的时间格式，如 `2024-05-01T20-00-00`、`2024-05-01 20:00:00`、`20240501-200000`
+static RECORDER_TIME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{4})-?(\d{2})-?(\d{2})[T _-]?(\d{2})[-:.]?(\d{2})[-:.]?(\d{2})")
+        .expect("invalid recorder time regex")
+});
+
+const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+fn default_number_prefix() -> String {
+    "P".to_string()
+}
+
+/// 添加视频时生成分P标题的规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PartTitleRule {
+    /// 使用文件名（不含扩展名）
+    FileStem,
+    /// 使用正则对文件名匹配得到的分组，`group` 为序号或分组名，未匹配时回退到文件名
+    RegexCapture {
+        pattern: String,
+        #[serde(default)]
+        group: String,
+    },
+    /// 按分P序号编号，如 `P1`、`P01`
+    Numbered {
+        #[serde(default = "default_number_prefix")]
+        prefix: String,
+        #[serde(default)]
+        width: usize,
+    },
+    /// 使用文件修改时间
+    FileMtime {
+        #[serde(default)]
+        format: Option<String>,
+    },
+    /// 从录播文件名中解析录制时间，解析失败时回退到文件修改时间
+    RecorderTime {
+        #[serde(default)]
+        format: Option<String>,
+    },
+}
+
+/// 生成的分P标题
+#[derive(Debug, Clone, Serialize)]
+pub struct PartTitle {
+    pub path: String,
+    pub title: String,
+}
+
+/// 自然排序比较：数字部分按数值比较，使 `part2` 排在 `part10` 之前，字母不区分大小写
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x_digits = String::new();
+                while let Some(c) = a_chars.next_if(char::is_ascii_digit) {
+                    x_digits.push(c);
+                }
+                let mut y_digits = String::new();
+                while let Some(c) = b_chars.next_if(char::is_ascii_digit) {
+                    y_digits.push(c);
+                }
+
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                // 大小写只在其余部分完全相同时才影响顺序
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn file_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// 按用户提供的格式输出时间，格式无效时返回 None
+fn format_time(time: &NaiveDateTime, format: &str) -> Option<String> {
+    let mut text = String::new();
+    write!(text, "{}", time.format(format)).ok()?;
+    Some(text)
+}
+
+fn time_format(format: &Option<String>) -> &str {
+    format
+        .as_deref()
+        .map(str::trim)
+        .filter(|format| !format.is_empty())
+        .unwrap_or(DEFAULT_TIME_FORMAT)
+}
+
+fn format_mtime(path: &str, format: &str) -> Option<String> {
+    let mtime = media::file_mtime(Path::new(path))?;
+    let time = chrono::DateTime::from_timestamp(i64::try_from(mtime).ok()?, 0)?
+        .with_timezone(&chrono::Local);
+    format_time(&time.naive_local(), format)
+}
+
+fn parse_recorder_time(stem: &str) -> Option<NaiveDateTime> {
+    let captures = RECORDER_TIME.captures(stem)?;
+    let text = format!(
+        "{}-{}-{} {}:{}:{}",
+        &captures[1], &captures[2], &captures[3], &captures[4], &captures[5], &captures[6]
+    );
+    NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S").ok()
+}
+
+impl PartTitleRule {
+    /// 为一组文件生成分P标题，`start_index` 为第一个文件的分P序号（从 0 开始）
+    pub fn apply(&self, paths: &[String], start_index: usize) -> Result<Vec<PartTitle>> {
+        let regex = match self {
+            Self::RegexCapture { pattern, .. } => {
+                Some(Regex::new(pattern).map_err(|e| anyhow::anyhow!("分P标题匹配规则无效: {e}"))?)
+            }
+            _ => None,
+        };
+
+        Ok(paths
+            .iter()
+            .enumerate()
+            .map(|(offset, path)| {
+                let stem = file_stem(path);
+                let title = match self {
+                    Self::FileStem => None,
+                    Self::RegexCapture { group, .. } => regex.as_ref().and_then(|regex| {
+                        let captures = regex.captures(&stem)?;
+                        let matched = match group.trim() {
+                            "" => captures.get(1).or_else(|| captures.get(0)),
+                            group => match group.parse::<usize>() {
+                                Ok(index) => captures.get(index),
+                                Err(_) => captures.name(group),
+                            },
+                        };
+                        matched.map(|m| m.as_str().to_string())
+                    }),
+                    Self::Numbered { prefix, width } => Some(format!(
+                        "{prefix}{:0width$}",
+                        start_index + offset + 1,
+                        width = *width
+                    )),
+                    Self::FileMtime { format } => format_mtime(path, time_format(format)),
+                    Self::RecorderTime { format } => {
+                        let format = time_format(format);
+                        parse_recorder_time(&stem)
+                            .and_then(|time| format_time(&time, format))
+                            .or_else(|| format_mtime(path, format))
+                    }
+                };
+
+                let title = title
+                    .map(|title| title.trim().to_string())
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or(stem);
+                PartTitle {
+                    path: path.clone(),
+                    title: title.chars().take(TITLE_MAX_CHARS).collect(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// 按自然顺序排序文件并生成分P标题，未设置规则时使用文件名
+pub fn generate_part_titles(
+    rule: Option<&PartTitleRule>,
+    mut paths: Vec<String>,
+    start_index: usize,
+) -> Result<Vec<PartTitle>> {
+    paths.sort_by(|a, b| natural_cmp(a, b));
+    rule.unwrap_or(&PartTitleRule::FileStem)
+        .apply(&paths, start_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected_time() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-05-01 20:00:00", DEFAULT_TIME_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn numbers_are_compared_by_value() {
+        assert_eq!(natural_cmp("part2", "part10"), Ordering::Less);
+        assert_eq!(natural_cmp("part10", "part2"), Ordering::Greater);
+        assert_eq!(natural_cmp("part01", "part2"), Ordering::Less);
+        assert_eq!(natural_cmp("录播9.flv", "录播10.flv"), Ordering::Less);
+    }
+
+    #[test]
+    fn leading_zeros_only_break_ties() {
+        assert_eq!(natural_cmp("part01", "part1"), Ordering::Less);
+        assert_eq!(natural_cmp("part1", "part01"), Ordering::Greater);
+        assert_eq!(natural_cmp("part001b", "part1a"), Ordering::Greater);
+    }
+
+    #[test]
+    fn letters_are_case_folded() {
+        assert_eq!(natural_cmp("Apple", "banana"), Ordering::Less);
+        assert_eq!(natural_cmp("PART2", "part10"), Ordering::Less);
+        // 忽略大小写相同时按原始字符排序，保证结果稳定
+        assert_eq!(natural_cmp("A", "a"), Ordering::Less);
+    }
+
+    #[test]
+    fn sorts_paths_before_generating_titles() {
+        let paths = ["p10.flv", "P2.flv", "p1.flv"].map(str::to_string).to_vec();
+        let titles = generate_part_titles(None, paths, 0).unwrap();
+        let titles: Vec<_> = titles.iter().map(|part| part.title.as_str()).collect();
+        assert_eq!(titles, ["p1", "P2", "p10"]);
+    }
+
+    #[test]
+    fn parses_recorder_time_variants() {
+        for stem in [
+            "录制-2024-05-01T20-00-00",
+            "2024-05-01 20:00:00 直播",
+            "20240501-200000",
+            "房间_20240501_200000_标题",
+            "2024-05-01_20.00.00",
+        ] {
+            assert_eq!(parse_recorder_time(stem), Some(expected_time()), "{stem}");
+        }
+        assert_eq!(parse_recorder_time("20241301-200000"), None);
+        assert_eq!(parse_recorder_time("没有时间"), None);
+    }
+
+    #[test]
+    fn recorder_time_uses_custom_format() {
+        let rule = PartTitleRule::RecorderTime {
+            format: Some("%m月%d日 %H点".to_string()),
+        };
+        let titles = rule
+            .apply(&["/不存在/录制-20240501-200000.flv".to_string()], 0)
+            .unwrap();
+        assert_eq!(titles[0].title, "05月01日 20点");
+    }
+
+    #[test]
+    fn invalid_format_falls_back_to_file_stem() {
+        let rule = PartTitleRule::RecorderTime {
+            format: Some("%Q".to_string()),
+        };
+        let titles = rule
+            .apply(&["/不存在/录制-20240501-200000.flv".to_string()], 0)
+            .unwrap();
+        assert_eq!(titles[0].title, "录制-20240501-200000");
+
+        let rule = PartTitleRule::FileMtime {
+            format: Some("%Q".to_string()),
+        };
+        let titles = rule.apply(&["/不存在/第一段.flv".to_string()], 0).unwrap();
+        assert_eq!(titles[0].title, "第一段");
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use chrono::{DateTime, Local, TimeZone};
+use regex::Regex;
+
+/// 渲染单个分P时可用的文件信息
+#[derive(Debug, Clone, Default)]
+pub struct PartVars {
+    /// 本地文件名（不含扩展名）
+    pub filename: String,
+    /// 文件修改时间（秒级时间戳）
+    pub file_mtime: Option<u64>,
+    /// 视频时长（秒）
+    pub duration: Option<u64>,
+}
+
+/// 模板变量替换的上下文
+///
+/// 支持的变量：`{date[:格式]}`、`{time[:格式]}`、`{filename}`、`{file_mtime[:格式]}`、
+/// `{part_index[:位数]}`、`{part_count}`、`{streamer}`、`{duration}`，
+/// 以及 `filename_pattern` 对文件名匹配得到的分组 `{1}`、`{name}`。
+/// `{{` 与 `}}` 输出字面量花括号，无法解析的变量原样保留。
+pub struct PlaceholderContext {
+    now: DateTime<Local>,
+    streamer: String,
+    parts: Vec<PartVars>,
+    filename_pattern: Option<Regex>,
+}
+
+/// 渲染结果
+#[derive(Debug, Clone, Default)]
+pub struct Rendered {
+    pub text: String,
+    /// 无法解析、已原样保留的变量
+    pub unresolved: Vec<String>,
+}
+
+fn format_duration(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// 按用户提供的格式输出时间，格式无效时返回 None
+fn format_time(time: &DateTime<Local>, format: &str) -> Option<String> {
+    let mut text = String::new();
+    write!(text, "{}", time.format(format)).ok()?;
+    Some(text)
+}
+
+fn format_timestamp(timestamp: u64, format: &str) -> Option<String> {
+    let time = Local
+        .timestamp_opt(i64::try_from(timestamp).ok()?, 0)
+        .single()?;
+    format_time(&time, format)
+}
+
+impl PlaceholderContext {
+    pub fn new(
+        streamer: &str,
+        parts: Vec<PartVars>,
+        filename_pattern: Option<&str>,
+    ) -> Result<Self> {
+        let filename_pattern = filename_pattern
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| anyhow::anyhow!("文件名匹配规则无效: {e}"))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            now: Local::now(),
+            streamer: streamer.to_string(),
+            parts,
+            filename_pattern,
+        })
+    }
+
+    /// 按稿件渲染：文件相关变量取第一个分P，时长为所有分P之和
+    pub fn render(&self, text: &str) -> Rendered {
+        self.render_with(text, None)
+    }
+
+    /// 按分P渲染，`index` 从 0 开始
+    pub fn render_part(&self, text: &str, index: usize) -> Rendered {
+        self.render_with(text, Some(index))
+    }
+
+    fn render_with(&self, text: &str, part_index: Option<usize>) -> Rendered {
+        let mut rendered = Rendered::default();
+        let mut rest = text;
+
+        while let Some(pos) = rest.find(['{', '}']) {
+            rendered.text.push_str(&rest[..pos]);
+            let tail = &rest[pos..];
+
+            if tail.starts_with("{{") || tail.starts_with("}}") {
+                rendered.text.push_str(&tail[..1]);
+                rest = &tail[2..];
+                continue;
+            }
+
+            if let Some(after) = tail.strip_prefix('}') {
+                rendered.text.push('}');
+                rest = after;
+                continue;
+            }
+
+            let Some(end) = tail.find('}') else {
+                rendered.text.push_str(tail);
+                rest = "";
+                break;
+            };
+
+            let inner = &tail[1..end];
+            let (name, arg) = match inner.split_once(':') {
+                Some((name, arg)) => (name.trim(), Some(arg)),
+                None => (inner.trim(), None),
+            };
+
+            match self.resolve(name, arg, part_index) {
+                Some(value) => rendered.text.push_str(&value),
+                None => {
+                    rendered.text.push_str(&tail[..=end]);
+                    rendered.unresolved.push(inner.to_string());
+                }
+            }
+            rest = &tail[end + 1..];
+        }
+
+        rendered.text.push_str(rest);
+        rendered
+    }
+
+    fn capture(&self, part: Option<&PartVars>, name: &str) -> Option<String> {
+        let captures = self.filename_pattern.as_ref()?.captures(&part?.filename)?;
+        let matched = match name.parse::<usize>() {
+            Ok(index) => captures.get(index),
+            Err(_) => captures.name(name),
+        };
+        matched.map(|m| m.as_str().to_string())
+    }
+
+    fn resolve(&self, name: &str, arg: Option<&str>, part_index: Option<usize>) -> Option<String> {
+        let part = self.parts.get(part_index.unwrap_or(0));
+
+        match name {
+            "date" => format_time(&self.now, arg.unwrap_or("%Y-%m-%d")),
+            "time" => format_time(&self.now, arg.unwrap_or("%H:%M")),
+            "filename" => part.map(|part| part.filename.clone()),
+            "file_mtime" => format_timestamp(part?.file_mtime?, arg.unwrap_or("%Y-%m-%d %H:%M")),
+            "part_index" => {
+                let index = part_index.unwrap_or(0) + 1;
+                let width = arg.and_then(|arg| arg.trim().parse::<usize>().ok());
+                Some(format!("{index:0width$}", width = width.unwrap_or(0)))
+            }
+            "part_count" => Some(self.parts.len().to_string()),
+            "streamer" => self
+                .capture(part, "streamer")
+                .or_else(|| (!self.streamer.is_empty()).then(|| self.streamer.clone())),
+            "duration" => match part_index {
+                Some(_) => part?.duration.map(format_duration),
+                None => self
+                    .parts
+                    .iter()
+                    .map(|part| part.duration)
+                    .sum::<Option<u64>>()
+                    .filter(|_| !self.parts.is_empty())
+                    .map(format_duration),
+            },
+            _ => self.capture(part, name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> PlaceholderContext {
+        let part = PartVars {
+            filename: "录播".to_string(),
+            file_mtime: Some(0),
+            duration: Some(61),
+        };
+        PlaceholderContext::new("主播", vec![part], None).unwrap()
+    }
+
+    #[test]
+    fn invalid_time_format_is_kept_as_unresolved() {
+        let rendered = context().render("{date:%Q}|{time:%Q}|{file_mtime:%Q}");
+        assert_eq!(rendered.text, "{date:%Q}|{time:%Q}|{file_mtime:%Q}");
+        assert_eq!(rendered.unresolved, ["date:%Q", "time:%Q", "file_mtime:%Q"]);
+    }
+
+    #[test]
+    fn valid_time_format_is_rendered() {
+        let rendered = context().render("{date:%Y}-{duration}");
+        assert!(rendered.unresolved.is_empty());
+        assert_eq!(rendered.text.len(), "2026-0:01:01".len());
+        assert!(rendered.text.ends_with("-0:01:01"));
+    }
+}
@@ -0,0 +1,372 @@
+use std::{path::Path, sync::LazyLock};
+
+use anyhow::{Result, anyhow, bail};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 字幕文件大小上限，B站对单个字幕的内容长度有限制
+const SUBTITLE_MAX_BYTES: u64 = 4 * 1024 * 1024;
+/// BCC 中字幕显示在画面底部
+const BCC_LOCATION_BOTTOM: u8 = 2;
+
+/// B站字幕格式（BCC）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bcc {
+    #[serde(default = "default_font_size")]
+    pub font_size: f64,
+    #[serde(default = "default_font_color")]
+    pub font_color: String,
+    #[serde(default = "default_background_alpha")]
+    pub background_alpha: f64,
+    #[serde(default = "default_background_color")]
+    pub background_color: String,
+    #[serde(rename = "Stroke", default = "default_stroke")]
+    pub stroke: String,
+    pub body: Vec<BccLine>,
+}
+
+/// 单条字幕，时间以秒为单位
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BccLine {
+    pub from: f64,
+    pub to: f64,
+    #[serde(default = "default_location")]
+    pub location: u8,
+    pub content: String,
+}
+
+fn default_font_size() -> f64 {
+    0.4
+}
+
+fn default_font_color() -> String {
+    "#FFFFFF".to_string()
+}
+
+fn default_background_alpha() -> f64 {
+    0.5
+}
+
+fn default_background_color() -> String {
+    "#9C27B0".to_string()
+}
+
+fn default_stroke() -> String {
+    "none".to_string()
+}
+
+fn default_location() -> u8 {
+    BCC_LOCATION_BOTTOM
+}
+
+impl Bcc {
+    fn new(body: Vec<BccLine>) -> Self {
+        Self {
+            font_size: default_font_size(),
+            font_color: default_font_color(),
+            background_alpha: default_background_alpha(),
+            background_color: default_background_color(),
+            stroke: default_stroke(),
+            body,
+        }
+    }
+}
+
+/// 支持的字幕格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+    Ass,
+    Bcc,
+}
+
+impl SubtitleFormat {
+    /// 按扩展名判断字幕格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            "ass" | "ssa" => Some(Self::Ass),
+            "bcc" | "json" => Some(Self::Bcc),
+            _ => None,
+        }
+    }
+}
+
+/// 读取字幕文件并转换为 BCC
+pub fn load_subtitle(path: &Path) -> Result<Bcc> {
+    let format = SubtitleFormat::from_path(path)
+        .ok_or_else(|| anyhow!("不支持的字幕格式，请使用 SRT、ASS、VTT 或 BCC"))?;
+    let size = std::fs::metadata(path)
+        .map_err(|e| anyhow!("读取字幕文件失败: {e}"))?
+        .len();
+    if size > SUBTITLE_MAX_BYTES {
+        bail!("字幕文件超过 {}MB", SUBTITLE_MAX_BYTES / 1024 / 1024);
+    }
+    let data = std::fs::read(path).map_err(|e| anyhow!("读取字幕文件失败: {e}"))?;
+    let text = String::from_utf8(data).map_err(|_| anyhow!("字幕文件不是 UTF-8 编码"))?;
+    parse_subtitle(&text, format)
+}
+
+/// 将字幕内容转换为 BCC，并校验时间轴
+pub fn parse_subtitle(text: &str, format: SubtitleFormat) -> Result<Bcc> {
+    let text = text.trim_start_matches('\u{feff}');
+    let bcc = match format {
+        SubtitleFormat::Srt | SubtitleFormat::Vtt => Bcc::new(parse_cues(text)?),
+        SubtitleFormat::Ass => Bcc::new(parse_ass(text)?),
+        SubtitleFormat::Bcc => {
+            serde_json::from_str(text).map_err(|e| anyhow!("BCC 字幕格式错误: {e}"))?
+        }
+    };
+    validate(bcc)
+}
+
+/// 检查每条字幕的时间，去掉空白字幕并按开始时间排序
+fn validate(mut bcc: Bcc) -> Result<Bcc> {
+    for (index, line) in bcc.body.iter().enumerate() {
+        if !(line.from.is_finite() && line.to.is_finite()) || line.from < 0.0 {
+            bail!("第 {} 条字幕的时间无效", index + 1);
+        }
+        if line.to <= line.from {
+            bail!(
+                "第 {} 条字幕的结束时间 {:.3} 不晚于开始时间 {:.3}",
+                index + 1,
+                line.to,
+                line.from
+            );
+        }
+    }
+
+    bcc.body.retain(|line| !line.content.trim().is_empty());
+    if bcc.body.is_empty() {
+        bail!("字幕文件中没有字幕");
+    }
+    bcc.body.sort_by(|a, b| a.from.total_cmp(&b.from));
+    Ok(bcc)
+}
+
+/// 解析 `时:分:秒.毫秒` 或 `分:秒.毫秒` 格式的时间，小数分隔符可以是 `.` 或 `,`
+fn parse_timestamp(text: &str) -> Option<f64> {
+    let text = text.trim().replace(',', ".");
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        let value: f64 = part.trim().parse().ok()?;
+        if value < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Some(seconds)
+}
+
+/// `<i>`、`<c.color>` 等样式标签及 ASS 的 `{\an8}` 等特效标签
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"</?[a-zA-Z][^>]*>|\{\\[^}]*\}").unwrap());
+
+/// 去掉样式标签
+fn strip_tags(text: &str) -> String {
+    TAG.replace_all(text, "").trim().to_string()
+}
+
+/// 解析 SRT 与 WebVTT：以空行分隔的字幕块，含 `-->` 的行为时间轴
+fn parse_cues(text: &str) -> Result<Vec<BccLine>> {
+    let mut lines = Vec::new();
+    let mut current: Option<(BccLine, Vec<&str>)> = None;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            if let Some((mut cue, content)) = current.take() {
+                cue.content = strip_tags(&content.join("\n"));
+                lines.push(cue);
+            }
+            continue;
+        }
+
+        match &mut current {
+            Some((_, content)) => content.push(line),
+            None => {
+                let Some((start, end)) = line.split_once("-->") else {
+                    // 序号、WEBVTT 头、NOTE 等非字幕内容
+                    continue;
+                };
+                // WebVTT 的时间后可以带有位置等设置
+                let end = end.split_whitespace().next().unwrap_or_default();
+                let (Some(from), Some(to)) = (parse_timestamp(start), parse_timestamp(end)) else {
+                    bail!("第 {} 行的时间格式无效: {}", number + 1, line);
+                };
+                current = Some((
+                    BccLine {
+                        from,
+                        to,
+                        location: BCC_LOCATION_BOTTOM,
+                        content: String::new(),
+                    },
+                    Vec::new(),
+                ));
+            }
+        }
+    }
+
+    if let Some((mut cue, content)) = current {
+        cue.content = strip_tags(&content.join("\n"));
+        lines.push(cue);
+    }
+    Ok(lines)
+}
+
+/// 解析 ASS/SSA 中 `[Events]` 段的 `Dialogue` 行
+fn parse_ass(text: &str) -> Result<Vec<BccLine>> {
+    let mut lines = Vec::new();
+    let mut in_events = false;
+    let mut fields: Vec<String> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+
+        if let Some(format) = line.strip_prefix("Format:") {
+            fields = format
+                .split(',')
+                .map(|field| field.trim().to_ascii_lowercase())
+                .collect();
+            continue;
+        }
+        let Some(dialogue) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+        if fields.is_empty() {
+            bail!("ASS 字幕缺少 Format 行");
+        }
+
+        // 最后一个字段为文本，其中可能含有逗号
+        let values: Vec<&str> = dialogue.splitn(fields.len(), ',').collect();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .position(|field| field == name)
+                .and_then(|index| values.get(index))
+                .map(|value| value.trim())
+        };
+        let (Some(start), Some(end), Some(content)) = (field("start"), field("end"), field("text"))
+        else {
+            bail!("第 {} 行的字幕格式无效", number + 1);
+        };
+        let (Some(from), Some(to)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            bail!("第 {} 行的时间格式无效: {}", number + 1, line);
+        };
+
+        let content = content
+            .replace("\\N", "\n")
+            .replace("\\n", "\n")
+            .replace("\\h", " ");
+        lines.push(BccLine {
+            from,
+            to,
+            location: BCC_LOCATION_BOTTOM,
+            content: strip_tags(&content),
+        });
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn times(bcc: &Bcc) -> Vec<(f64, f64)> {
+        bcc.body.iter().map(|line| (line.from, line.to)).collect()
+    }
+
+    fn contents(bcc: &Bcc) -> Vec<&str> {
+        bcc.body.iter().map(|line| line.content.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_srt_with_comma_or_dot_millis() {
+        let srt = "\u{feff}1\r\n00:00:01,500 --> 00:00:03,000\r\n<i>第一行</i>\r\n第二行\r\n\r\n\
+                   2\r\n00:01:02.250 --> 00:01:04.000\r\n下一条\r\n";
+        let bcc = parse_subtitle(srt, SubtitleFormat::Srt).unwrap();
+        assert_eq!(times(&bcc), [(1.5, 3.0), (62.25, 64.0)]);
+        assert_eq!(contents(&bcc), ["第一行\n第二行", "下一条"]);
+        assert_eq!(bcc.body[0].location, BCC_LOCATION_BOTTOM);
+    }
+
+    #[test]
+    fn parses_vtt_with_cue_settings_and_short_timestamps() {
+        let vtt = "WEBVTT\n\nNOTE 注释\n\nintro\n00:01.000 --> 00:02.500 align:start position:10%\n\
+                   <c.yellow>你好</c>\n\n00:00:03.000 --> 00:00:04.000\n再见\n";
+        let bcc = parse_subtitle(vtt, SubtitleFormat::Vtt).unwrap();
+        assert_eq!(times(&bcc), [(1.0, 2.5), (3.0, 4.0)]);
+        assert_eq!(contents(&bcc), ["你好", "再见"]);
+    }
+
+    #[test]
+    fn parses_ass_by_format_field_order() {
+        let ass = "[Script Info]\nTitle: 测试\n\n[V4+ Styles]\nFormat: Name, Fontname\n\
+                   Style: Default,Arial\n\n[Events]\n\
+                   Format: Layer, End, Start, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+                   Comment: 0,0:00:09.00,0:00:08.00,Default,,0,0,0,,注释不会被导入\n\
+                   Dialogue: 0,0:00:05.00,0:00:02.50,Default,,0,0,0,,{\\an8}你好，世界, 逗号\\N第二行\n\
+                   Dialogue: 0,0:00:01.00,0:00:00.00,Default,,0,0,0,,先出现\n";
+        let bcc = parse_subtitle(ass, SubtitleFormat::Ass).unwrap();
+        // 按开始时间排序
+        assert_eq!(times(&bcc), [(0.0, 1.0), (2.5, 5.0)]);
+        assert_eq!(contents(&bcc), ["先出现", "你好，世界, 逗号\n第二行"]);
+    }
+
+    #[test]
+    fn ass_without_format_is_rejected() {
+        let ass = "[Events]\nDialogue: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,内容\n";
+        assert!(parse_subtitle(ass, SubtitleFormat::Ass).is_err());
+    }
+
+    #[test]
+    fn parses_bcc_and_drops_blank_lines() {
+        let bcc = r#"{"body":[{"from":2.0,"to":3.0,"content":"后"},{"from":0.5,"to":1.0,"content":" "},{"from":0.0,"to":1.0,"content":"前"}]}"#;
+        let bcc = parse_subtitle(bcc, SubtitleFormat::Bcc).unwrap();
+        assert_eq!(contents(&bcc), ["前", "后"]);
+        assert_eq!(bcc.font_size, default_font_size());
+    }
+
+    #[test]
+    fn invalid_timing_is_rejected() {
+        let ends_before_start = "1\n00:00:03,000 --> 00:00:03,000\n内容\n";
+        let error = parse_subtitle(ends_before_start, SubtitleFormat::Srt).unwrap_err();
+        assert!(error.to_string().contains("不晚于开始时间"), "{error}");
+
+        let malformed = "1\n00:00:aa,000 --> 00:00:03,000\n内容\n";
+        let error = parse_subtitle(malformed, SubtitleFormat::Srt).unwrap_err();
+        assert!(error.to_string().contains("第 2 行"), "{error}");
+
+        let negative = r#"{"body":[{"from":-1.0,"to":1.0,"content":"内容"}]}"#;
+        assert!(parse_subtitle(negative, SubtitleFormat::Bcc).is_err());
+    }
+
+    #[test]
+    fn empty_subtitle_is_rejected() {
+        assert!(parse_subtitle("WEBVTT\n\n", SubtitleFormat::Vtt).is_err());
+        assert!(parse_subtitle("{", SubtitleFormat::Bcc).is_err());
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            SubtitleFormat::from_path(Path::new("a.SRT")),
+            Some(SubtitleFormat::Srt)
+        );
+        assert_eq!(
+            SubtitleFormat::from_path(Path::new("a.ssa")),
+            Some(SubtitleFormat::Ass)
+        );
+        assert_eq!(SubtitleFormat::from_path(Path::new("a.txt")), None);
+    }
+}
@@ -0,0 +1,69 @@
+import { defineStore } from 'pinia'
+import { ref } from 'vue'
+import { invoke } from '../utils/invoke'
+import type { ArchiveCache, ArchivePage, TrackedArchive } from '../types/archive'
+
+export const useArchiveStore = defineStore('archive', () => {
+    const archiveCache = ref<Record<number, ArchiveCache>>({})
+    const trackedArchives = ref<TrackedArchive[]>([])
+
+    // 分页获取稿件列表
+    const getArchiveList = async (
+        uid: number,
+        pn: number,
+        ps: number,
+        status?: string
+    ): Promise<ArchivePage> => {
+        try {
+            return await invoke<ArchivePage>('get_archive_list', { uid, pn, ps, status })
+        } catch (error) {
+            console.error('获取稿件列表失败:', error)
+            throw error
+        }
+    }
+
+    // 同步全部稿件到本地缓存
+    const syncArchives = async (uid: number): Promise<ArchiveCache> => {
+        try {
+            const cache = await invoke<ArchiveCache>('sync_archives', { uid })
+            archiveCache.value[uid] = cache
+            return cache
+        } catch (error) {
+            console.error('同步稿件列表失败:', error)
+            throw error
+        }
+    }
+
+    // 读取本地缓存的稿件列表
+    const loadCachedArchives = async (uid: number): Promise<ArchiveCache> => {
+        const cache = await invoke<ArchiveCache>('get_cached_archives', { uid })
+        archiveCache.value[uid] = cache
+        return cache
+    }
+
+    const trackArchive = async (uid: number, aid: number) => {
+        await invoke('track_archive', { uid, aid })
+        await loadTrackedArchives()
+    }
+
+    const untrackArchive = async (aid: number) => {
+        await invoke('untrack_archive', { aid })
+        await loadTrackedArchives()
+    }
+
+    const loadTrackedArchives = async () => {
+        trackedArchives.value = await invoke<TrackedArchive[]>('get_tracked_archives')
+        return trackedArchives.value
+    }
+
+    return {
+        archiveCache,
+        trackedArchives,
+        getArchiveList,
+        syncArchives,
+        loadCachedArchives,
+        trackArchive,
+        untrackArchive,
+        loadTrackedArchives
+    }
+})
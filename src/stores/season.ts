@@ -0,0 +1,93 @@
+import { defineStore } from 'pinia'
+import { invoke } from '../utils/invoke'
+import type {
+    Episode,
+    SeasonDetail,
+    SeasonEdit,
+    SeasonPage,
+    SectionDetail
+} from '../types/season'
+
+export const useSeasonStore = defineStore('season', () => {
+    // 分页获取合集列表
+    const getSeasonPage = async (uid: number, pn: number, ps: number): Promise<SeasonPage> => {
+        try {
+            return await invoke<SeasonPage>('get_season_page', { uid, pn, ps })
+        } catch (error) {
+            console.error('获取合集列表失败:', error)
+            throw error
+        }
+    }
+
+    const getSeasonDetail = async (uid: number, seasonId: number) => {
+        return await invoke<SeasonDetail>('get_season_detail', { uid, seasonId })
+    }
+
+    const createSeason = async (uid: number, season: SeasonEdit) => {
+        return await invoke<number>('create_season', { uid, season })
+    }
+
+    const editSeason = async (uid: number, seasonId: number, season: SeasonEdit) => {
+        return await invoke<boolean>('edit_season', { uid, seasonId, season })
+    }
+
+    const deleteSeason = async (uid: number, seasonId: number) => {
+        return await invoke<boolean>('delete_season', { uid, seasonId })
+    }
+
+    // 上传合集封面，返回封面地址
+    const uploadSeasonCover = async (uid: number, file: string) => {
+        return await invoke<string>('upload_season_cover', { uid, file })
+    }
+
+    const createSection = async (uid: number, seasonId: number, title: string) => {
+        return await invoke<number>('create_section', { uid, seasonId, title })
+    }
+
+    const renameSection = async (uid: number, sectionId: number, title: string) => {
+        return await invoke<boolean>('rename_section', { uid, sectionId, title })
+    }
+
+    // sectionIds 需包含合集中的全部小节
+    const sortSections = async (uid: number, seasonId: number, sectionIds: number[]) => {
+        return await invoke<boolean>('sort_sections', { uid, seasonId, sectionIds })
+    }
+
+    const deleteSection = async (uid: number, sectionId: number) => {
+        return await invoke<boolean>('delete_section', { uid, sectionId })
+    }
+
+    const getSectionEpisodes = async (uid: number, sectionId: number) => {
+        return await invoke<SectionDetail>('get_section_episodes', { uid, sectionId })
+    }
+
+    const addSectionEpisodes = async (uid: number, sectionId: number, episodes: Episode[]) => {
+        return await invoke<boolean>('add_section_episodes', { uid, sectionId, episodes })
+    }
+
+    const removeSectionEpisode = async (uid: number, episodeId: number) => {
+        return await invoke<boolean>('remove_section_episode', { uid, episodeId })
+    }
+
+    // episodeIds 需包含小节中的全部分集
+    const sortSectionEpisodes = async (uid: number, sectionId: number, episodeIds: number[]) => {
+        return await invoke<boolean>('sort_section_episodes', { uid, sectionId, episodeIds })
+    }
+
+    return {
+        getSeasonPage,
+        getSeasonDetail,
+        createSeason,
+        editSeason,
+        deleteSeason,
+        uploadSeasonCover,
+        createSection,
+        renameSection,
+        sortSections,
+        deleteSection,
+        getSectionEpisodes,
+        addSectionEpisodes,
+        removeSectionEpisode,
+        sortSectionEpisodes
+    }
+})
@@ -1,6 +1,6 @@
 import { defineStore } from 'pinia'
 import { ref } from 'vue'
-import { invoke } from '@tauri-apps/api/core'
+import { invoke } from '../utils/invoke'
 import { useUtilsStore } from './utils'
 
 interface UploadTask {
@@ -28,6 +28,7 @@ export const useUploadStore = defineStore('upload', () => {
     const submitRequestQueue: Array<{
         uid: number
         upload: any
+        templateName?: string
         cancelKey?: string
         cancelled: boolean
         resolve: (value: any) => void
@@ -38,6 +39,7 @@ export const useUploadStore = defineStore('upload', () => {
     let activeSubmitRequest: {
         uid: number
         upload: any
+        templateName?: string
         cancelKey?: string
         cancelled: boolean
         resolve: (value: any) => void
@@ -101,7 +103,7 @@ export const useUploadStore = defineStore('upload', () => {
                     }
                 }
 
-                const { uid, upload, resolve, reject } = request
+                const { uid, upload, templateName, resolve, reject } = request
 
                 if (request.cancelled) {
                     reject(createSubmitCancelledError())
@@ -110,7 +112,7 @@ export const useUploadStore = defineStore('upload', () => {
                 }
 
                 try {
-                    const result = await invoke('submit', { uid, form: upload })
+                    const result = await invoke('submit', { uid, form: upload, templateName })
                     resolve(result)
                 } catch (error) {
                     console.error('提交视频失败:', error)
@@ -291,11 +293,16 @@ export const useUploadStore = defineStore('upload', () => {
     }
 
     // 提交视频
-    const submitTemplate = async (uid: number, upload: any, options?: { cancelKey?: string }) => {
+    const submitTemplate = async (
+        uid: number,
+        upload: any,
+        options?: { cancelKey?: string; templateName?: string }
+    ) => {
         return new Promise<any>((resolve, reject) => {
             submitRequestQueue.push({
                 uid,
                 upload,
+                templateName: options?.templateName,
                 cancelKey: options?.cancelKey,
                 cancelled: false,
                 resolve,
@@ -305,6 +312,26 @@ export const useUploadStore = defineStore('upload', () => {
         })
     }
 
+    // 提交前离线校验模板，返回字段级错误
+    const validateTemplate = async (
+        uid: number,
+        templateName: string,
+        template: any,
+        checkVideos: boolean = true
+    ) => {
+        try {
+            return await invoke<Array<{ field: string; message: string }>>('validate_template', {
+                uid,
+                templateName,
+                template,
+                checkVideos
+            })
+        } catch (error) {
+            console.error('校验模板失败:', error)
+            throw error
+        }
+    }
+
     const getUploadTask = (taskId: string) => {
         const task = uploadQueue.value.find(t => t.id === taskId)
         if (task) {
@@ -323,6 +350,7 @@ export const useUploadStore = defineStore('upload', () => {
         getUploadQueue,
         retryUpload,
         submitTemplate,
+        validateTemplate,
         getUploadTask,
         cancelPendingSubmitByKey,
         isSubmitCancelledError
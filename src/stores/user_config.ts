@@ -1,7 +1,10 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
-import { invoke } from '@tauri-apps/api/core'
+import { invoke } from '../utils/invoke'
 import { useAuthStore } from './auth'
+import type { NotificationConfig, NotificationSink } from '../types/notification'
+import type { CoverSpec } from '../types/cover'
+import { DIRECT_PROXY, type ProxyProfile } from '../types/proxy'
 
 // 用户信息接口
 interface User {
@@ -41,6 +44,8 @@ export interface TemplateConfig {
     topic_name?: string
     season_id?: number
     section_id?: number
+    // 投稿后自动加入合集时的分集标题，{title} 为稿件标题
+    season_episode_title?: string | null
     dolby: number
     lossless_music: number
     no_reprint: number
@@ -57,12 +62,26 @@ export interface TemplateConfig {
     staff?: Array<{ title: string; mid: number; is_del: number }>
     state?: number
     state_desc?: string
+    filename_pattern?: string // 文件名匹配正则，分组可在标题等字段中引用
+    part_title_rule?: PartTitleRule // 添加视频时生成分P标题的规则
+    cover_spec?: CoverSpec | null // 叠加文字生成封面的配置
+    parent?: string // 父模板名称
+    inherit_fields?: string[] // 从父模板继承的字段
 }
 
+type PartTitleRule =
+    | { type: 'file_stem' }
+    | { type: 'regex_capture'; pattern: string; group?: string }
+    | { type: 'numbered'; prefix?: string; width?: number }
+    | { type: 'file_mtime'; format?: string }
+    | { type: 'recorder_time'; format?: string }
+
 interface UserConfig {
     user: { name: string; cookie: any }
     line?: string
     proxy?: string
+    api_proxy?: string | null // 接口请求使用的代理名称
+    upload_proxy?: string | null // 分片上传使用的代理名称，未设置时与接口请求相同
     limit: number
     watermark: number
     auto_edit: number
@@ -73,11 +92,14 @@ interface UserConfig {
 
 // 配置根接口
 interface ConfigRoot {
+    schema_version?: number
     max_curr: number
     auto_upload: boolean
     auto_start: boolean
     log_level: string
     config: Record<number, UserConfig> // uid -> 用户配置
+    notifications?: NotificationConfig
+    proxy_profiles?: ProxyProfile[]
 }
 
 // 用户模板组合接口
@@ -252,6 +274,7 @@ export const useUserConfigStore = defineStore('userConfig', () => {
         topic_name: undefined,
         season_id: undefined,
         section_id: undefined,
+        season_episode_title: null,
         dolby: 0,
         lossless_music: 0,
         no_reprint: 0,
@@ -556,6 +579,76 @@ export const useUserConfigStore = defineStore('userConfig', () => {
         }
     }
 
+    // 导出模板到 JSON 文件
+    const exportUserTemplates = async (
+        userUid: number,
+        templateNames: string[],
+        includeCover: boolean,
+        path: string
+    ) => {
+        try {
+            return await invoke<number>('export_templates', {
+                uid: userUid,
+                templateNames,
+                includeCover,
+                path
+            })
+        } catch (err: any) {
+            throw new Error(err || '导出模板失败')
+        }
+    }
+
+    // 从 JSON 文件导入模板
+    const importUserTemplates = async (userUid: number, path: string) => {
+        try {
+            const imported = await invoke<string[]>('import_templates', { uid: userUid, path })
+            await loadConfig()
+            await saveConfig()
+            return imported
+        } catch (err: any) {
+            throw new Error(err || '导入模板失败')
+        }
+    }
+
+    // 复制模板到其他账号
+    const cloneUserTemplate = async (
+        fromUid: number,
+        toUid: number,
+        templateName: string,
+        newName?: string
+    ) => {
+        try {
+            const cloned = await invoke<string>('clone_user_template', {
+                fromUid,
+                toUid,
+                templateName,
+                newName
+            })
+            await loadConfig()
+            await saveConfig()
+            return cloned
+        } catch (err: any) {
+            throw new Error(err || '复制模板失败')
+        }
+    }
+
+    // 沿继承链解析模板的最终配置，template 为尚未保存的修改
+    const resolveUserTemplate = async (
+        uid: number,
+        templateName: string,
+        template?: TemplateConfig
+    ) => {
+        try {
+            return await invoke<TemplateConfig>('resolve_user_template', {
+                uid,
+                templateName,
+                template: template ?? null
+            })
+        } catch (err: any) {
+            throw new Error(err || '解析模板失败')
+        }
+    }
+
     const reorderUserTemplates = async (userUid: number, templateOrder: string[]) => {
         return persistTemplateOrder(userUid, templateOrder)
     }
@@ -680,6 +773,81 @@ export const useUserConfigStore = defineStore('userConfig', () => {
         return true
     }
 
+    const updateNotificationConfig = async (notifications: NotificationConfig) => {
+        if (!configRoot.value) {
+            throw new Error('配置未加载')
+        }
+
+        configRoot.value.notifications = notifications
+
+        try {
+            await invoke('save_notification_config', { notifications })
+            // 保存配置
+            await saveConfig()
+        } catch (err) {
+            throw new Error('保存配置失败: ' + err)
+        }
+
+        return true
+    }
+
+    const updateProxyProfiles = async (profiles: ProxyProfile[]) => {
+        if (!configRoot.value) {
+            throw new Error('配置未加载')
+        }
+
+        try {
+            await invoke('save_proxy_profiles', { profiles })
+            configRoot.value.proxy_profiles = profiles
+
+            // 与后端一致，已删除的代理从各账号的选择中移除
+            const names = new Set(profiles.map(profile => profile.name))
+            const isMissing = (name?: string | null) =>
+                !!name && name !== DIRECT_PROXY && !names.has(name)
+            for (const userConfig of Object.values(configRoot.value.config)) {
+                if (isMissing(userConfig.api_proxy)) {
+                    userConfig.api_proxy = null
+                }
+                if (isMissing(userConfig.upload_proxy)) {
+                    userConfig.upload_proxy = null
+                }
+            }
+
+            await saveConfig()
+        } catch (err) {
+            throw new Error('保存代理配置失败: ' + err)
+        }
+
+        return true
+    }
+
+    // 选择账号的接口代理与上传代理，为空时使用默认设置
+    const updateUserProxy = async (
+        userUid: number,
+        apiProxy: string | null,
+        uploadProxy: string | null
+    ) => {
+        const userConfig = configRoot.value?.config[userUid]
+        if (!userConfig) {
+            throw new Error('用户配置不存在')
+        }
+
+        try {
+            await invoke('save_user_proxy', { uid: userUid, apiProxy, uploadProxy })
+            userConfig.api_proxy = apiProxy
+            userConfig.upload_proxy = uploadProxy
+            await saveConfig()
+        } catch (err) {
+            throw new Error('保存代理设置失败: ' + err)
+        }
+
+        return true
+    }
+
+    const testNotificationSink = async (sink: NotificationSink) => {
+        return await invoke<boolean>('test_notification_sink', { sink })
+    }
+
     return {
         // 状态
         configRoot,
@@ -705,10 +873,18 @@ export const useUserConfigStore = defineStore('userConfig', () => {
         updateUserTemplate,
         duplicateUserTemplate,
         renameUserTemplate,
+        exportUserTemplates,
+        importUserTemplates,
+        cloneUserTemplate,
+        resolveUserTemplate,
         reorderUserTemplates,
         sortUserTemplates,
         updateUserConfig,
         updateGlobalConfig,
+        updateNotificationConfig,
+        testNotificationSink,
+        updateProxyProfiles,
+        updateUserProxy,
         createDefaultTemplate
     }
 })
@@ -1,8 +1,21 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
-import { invoke } from '@tauri-apps/api/core'
+import { invoke } from '../utils/invoke'
 import { ElMessage } from 'element-plus'
-import type { MentionUserGroup } from '../types/mention'
+import type { MentionUserGroup, ParsedDesc } from '../types/mention'
+import type { CoverCandidate, CoverOptions, CoverPreview } from '../types/cover'
+import type { CategoryMatch, MetadataStatus } from '../types/metadata'
+import type { TagSuggestion } from '../types/tag'
+import type { TemplateConfig } from './user_config'
+
+export interface EndpointMetrics {
+    requests: number
+    failures: number
+    retries: number
+    total_ms: number
+    max_ms: number
+    last_error: string | null
+}
 
 export const useUtilsStore = defineStore('template', () => {
     const archieve_pre = ref<any>(null)
@@ -75,6 +88,24 @@ export const useUtilsStore = defineStore('template', () => {
         }
     }
 
+    // 按自然顺序排序视频并生成分P标题
+    const generatePartTitles = async (
+        rule: any,
+        files: string[],
+        startIndex: number
+    ): Promise<Array<{ path: string; title: string }>> => {
+        try {
+            return await invoke<Array<{ path: string; title: string }>>('generate_part_titles', {
+                rule: rule ?? null,
+                files,
+                startIndex
+            })
+        } catch (error) {
+            console.error('生成分P标题失败:', error)
+            throw error
+        }
+    }
+
     const getAvatarCacheDir = async () => {
         try {
             const cacheDir = await invoke<string>('get_avatar_cache_dir')
@@ -118,6 +149,43 @@ export const useUtilsStore = defineStore('template', () => {
         }
     }
 
+    // 忽略缓存有效期，重新获取分区、话题等元数据
+    const refreshMetadata = async (uid: number) => {
+        try {
+            const status = await invoke<MetadataStatus[]>('refresh_metadata', { uid })
+            await initArchievePre(uid)
+            await initTopicList(uid)
+            return status
+        } catch (error) {
+            console.error('刷新分区与话题失败:', error)
+            throw error
+        }
+    }
+
+    const searchCategories = async (uid: number, keyword: string) => {
+        try {
+            return await invoke<CategoryMatch[]>('search_categories', { uid, keyword })
+        } catch (error) {
+            console.error('搜索分区失败:', error)
+            throw error
+        }
+    }
+
+    const suggestTags = async (
+        uid: number,
+        title: string,
+        tid: number,
+        desc: string,
+        current: string[]
+    ) => {
+        try {
+            return await invoke<TagSuggestion[]>('suggest_tags', { uid, title, tid, desc, current })
+        } catch (error) {
+            console.error('获取推荐标签失败:', error)
+            throw error
+        }
+    }
+
     const searchTopics = async (uid: number, query: string) => {
         try {
             const results = await invoke('search_topics', { uid, query })
@@ -142,6 +210,15 @@ export const useUtilsStore = defineStore('template', () => {
         }
     }
 
+    const parseDescMentions = async (uid: number, desc: string) => {
+        try {
+            return await invoke<ParsedDesc>('parse_desc_mentions', { uid, desc })
+        } catch (error) {
+            console.error('识别简介@用户失败:', error)
+            throw error
+        }
+    }
+
     const getSeasonList = async (uid: number) => {
         hasSeason.value = false
 
@@ -157,13 +234,22 @@ export const useUtilsStore = defineStore('template', () => {
         return hasSeason.value
     }
 
-    const uploadCover = async (uid: number, file: string) => {
+    const previewCover = async (file: string, options?: CoverOptions) => {
+        try {
+            return await invoke<CoverPreview>('preview_cover', { file, options })
+        } catch (error) {
+            console.error('封面预览失败:', error)
+            throw error
+        }
+    }
+
+    const uploadCover = async (uid: number, file: string, options?: CoverOptions) => {
         if (!file) {
             return undefined
         }
         try {
             console.log('上传文件:', file)
-            const cover_url: string = await invoke('upload_cover', { uid, file })
+            const cover_url: string = await invoke('upload_cover', { uid, file, options })
             console.log('上传封面成功:', cover_url)
             return cover_url
         } catch (error) {
@@ -172,6 +258,76 @@ export const useUtilsStore = defineStore('template', () => {
         }
     }
 
+    const extractCoverCandidates = async (video: any, count?: number) => {
+        try {
+            return await invoke<CoverCandidate[]>('extract_cover_candidates', { video, count })
+        } catch (error) {
+            console.error('截取候选封面失败:', error)
+            throw error
+        }
+    }
+
+    const uploadCoverFrame = async (
+        uid: number,
+        video: any,
+        timestamp: number,
+        options?: CoverOptions
+    ) => {
+        try {
+            const cover_url = await invoke<string>('upload_cover_frame', {
+                uid,
+                video,
+                timestamp,
+                options
+            })
+            console.log('上传封面成功:', cover_url)
+            return cover_url
+        } catch (error) {
+            console.error('上传视频截图封面失败:', error)
+            throw error
+        }
+    }
+
+    const previewTemplateCover = async (
+        uid: number,
+        templateName: string,
+        template: TemplateConfig,
+        options?: CoverOptions
+    ) => {
+        try {
+            return await invoke<CoverPreview>('preview_template_cover', {
+                uid,
+                templateName,
+                template,
+                options
+            })
+        } catch (error) {
+            console.error('生成封面预览失败:', error)
+            throw error
+        }
+    }
+
+    const uploadTemplateCover = async (
+        uid: number,
+        templateName: string,
+        template: TemplateConfig,
+        options?: CoverOptions
+    ) => {
+        try {
+            const cover_url = await invoke<string>('upload_template_cover', {
+                uid,
+                templateName,
+                template,
+                options
+            })
+            console.log('上传封面成功:', cover_url)
+            return cover_url
+        } catch (error) {
+            console.error('生成并上传封面失败:', error)
+            throw error
+        }
+    }
+
     const getVideoDetail = async (uid: number, videoId: string) => {
         try {
             const detail = await invoke('get_video_detail', { uid, videoId })
@@ -217,6 +373,16 @@ export const useUtilsStore = defineStore('template', () => {
         }
     }
 
+    // 获取账号各接口的请求统计
+    const getApiMetrics = async (uid: number): Promise<Record<string, EndpointMetrics>> => {
+        try {
+            return await invoke<Record<string, EndpointMetrics>>('get_api_metrics', { uid })
+        } catch (error) {
+            console.error('获取接口统计失败:', error)
+            throw error
+        }
+    }
+
     // 消息提示帮助函数
     const showMessage = (
         message: string,
@@ -243,6 +409,55 @@ export const useUtilsStore = defineStore('template', () => {
         }
     }
 
+    // 导出应用数据备份
+    const exportBackup = async (includeCredentials: boolean): Promise<string> => {
+        try {
+            const result = await invoke<string>('export_backup', { includeCredentials })
+            showMessage('备份导出成功', 'success')
+            return result
+        } catch (error) {
+            console.error('导出备份失败:', error)
+            showMessage(`导出备份失败: ${error}`, 'error')
+            throw error
+        }
+    }
+
+    // 预览备份与当前配置的差异
+    const inspectBackup = async (path: string): Promise<any> => {
+        try {
+            return await invoke('inspect_backup', { path })
+        } catch (error) {
+            console.error('读取备份失败:', error)
+            showMessage(`读取备份失败: ${error}`, 'error')
+            throw error
+        }
+    }
+
+    // 从备份合并用户与模板
+    const importBackup = async (path: string): Promise<any> => {
+        try {
+            const summary = await invoke('import_backup', { path })
+            showMessage('备份导入成功', 'success')
+            return summary
+        } catch (error) {
+            console.error('导入备份失败:', error)
+            showMessage(`导入备份失败: ${error}`, 'error')
+            throw error
+        }
+    }
+
+    // 提示启动时加载配置产生的警告
+    const checkConfigWarning = async () => {
+        try {
+            const warning = await invoke<string | null>('get_config_warning')
+            if (warning) {
+                showMessage(warning, 'warning')
+            }
+        } catch (error) {
+            console.error('获取配置警告失败:', error)
+        }
+    }
+
     // 检查更新
     const checkUpdate = async (): Promise<string | null> => {
         try {
@@ -277,19 +492,34 @@ export const useUtilsStore = defineStore('template', () => {
         getFileSize,
         getAvatarCacheDir,
         readDirRecursive,
+        generatePartTitles,
+        previewCover,
         uploadCover,
+        extractCoverCandidates,
+        uploadCoverFrame,
+        previewTemplateCover,
+        uploadTemplateCover,
         downloadCover,
         initArchievePre,
         initTopicList,
+        refreshMetadata,
+        searchCategories,
+        suggestTags,
         searchTopics,
         searchMention,
+        parseDescMentions,
         getVideoDetail,
         hasSeason,
         getSeasonList,
         getVideoSeason,
         switchSeason,
+        getApiMetrics,
         showMessage,
         exportLogs,
+        exportBackup,
+        inspectBackup,
+        importBackup,
+        checkConfigWarning,
         checkUpdate,
         log
     }
@@ -0,0 +1,50 @@
+export type ArchiveStage = 'transcoding' | 'pending' | 'published' | 'rejected' | 'unknown'
+
+export interface ArchiveSummary {
+    aid: number
+    bvid: string
+    title: string
+    cover: string
+    state: number
+    state_desc: string
+    stage: ArchiveStage
+    reject_reasons: string[]
+    ctime: number
+    ptime: number
+    duration: number
+    view: number
+    danmaku: number
+    reply: number
+    like: number
+    coin: number
+    favorite: number
+    share: number
+}
+
+export interface ArchivePage {
+    archives: ArchiveSummary[]
+    pn: number
+    ps: number
+    count: number
+}
+
+export interface ArchiveCache {
+    uid: number
+    updated_at: number
+    archives: ArchiveSummary[]
+}
+
+export interface TrackedArchive {
+    uid: number
+    aid: number
+    state: number | null
+    stage: ArchiveStage
+    since: number
+}
+
+export interface ArchiveStateEvent {
+    uid: number
+    previous_state: number | null
+    previous_stage: ArchiveStage
+    archive: ArchiveSummary
+}
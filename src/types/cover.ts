@@ -0,0 +1,43 @@
+export type CoverAspect = 'wide' | 'standard'
+
+export type CoverFit = 'crop' | 'pad'
+
+export type TextAlign = 'left' | 'center' | 'right'
+
+export interface TextLayer {
+    text: string // 支持与标题相同的模板变量
+    font: string // TTF/OTF 字体文件路径
+    size: number
+    color: string // #RRGGBB 或 #RRGGBBAA
+    stroke_color?: string | null
+    stroke_width: number
+    x: number
+    y: number // 第一行文字顶部
+    align: TextAlign
+}
+
+export interface CoverSpec {
+    base_image: string
+    layers: TextLayer[]
+}
+
+export interface CoverOptions {
+    aspect: CoverAspect
+    fit: CoverFit
+}
+
+export interface CoverPreview {
+    data_url: string
+    size: number
+    width: number
+    height: number
+    original_width: number
+    original_height: number
+    original_format: string
+    warnings: string[]
+}
+
+export interface CoverCandidate {
+    timestamp: number
+    thumbnail: string
+}
@@ -1,3 +1,5 @@
+import type { DescV2Item } from '../stores/user_config'
+
 export interface MentionUserItem {
     face: string
     fans: number
@@ -22,3 +24,9 @@ export interface MentionOption {
     groupName: string
     showGroupLabel: boolean
 }
+
+export interface ParsedDesc {
+    desc: string
+    desc_v2: DescV2Item[] | null
+    unresolved: string[]
+}
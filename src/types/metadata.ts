@@ -0,0 +1,19 @@
+export type MetadataKind = 'archive_pre' | 'type_list_v2' | 'topics' | 'activities'
+
+export interface MetadataStatus {
+    kind: MetadataKind
+    fetched_at: number | null
+    checked_at: number | null
+    expires_at: number | null
+    etag: string | null
+    fresh: boolean
+}
+
+export interface CategoryMatch {
+    id: number
+    name: string
+    desc: string
+    parent_id: number | null
+    parent_name: string | null
+    v2: boolean
+}
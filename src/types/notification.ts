@@ -0,0 +1,28 @@
+export type NotifyEvent =
+    | 'upload_finished'
+    | 'upload_failed'
+    | 'submit_succeeded'
+    | 'submit_failed'
+    | 'archive_published'
+    | 'archive_rejected'
+
+export type SinkKind =
+    | { type: 'desktop' }
+    | { type: 'webhook'; url: string; body_template?: string | null; headers?: Record<string, string> }
+    | { type: 'server_chan'; send_key: string }
+    | { type: 'bark'; server?: string; device_key: string }
+    | { type: 'telegram'; bot_token: string; chat_id: string }
+    | { type: 'ding_talk'; webhook: string; secret?: string | null }
+    | { type: 'feishu'; webhook: string; secret?: string | null }
+
+export type NotificationSink = {
+    name: string
+    enabled: boolean
+    // 为空时接收全部事件
+    events: NotifyEvent[]
+} & SinkKind
+
+export interface NotificationConfig {
+    enabled: boolean
+    sinks: NotificationSink[]
+}
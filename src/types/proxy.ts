@@ -0,0 +1,13 @@
+export type ProxyScheme = 'http' | 'https' | 'socks5'
+
+export interface ProxyProfile {
+    name: string
+    scheme: ProxyScheme
+    host: string
+    port: number
+    username?: string | null
+    password?: string | null
+}
+
+// 表示不使用代理的保留名称
+export const DIRECT_PROXY = 'direct'
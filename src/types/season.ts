@@ -0,0 +1,75 @@
+export interface Season {
+    id: number
+    title: string
+    desc: string
+    cover: string
+    is_end: number
+    state: number
+    ep_num: number
+    ctime: number
+    mtime: number
+}
+
+export interface Section {
+    id: number
+    season_id: number
+    title: string
+    type: number
+    order: number
+    ep_count: number
+    state: number
+}
+
+// id 为分集 ID，删除与排序时使用
+export interface SectionEpisode {
+    id: number
+    title: string
+    aid: number
+    bvid: string
+    cid: number
+    season_id: number
+    section_id: number
+    order: number
+    archive_title: string
+    state: number
+}
+
+export interface SeasonDetail {
+    season: Season
+    sections: Section[]
+}
+
+export interface SeasonPage {
+    seasons: SeasonDetail[]
+    pn: number
+    ps: number
+    total: number
+}
+
+export interface SectionDetail {
+    section: Section
+    episodes: SectionEpisode[]
+}
+
+export interface SeasonEdit {
+    title: string
+    desc?: string
+    cover?: string
+    is_end?: boolean
+}
+
+export interface Episode {
+    title: string
+    aid: number
+    cid: number
+}
+
+export interface SeasonJoinEvent {
+    uid: number
+    aid: number
+    season_id: number
+    section_id: number | null
+    title: string
+    success: boolean
+    error: string | null
+}
@@ -0,0 +1,12 @@
+export interface PartSubtitleResult {
+    index: number
+    path: string
+    error: string | null
+    skipped: boolean // 字幕与已上传的内容相同，未重复提交
+}
+
+export interface SubtitleUploadEvent {
+    uid: number
+    aid: number
+    results: PartSubtitleResult[]
+}
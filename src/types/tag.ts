@@ -0,0 +1,7 @@
+export type TagSource = 'recommend' | 'local'
+
+export interface TagSuggestion {
+    tag: string
+    source: TagSource
+    uses: number
+}
@@ -0,0 +1,73 @@
+import { invoke as tauriInvoke, type InvokeArgs } from '@tauri-apps/api/core'
+
+// 后端 AppError 的分类，与 src-tauri/src/error.rs 中的 ErrorCategory 对应
+export type ErrorCategory =
+    | 'auth_expired'
+    | 'rate_limited'
+    | 'captcha_required'
+    | 'validation'
+    | 'permission'
+    | 'not_found'
+    | 'network'
+    | 'server'
+    | 'unknown'
+    | 'config'
+    | 'internal'
+
+export interface FieldError {
+    field: string
+    message: string
+}
+
+interface AppErrorPayload {
+    code: string
+    category: ErrorCategory
+    message: string
+    bili_code?: number
+    bili_message?: string
+    fields?: FieldError[]
+}
+
+// 后端命令返回的结构化错误，toString 返回提示文本，兼容原有的字符串拼接
+export class AppError extends Error {
+    code: string
+    category: ErrorCategory
+    biliCode?: number
+    biliMessage?: string
+    fields: FieldError[]
+
+    constructor(payload: AppErrorPayload) {
+        super(payload.message)
+        this.name = 'AppError'
+        this.code = payload.code
+        this.category = payload.category
+        this.biliCode = payload.bili_code
+        this.biliMessage = payload.bili_message
+        this.fields = payload.fields || []
+    }
+
+    toString() {
+        return this.message
+    }
+}
+
+const isAppErrorPayload = (value: unknown): value is AppErrorPayload => {
+    return (
+        typeof value === 'object' &&
+        value !== null &&
+        typeof Reflect.get(value, 'message') === 'string' &&
+        typeof Reflect.get(value, 'category') === 'string'
+    )
+}
+
+// 调用后端命令，将结构化错误转换为 AppError
+export const invoke = async <T>(cmd: string, args?: InvokeArgs): Promise<T> => {
+    try {
+        return await tauriInvoke<T>(cmd, args)
+    } catch (error) {
+        if (isAppErrorPayload(error)) {
+            throw new AppError(error)
+        }
+        throw error
+    }
+}